@@ -209,6 +209,12 @@
           dir = "cometbls";
           client-type = "cometbls";
         }
+        {
+          name = "cometbls-mock-zkp-verifier";
+          dir = "cometbls";
+          client-type = "cometbls";
+          features = [ "mock-zkp-verifier" ];
+        }
         {
           name = "ethereum";
           dir = "ethereum";
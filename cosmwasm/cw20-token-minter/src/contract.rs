@@ -7,8 +7,8 @@ use cosmwasm_std::{
 };
 use cw20::{Cw20QueryMsg, TokenInfoResponse};
 use ucs03_zkgm_token_minter_api::{
-    ExecuteMsg, LocalTokenMsg, MetadataResponse, PredictWrappedTokenResponse, QueryMsg,
-    WrappedTokenMsg, DISPATCH_EVENT, DISPATCH_EVENT_ATTR,
+    cw721, ExecuteMsg, LocalTokenMsg, MetadataResponse, NonFungibleTokenMsg,
+    PredictWrappedTokenResponse, QueryMsg, WrappedTokenMsg, DISPATCH_EVENT, DISPATCH_EVENT_ATTR,
 };
 use unionlabs::{ethereum::keccak256, primitives::H256};
 
@@ -22,6 +22,8 @@ pub enum TokenMinterInitMsg {
     Cw20 {
         cw20_base_code_id: u64,
         dummy_code_id: u64,
+        #[serde(default)]
+        cw721_base_code_id: Option<u64>,
     },
 }
 
@@ -33,6 +35,7 @@ pub fn instantiate(
     TokenMinterInitMsg::Cw20 {
         cw20_base_code_id,
         dummy_code_id,
+        cw721_base_code_id,
     }: TokenMinterInitMsg,
 ) -> StdResult<Response> {
     CONFIG.save(
@@ -41,16 +44,29 @@ pub fn instantiate(
             admin: info.sender,
             cw20_base_code_id,
             dummy_code_id,
+            cw721_base_code_id,
         },
     )?;
     Ok(Response::default())
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// Set the code id of the cw721 implementation used for wrapped nft collections.
+    #[serde(default)]
+    pub cw721_base_code_id: Option<u64>,
+}
 
 #[entry_point]
-pub fn migrate(_: DepsMut, _: Env, _: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _: Env, msg: MigrateMsg) -> StdResult<Response> {
+    if let Some(cw721_base_code_id) = msg.cw721_base_code_id {
+        CONFIG.update(deps.storage, |config| {
+            StdResult::Ok(Config {
+                cw721_base_code_id: Some(cw721_base_code_id),
+                ..config
+            })
+        })?;
+    }
     Ok(Response::new())
 }
 
@@ -213,11 +229,122 @@ pub fn execute(
                 }
             }
         },
+        ExecuteMsg::NonFungible(msg) => match msg {
+            NonFungibleTokenMsg::CreateCollection {
+                metadata,
+                path,
+                channel,
+                token,
+            } => {
+                let code_id = config
+                    .cw721_base_code_id
+                    .ok_or(Error::NonFungibleUnsupported)?;
+                let salt = calculate_salt(
+                    U256::from_be_bytes::<{ U256::BYTES }>(
+                        path.as_slice().try_into().expect("correctly encoded; qed"),
+                    ),
+                    channel,
+                    token.to_vec(),
+                );
+                Response::new().add_message(WasmMsg::Instantiate2 {
+                    admin: Some(env.contract.address.to_string()),
+                    code_id,
+                    label: format!("{}/{}", channel, Binary::new(salt.clone()).to_base64()),
+                    msg: to_json_binary(&cw721::InstantiateMsg {
+                        name: restrict_name(metadata.name),
+                        symbol: restrict_symbol(metadata.symbol),
+                        minter: env.contract.address.to_string(),
+                    })?,
+                    funds: vec![],
+                    salt: Binary::new(salt),
+                })
+            }
+            NonFungibleTokenMsg::Mint {
+                collection,
+                token_id,
+                token_uri,
+                owner,
+            } => Response::new().add_message(wasm_execute(
+                collection,
+                &cw721::ExecuteMsg::Mint {
+                    token_id,
+                    owner,
+                    token_uri,
+                    extension: cosmwasm_std::Empty {},
+                },
+                vec![],
+            )?),
+            NonFungibleTokenMsg::Burn {
+                from,
+                collection,
+                token_id,
+            } => {
+                ensure_nft_owner(deps.as_ref(), &from, &collection, &token_id)?;
+                // The owner approved zkgm, which executes the burn.
+                let msg = wasm_execute(collection, &cw721::ExecuteMsg::Burn { token_id }, vec![])?;
+                Response::new().add_event(
+                    Event::new(DISPATCH_EVENT)
+                        .add_attribute(DISPATCH_EVENT_ATTR, to_json_string(&vec![msg])?),
+                )
+            }
+            NonFungibleTokenMsg::Escrow {
+                from,
+                collection,
+                token_id,
+                recipient,
+            } => {
+                ensure_nft_owner(deps.as_ref(), &from, &collection, &token_id)?;
+                // The owner approved zkgm, which executes the transfer.
+                let msg = wasm_execute(
+                    collection,
+                    &cw721::ExecuteMsg::TransferNft {
+                        recipient,
+                        token_id,
+                    },
+                    vec![],
+                )?;
+                Response::new().add_event(
+                    Event::new(DISPATCH_EVENT)
+                        .add_attribute(DISPATCH_EVENT_ATTR, to_json_string(&vec![msg])?),
+                )
+            }
+            NonFungibleTokenMsg::Unescrow {
+                collection,
+                token_id,
+                recipient,
+            } => Response::new().add_message(wasm_execute(
+                collection,
+                &cw721::ExecuteMsg::TransferNft {
+                    recipient,
+                    token_id,
+                },
+                vec![],
+            )?),
+        },
     };
 
     Ok(response)
 }
 
+/// zkgm executes the transfer or burn with its own approval, which the owner may have given for all of its nfts.
+fn ensure_nft_owner(deps: Deps, from: &str, collection: &str, token_id: &str) -> Result<(), Error> {
+    let cw721::OwnerOfResponse { owner } = deps.querier.query_wasm_smart(
+        collection,
+        &cw721::QueryMsg::OwnerOf {
+            token_id: token_id.to_owned(),
+            include_expired: None,
+        },
+    )?;
+    if owner != from {
+        return Err(Error::NotNftOwner {
+            from: from.to_owned(),
+            collection: collection.to_owned(),
+            token_id: token_id.to_owned(),
+        });
+    }
+    Ok(())
+}
+
 fn is_native_token(deps: Deps, token: &str) -> bool {
     match deps.storage.get(
         &0x3_u8
@@ -266,6 +393,30 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, Error> {
                 wrapped_token: deps.api.addr_humanize(&token_addr)?.to_string(),
             })?)
         }
+        QueryMsg::PredictWrappedCollection {
+            path,
+            channel,
+            token,
+        } => {
+            let code_id = CONFIG
+                .load(deps.storage)?
+                .cw721_base_code_id
+                .ok_or(Error::NonFungibleUnsupported)?;
+            let code_hash = get_code_hash(deps, code_id)?;
+            let collection_addr = instantiate2_address(
+                &code_hash.into_bytes(),
+                &deps.api.addr_canonicalize(env.contract.address.as_str())?,
+                &calculate_salt(
+                    path.parse::<U256>().map_err(Error::U256Parse)?,
+                    channel,
+                    token.to_vec(),
+                ),
+            )?;
+
+            Ok(to_json_binary(&PredictWrappedTokenResponse {
+                wrapped_token: deps.api.addr_humanize(&collection_addr)?.to_string(),
+            })?)
+        }
         QueryMsg::Metadata { denom } => match query_token_info(deps, &denom) {
             Ok(TokenInfoResponse {
                 name,
@@ -36,4 +36,14 @@ pub enum Error {
 
     #[error("{0:?}")]
     U256Parse(ParseError),
+
+    #[error("non fungible tokens are not supported, the cw721 code id is not configured")]
+    NonFungibleUnsupported,
+
+    #[error("nft {token_id} of collection {collection} is not owned by {from}")]
+    NotNftOwner {
+        from: String,
+        collection: String,
+        token_id: String,
+    },
 }
@@ -7,6 +7,8 @@ pub struct Config {
     pub admin: Addr,
     pub dummy_code_id: u64,
     pub cw20_base_code_id: u64,
+    #[serde(default)]
+    pub cw721_base_code_id: Option<u64>,
 }
 
 pub const CONFIG: Item<Config> = Item::new("conf");
@@ -499,6 +499,7 @@ async fn do_main() -> Result<()> {
                             TokenMinterInitMsg::Cw20 {
                                 cw20_base_code_id: code_id,
                                 dummy_code_id: bytecode_base_code_id,
+                                cw721_base_code_id: None,
                             }
                         }
                         TokenMinterConfig::Native => TokenMinterInitMsg::Native,
@@ -0,0 +1,11 @@
+use cosmwasm_schema::write_api;
+use ucs03_zkgm::msg::{ExecuteMsg, InitMsg, MigrateMsg, QueryMsg};
+
+fn main() {
+    write_api! {
+        instantiate: InitMsg,
+        execute: ExecuteMsg,
+        query: QueryMsg,
+        migrate: MigrateMsg,
+    }
+}
@@ -7,6 +7,7 @@ pub const OP_FORWARD: u8 = 0x00;
 pub const OP_MULTIPLEX: u8 = 0x01;
 pub const OP_BATCH: u8 = 0x02;
 pub const OP_FUNGIBLE_ASSET_ORDER: u8 = 0x03;
+pub const OP_NON_FUNGIBLE_ASSET_ORDER: u8 = 0x04;
 
 pub const ACK_ERR_ONLY_MAKER: &[u8] = &[0xDE, 0xAD, 0xC0, 0xDE];
 
@@ -76,6 +77,19 @@ alloy::sol! {
       uint256 quote_amount;
   }
 
+  #[derive(Debug, PartialEq)]
+  struct NonFungibleAssetOrder {
+      bytes sender;
+      bytes receiver;
+      bytes base_token;
+      string base_token_name;
+      string base_token_symbol;
+      uint256 base_token_path;
+      bytes quote_token;
+      string token_id;
+      string token_uri;
+  }
+
   #[derive(Debug)]
   struct Ack {
       uint256 tag;
@@ -86,6 +100,10 @@ alloy::sol! {
       bytes[] acknowledgements;
   }
 
+  /// For a market maker fill, `market_maker` is the address (on the source chain) the market maker
+  /// is paid the base asset at. For a protocol fill, it is the payee of the recv relayer, used to
+  /// pay the relayer fee escrowed on the source chain: the `relayer_msg` of the relayer if not
+  /// empty, otherwise the address of the relayer on the destination chain.
   #[derive(Debug)]
   struct FungibleAssetOrderAck {
       uint256 fill_type;
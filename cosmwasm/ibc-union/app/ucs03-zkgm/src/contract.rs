@@ -4,17 +4,19 @@ use alloy::{primitives::U256, sol_types::SolValue};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    instantiate2_address, to_json_binary, to_json_string, wasm_execute, Addr, Binary,
-    CodeInfoResponse, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QueryRequest, Reply,
-    Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128, Uint256, WasmMsg,
+    instantiate2_address, to_json_binary, to_json_string, wasm_execute, Addr, BankMsg, Binary,
+    CodeInfoResponse, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order, QueryRequest,
+    Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult, Uint128, Uint256, WasmMsg,
 };
+use cw_storage_plus::Bound;
 use ibc_union_msg::{
     module::IbcUnionMsg,
     msg::{MsgSendPacket, MsgWriteAcknowledgement},
 };
-use ibc_union_spec::types::Packet;
+use ibc_union_spec::{path::COMMITMENT_MAGIC, types::Packet};
 use ucs03_zkgm_token_minter_api::{
-    LocalTokenMsg, Metadata, MetadataResponse, WrappedTokenMsg, DISPATCH_EVENT, DISPATCH_EVENT_ATTR,
+    cw721, CollectionMetadata, LocalTokenMsg, Metadata, MetadataResponse, NonFungibleTokenMsg,
+    WrappedTokenMsg, DISPATCH_EVENT, DISPATCH_EVENT_ATTR,
 };
 use unionlabs::{
     ethereum::keccak256,
@@ -25,13 +27,19 @@ use unionlabs_cosmwasm_upgradable::UpgradeMsg;
 use crate::{
     com::{
         decode_fungible_asset, Ack, Batch, BatchAck, FungibleAssetOrder, FungibleAssetOrderAck,
-        Instruction, Multiplex, ZkgmPacket, ACK_ERR_ONLY_MAKER, FILL_TYPE_MARKETMAKER,
-        FILL_TYPE_PROTOCOL, INSTR_VERSION_0, INSTR_VERSION_1, OP_BATCH, OP_FUNGIBLE_ASSET_ORDER,
-        OP_MULTIPLEX, TAG_ACK_FAILURE, TAG_ACK_SUCCESS,
+        Instruction, Multiplex, NonFungibleAssetOrder, ZkgmPacket, ACK_ERR_ONLY_MAKER,
+        FILL_TYPE_MARKETMAKER, FILL_TYPE_PROTOCOL, INSTR_VERSION_0, INSTR_VERSION_1, OP_BATCH,
+        OP_FUNGIBLE_ASSET_ORDER, OP_MULTIPLEX, OP_NON_FUNGIBLE_ASSET_ORDER, TAG_ACK_FAILURE,
+        TAG_ACK_SUCCESS,
+    },
+    msg::{
+        ChannelBalance, ChannelBalancesResponse, EurekaMsg, ExecuteMsg, InitMsg,
+        PredictWrappedTokenResponse, QueryMsg, RateLimitFlow, RateLimitResponse,
+        TokenOriginResponse,
     },
-    msg::{EurekaMsg, ExecuteMsg, InitMsg, PredictWrappedTokenResponse, QueryMsg},
     state::{
-        CHANNEL_BALANCE, CONFIG, EXECUTING_PACKET, EXECUTION_ACK, HASH_TO_FOREIGN_TOKEN,
+        RelayerFeeEscrow, CHANNEL_BALANCE, CHANNEL_PAUSE, CONFIG, EXECUTING_PACKET, EXECUTION_ACK,
+        GUARDIAN, HASH_TO_FOREIGN_TOKEN, NFT_ESCROW, RATE_LIMIT, RATE_LIMIT_FLOW, RELAYER_FEE,
         TOKEN_MINTER, TOKEN_ORIGIN,
     },
     ContractError,
@@ -193,6 +201,7 @@ pub fn execute(
             timeout_height,
             timeout_timestamp,
             salt,
+            relayer_fee,
         } => transfer(
             deps,
             env,
@@ -206,8 +215,134 @@ pub fn execute(
             timeout_height,
             timeout_timestamp,
             salt,
+            relayer_fee,
+        ),
+        ExecuteMsg::TransferNft {
+            channel_id,
+            receiver,
+            collection,
+            token_id,
+            quote_token,
+            timeout_height,
+            timeout_timestamp,
+            salt,
+        } => transfer_nft(
+            deps,
+            info,
+            channel_id,
+            receiver,
+            collection,
+            token_id,
+            quote_token,
+            timeout_height,
+            timeout_timestamp,
+            salt,
         ),
+        ExecuteMsg::SetGuardian { guardian } => {
+            ensure_admin(deps.as_ref(), &info)?;
+            GUARDIAN.save(deps.storage, &guardian)?;
+            Ok(Response::new().add_attribute("guardian", guardian))
+        }
+        ExecuteMsg::SetChannelPause { channel_id, pause } => {
+            if info.sender != CONFIG.load(deps.storage)?.admin
+                && GUARDIAN.may_load(deps.storage)? != Some(info.sender)
+            {
+                return Err(ContractError::OnlyGuardian);
+            }
+            CHANNEL_PAUSE.save(deps.storage, channel_id, &pause)?;
+            Ok(Response::new()
+                .add_attribute("channel_id", channel_id.to_string())
+                .add_attribute("pause_send", pause.send.to_string())
+                .add_attribute("pause_recv", pause.recv.to_string()))
+        }
+        ExecuteMsg::SetRateLimit {
+            channel_id,
+            denom,
+            rate_limit,
+        } => {
+            ensure_admin(deps.as_ref(), &info)?;
+            // The flows of the current window are reset, the new limit applies to a new window.
+            RATE_LIMIT_FLOW.remove(deps.storage, (channel_id, denom.clone()));
+            match rate_limit {
+                Some(rate_limit) => {
+                    RATE_LIMIT.save(deps.storage, (channel_id, denom.clone()), &rate_limit)?
+                }
+                None => RATE_LIMIT.remove(deps.storage, (channel_id, denom.clone())),
+            }
+            Ok(Response::new()
+                .add_attribute("channel_id", channel_id.to_string())
+                .add_attribute("denom", denom))
+        }
+    }
+}
+
+fn ensure_admin(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    if info.sender != CONFIG.load(deps.storage)?.admin {
+        return Err(ContractError::OnlyAdmin);
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum FlowDirection {
+    Outflow,
+    Inflow,
+}
+
+/// Account `amount` to the flow of a local denom on a channel. Fails if the rate limit of the current
+/// window would be exceeded. Denoms without a rate limit are not tracked.
+fn record_flow(
+    storage: &mut dyn Storage,
+    env: &Env,
+    channel_id: u32,
+    denom: String,
+    amount: Uint256,
+    direction: FlowDirection,
+) -> Result<(), ContractError> {
+    let Some(rate_limit) = RATE_LIMIT.may_load(storage, (channel_id, denom.clone()))? else {
+        return Ok(());
+    };
+    let mut flow = RATE_LIMIT_FLOW
+        .may_load(storage, (channel_id, denom.clone()))?
+        .unwrap_or_default();
+    if env.block.time >= flow.window_end {
+        flow = RateLimitFlow {
+            outflow: Uint256::zero(),
+            inflow: Uint256::zero(),
+            window_end: env.block.time.plus_seconds(rate_limit.window_seconds),
+        };
+    }
+    let (current, max) = match direction {
+        FlowDirection::Outflow => (&mut flow.outflow, rate_limit.max_outflow),
+        FlowDirection::Inflow => (&mut flow.inflow, rate_limit.max_inflow),
+    };
+    let remaining = max.saturating_sub(*current);
+    if amount > remaining {
+        return Err(ContractError::RateLimitExceeded {
+            channel_id,
+            denom,
+            amount,
+            remaining,
+        });
+    }
+    *current += amount;
+    RATE_LIMIT_FLOW.save(storage, (channel_id, denom), &flow)?;
+    Ok(())
+}
+
+/// Give back the outflow of a refunded transfer. If the window of the transfer already ended,
+/// the outflow of the current window is reduced instead.
+fn undo_outflow(
+    storage: &mut dyn Storage,
+    channel_id: u32,
+    denom: String,
+    amount: Uint256,
+) -> StdResult<()> {
+    if let Some(mut flow) = RATE_LIMIT_FLOW.may_load(storage, (channel_id, denom.clone()))? {
+        flow.outflow = flow.outflow.saturating_sub(amount);
+        RATE_LIMIT_FLOW.save(storage, (channel_id, denom), &flow)?;
     }
+    Ok(())
 }
 
 fn enforce_version(version: &str, counterparty_version: Option<&str>) -> Result<(), ContractError> {
@@ -227,25 +362,89 @@ fn enforce_version(version: &str, counterparty_version: Option<&str>) -> Result<
 }
 
 fn timeout_packet(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     packet: Packet,
     relayer: Addr,
 ) -> Result<Response, ContractError> {
     let zkgm_packet = ZkgmPacket::abi_decode_params(&packet.data, true)?;
-    timeout_internal(
-        deps,
+    let response = timeout_internal(
+        deps.branch(),
         env,
         info,
-        packet,
+        packet.clone(),
         relayer,
         zkgm_packet.salt.into(),
         zkgm_packet.path,
         zkgm_packet.instruction,
+    )?;
+    // The packet has not been relayed, the fee goes back to the payer.
+    let key = relayer_fee_key(
+        packet.source_channel_id,
+        &packet.data,
+        packet.timeout_height,
+        packet.timeout_timestamp,
+    );
+    match RELAYER_FEE.may_load(deps.storage, key.clone())? {
+        Some(RelayerFeeEscrow { payer, fee }) => {
+            RELAYER_FEE.remove(deps.storage, key);
+            Ok(response.add_message(BankMsg::Send {
+                to_address: payer.into_string(),
+                amount: vec![fee],
+            }))
+        }
+        None => Ok(response),
+    }
+}
+
+/// The packet sent on a channel is fully determined by its data and timeouts, the destination
+/// channel being fixed by the source channel.
+fn relayer_fee_key(
+    channel_id: u32,
+    data: &[u8],
+    timeout_height: u64,
+    timeout_timestamp: u64,
+) -> (u32, String) {
+    (
+        channel_id,
+        keccak256(
+            [
+                data,
+                &timeout_height.to_be_bytes(),
+                &timeout_timestamp.to_be_bytes(),
+            ]
+            .concat(),
+        )
+        .to_string(),
     )
 }
 
+/// The payee of the recv relayer, carried in the acknowledgement of a filled fungible asset order:
+/// the `relayer_msg` (or address) of the recv relayer for a protocol fill, or the market maker, which
+/// is the recv relayer filling the order itself, for a market maker fill.
+///
+/// A failure acknowledgement carries no payee, the relayer fee is then paid to the ack relayer. Only
+/// `Transfer` escrows a relayer fee, so the other instructions (nft orders, batches, multiplexes) never
+/// have a fee to pay.
+fn recv_relayer_payee(
+    deps: Deps,
+    instruction: &Instruction,
+    successful: bool,
+    ack: &[u8],
+) -> Option<Addr> {
+    if !successful || instruction.opcode != OP_FUNGIBLE_ASSET_ORDER {
+        return None;
+    }
+    let order_ack = FungibleAssetOrderAck::abi_decode_params(ack, true).ok()?;
+    if order_ack.fill_type != FILL_TYPE_PROTOCOL && order_ack.fill_type != FILL_TYPE_MARKETMAKER {
+        return None;
+    }
+    deps.api
+        .addr_validate(str::from_utf8(&order_ack.market_maker).ok()?)
+        .ok()
+}
+
 #[allow(clippy::too_many_arguments)]
 fn timeout_internal(
     mut deps: DepsMut,
@@ -267,6 +466,15 @@ fn timeout_internal(
             let order = decode_fungible_asset(&instruction)?;
             refund(deps, packet.source_channel_id, order)
         }
+        OP_NON_FUNGIBLE_ASSET_ORDER => {
+            if instruction.version > INSTR_VERSION_0 {
+                return Err(ContractError::UnsupportedVersion {
+                    version: instruction.version,
+                });
+            }
+            let order = NonFungibleAssetOrder::abi_decode_params(&instruction.operand, true)?;
+            refund_nft(deps, packet.source_channel_id, order)
+        }
         OP_BATCH => {
             if instruction.version > INSTR_VERSION_0 {
                 return Err(ContractError::UnsupportedVersion {
@@ -316,7 +524,7 @@ fn timeout_internal(
 }
 
 fn acknowledge_packet(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     packet: Packet,
@@ -325,18 +533,43 @@ fn acknowledge_packet(
 ) -> Result<Response, ContractError> {
     let zkgm_packet = ZkgmPacket::abi_decode_params(&packet.data, true)?;
     let ack = Ack::abi_decode_params(&ack, true)?;
-    acknowledge_internal(
-        deps,
+    let successful = ack.tag == TAG_ACK_SUCCESS;
+    let inner_ack: Bytes = Vec::from(ack.inner_ack).into();
+    let payee = recv_relayer_payee(
+        deps.as_ref(),
+        &zkgm_packet.instruction,
+        successful,
+        &inner_ack,
+    );
+    let response = acknowledge_internal(
+        deps.branch(),
         env,
         info,
-        packet,
-        relayer,
+        packet.clone(),
+        relayer.clone(),
         zkgm_packet.salt.into(),
         zkgm_packet.path,
         zkgm_packet.instruction,
-        ack.tag == TAG_ACK_SUCCESS,
-        Vec::from(ack.inner_ack).into(),
-    )
+        successful,
+        inner_ack,
+    )?;
+    // The packet has been relayed, the fee is paid regardless of the outcome.
+    let key = relayer_fee_key(
+        packet.source_channel_id,
+        &packet.data,
+        packet.timeout_height,
+        packet.timeout_timestamp,
+    );
+    match RELAYER_FEE.may_load(deps.storage, key.clone())? {
+        Some(RelayerFeeEscrow { fee, .. }) => {
+            RELAYER_FEE.remove(deps.storage, key);
+            Ok(response.add_message(BankMsg::Send {
+                to_address: payee.unwrap_or(relayer).into_string(),
+                amount: vec![fee],
+            }))
+        }
+        None => Ok(response),
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -369,6 +602,20 @@ fn acknowledge_internal(
                 deps, env, info, packet, relayer, salt, path, order, order_ack,
             )
         }
+        OP_NON_FUNGIBLE_ASSET_ORDER => {
+            if instruction.version > INSTR_VERSION_0 {
+                return Err(ContractError::UnsupportedVersion {
+                    version: instruction.version,
+                });
+            }
+            let order = NonFungibleAssetOrder::abi_decode_params(&instruction.operand, true)?;
+            if successful {
+                // The nft stays escrowed (native) or burnt (wrapped).
+                Ok(Response::new())
+            } else {
+                refund_nft(deps, packet.source_channel_id, order)
+            }
+        }
         OP_BATCH => {
             if instruction.version > INSTR_VERSION_0 {
                 return Err(ContractError::UnsupportedVersion {
@@ -444,6 +691,12 @@ fn refund(
         u128::try_from(order.base_amount).map_err(|_| ContractError::AmountOverflow)?;
     let base_denom =
         String::from_utf8(base_token.to_vec()).map_err(|_| ContractError::InvalidBaseToken)?;
+    undo_outflow(
+        deps.storage,
+        source_channel,
+        base_denom.clone(),
+        base_amount.into(),
+    )?;
     let mut messages = Vec::<CosmosMsg>::new();
     // TODO: handle forward path
     if order.base_token_path == source_channel.try_into().unwrap() {
@@ -470,6 +723,41 @@ fn refund(
     Ok(Response::new().add_messages(messages))
 }
 
+fn refund_nft(
+    deps: DepsMut,
+    source_channel: u32,
+    order: NonFungibleAssetOrder,
+) -> Result<Response, ContractError> {
+    let sender = deps
+        .api
+        .addr_validate(str::from_utf8(&order.sender).map_err(|_| ContractError::InvalidSender)?)
+        .map_err(|_| ContractError::UnableToValidateSender)?;
+    let minter = TOKEN_MINTER.load(deps.storage)?;
+    let collection = String::from_utf8(order.base_token.to_vec())
+        .map_err(|_| ContractError::InvalidBaseToken)?;
+    // TODO: handle forward path
+    let msg = if order.base_token_path == alloy::primitives::U256::from(source_channel) {
+        // The wrapped nft has been burnt, mint it back.
+        NonFungibleTokenMsg::Mint {
+            collection,
+            token_id: order.token_id,
+            token_uri: (!order.token_uri.is_empty()).then_some(order.token_uri),
+            owner: sender.into_string(),
+        }
+    } else {
+        NFT_ESCROW.remove(
+            deps.storage,
+            (source_channel, collection.clone(), order.token_id.clone()),
+        );
+        NonFungibleTokenMsg::Unescrow {
+            collection,
+            token_id: order.token_id,
+            recipient: sender.into_string(),
+        }
+    };
+    Ok(Response::new().add_message(make_wasm_msg(msg, minter, vec![])?))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn acknowledge_fungible_asset_order(
     deps: DepsMut,
@@ -544,6 +832,16 @@ fn execute_packet(
     relayer: Addr,
     relayer_msg: Bytes,
 ) -> Result<Response, ContractError> {
+    // Failing the execution results in a failure acknowledgement, refunding the sender.
+    if CHANNEL_PAUSE
+        .may_load(deps.storage, packet.destination_channel_id)?
+        .unwrap_or_default()
+        .recv
+    {
+        return Err(ContractError::RecvPaused {
+            channel_id: packet.destination_channel_id,
+        });
+    }
     let zkgm_packet = ZkgmPacket::abi_decode_params(&packet.data, true)?;
     let (ack, response) = execute_internal(
         deps.branch(),
@@ -592,6 +890,15 @@ fn execute_internal(
                 order,
             )
         }
+        OP_NON_FUNGIBLE_ASSET_ORDER => {
+            if instruction.version > INSTR_VERSION_0 {
+                return Err(ContractError::UnsupportedVersion {
+                    version: instruction.version,
+                });
+            }
+            let order = NonFungibleAssetOrder::abi_decode_params(&instruction.operand, true)?;
+            execute_non_fungible_asset_order(deps, packet, path, order)
+        }
         OP_BATCH => {
             if instruction.version > INSTR_VERSION_0 {
                 return Err(ContractError::UnsupportedVersion {
@@ -660,6 +967,30 @@ fn query_predict_wrapped_token(
         .wrapped_token)
 }
 
+fn query_predict_wrapped_collection(
+    deps: Deps,
+    minter: &Addr,
+    path: U256,
+    channel: u32,
+    token: Bytes,
+) -> StdResult<String> {
+    Ok(deps
+        .querier
+        .query::<ucs03_zkgm_token_minter_api::PredictWrappedTokenResponse>(&QueryRequest::Wasm(
+            cosmwasm_std::WasmQuery::Smart {
+                contract_addr: minter.to_string(),
+                msg: to_json_binary(
+                    &ucs03_zkgm_token_minter_api::QueryMsg::PredictWrappedCollection {
+                        path: path.to_string(),
+                        channel,
+                        token: Binary::new(token.to_vec()),
+                    },
+                )?,
+            },
+        ))?
+        .wrapped_token)
+}
+
 // fn predict_wrapped_denom(path: alloy::primitives::U256, channel: u32, token: Bytes) -> String {
 //     // TokenFactory denom name limit
 //     const MAX_DENOM_LENGTH: usize = 44;
@@ -771,11 +1102,11 @@ fn execute_batch(
 #[allow(clippy::too_many_arguments)]
 fn execute_fungible_asset_order(
     deps: DepsMut,
-    _: Env,
+    env: Env,
     _info: MessageInfo,
     packet: Packet,
     relayer: Addr,
-    _relayer_msg: Bytes,
+    relayer_msg: Bytes,
     _salt: H256,
     path: U256,
     order: FungibleAssetOrder,
@@ -784,6 +1115,13 @@ fn execute_fungible_asset_order(
         return Ok((ACK_ERR_ONLY_MAKER.into(), Response::new()));
     }
 
+    // The relayer can provide its payee on the source chain, used to pay the relayer fee.
+    let payee = if relayer_msg.is_empty() {
+        relayer.as_bytes().to_vec()
+    } else {
+        relayer_msg.to_vec()
+    };
+
     let minter = TOKEN_MINTER.load(deps.storage)?;
     let wrapped_denom = query_predict_wrapped_token(
         deps.as_ref(),
@@ -804,6 +1142,14 @@ fn execute_fungible_asset_order(
         .map_err(|_| ContractError::UnableToValidateReceiver)?;
     let mut messages = Vec::<SubMsg>::new();
     if order.quote_token.as_ref() == wrapped_denom.as_bytes() {
+        record_flow(
+            deps.storage,
+            &env,
+            packet.destination_channel_id,
+            wrapped_denom.clone(),
+            (quote_amount + fee_amount).into(),
+            FlowDirection::Inflow,
+        )?;
         // TODO: handle forwarding path
         if !HASH_TO_FOREIGN_TOKEN.has(deps.storage, wrapped_denom.clone()) {
             HASH_TO_FOREIGN_TOKEN.save(
@@ -855,6 +1201,14 @@ fn execute_fungible_asset_order(
     } else if order.base_token_path == alloy::primitives::U256::from(packet.source_channel_id) {
         let quote_token = String::from_utf8(Vec::from(order.quote_token))
             .map_err(|_| ContractError::InvalidQuoteToken)?;
+        record_flow(
+            deps.storage,
+            &env,
+            packet.destination_channel_id,
+            quote_token.clone(),
+            (quote_amount + fee_amount).into(),
+            FlowDirection::Inflow,
+        )?;
         CHANNEL_BALANCE.update(
             deps.storage,
             (packet.destination_channel_id, quote_token.clone()),
@@ -891,7 +1245,7 @@ fn execute_fungible_asset_order(
     Ok((
         FungibleAssetOrderAck {
             fill_type: FILL_TYPE_PROTOCOL,
-            market_maker: Default::default(),
+            market_maker: payee.into(),
         }
         .abi_encode_params()
         .into(),
@@ -899,6 +1253,98 @@ fn execute_fungible_asset_order(
     ))
 }
 
+fn execute_non_fungible_asset_order(
+    deps: DepsMut,
+    packet: Packet,
+    path: U256,
+    order: NonFungibleAssetOrder,
+) -> Result<(Bytes, Response), ContractError> {
+    let minter = TOKEN_MINTER.load(deps.storage)?;
+    let wrapped_collection = query_predict_wrapped_collection(
+        deps.as_ref(),
+        &minter,
+        path,
+        packet.destination_channel_id,
+        Vec::from(order.base_token.clone()).into(),
+    )?;
+    let receiver = deps
+        .api
+        .addr_validate(
+            str::from_utf8(order.receiver.as_ref()).map_err(|_| ContractError::InvalidReceiver)?,
+        )
+        .map_err(|_| ContractError::UnableToValidateReceiver)?;
+    let mut messages = Vec::<CosmosMsg>::new();
+    if order.quote_token.as_ref() == wrapped_collection.as_bytes() {
+        // TODO: handle forwarding path
+        if !HASH_TO_FOREIGN_TOKEN.has(deps.storage, wrapped_collection.clone()) {
+            HASH_TO_FOREIGN_TOKEN.save(
+                deps.storage,
+                wrapped_collection.clone(),
+                &Vec::from(order.base_token.clone()).into(),
+            )?;
+            messages.push(make_wasm_msg(
+                NonFungibleTokenMsg::CreateCollection {
+                    metadata: CollectionMetadata {
+                        name: order.base_token_name,
+                        symbol: order.base_token_symbol,
+                    },
+                    path: path.to_be_bytes_vec().into(),
+                    channel: packet.destination_channel_id,
+                    token: Vec::from(order.base_token.clone()).into(),
+                },
+                &minter,
+                vec![],
+            )?);
+            TOKEN_ORIGIN.save(
+                deps.storage,
+                wrapped_collection.clone(),
+                &Uint256::from_u128(packet.destination_channel_id as _),
+            )?;
+        }
+        messages.push(make_wasm_msg(
+            NonFungibleTokenMsg::Mint {
+                collection: wrapped_collection,
+                token_id: order.token_id,
+                token_uri: (!order.token_uri.is_empty()).then_some(order.token_uri),
+                owner: receiver.into_string(),
+            },
+            &minter,
+            vec![],
+        )?);
+    } else if order.base_token_path == alloy::primitives::U256::from(packet.source_channel_id) {
+        let collection = String::from_utf8(Vec::from(order.quote_token))
+            .map_err(|_| ContractError::InvalidQuoteToken)?;
+        let escrow_key = (
+            packet.destination_channel_id,
+            collection.clone(),
+            order.token_id.clone(),
+        );
+        if !NFT_ESCROW.has(deps.storage, escrow_key.clone()) {
+            return Err(ContractError::NftNotEscrowed {
+                collection,
+                token_id: order.token_id,
+            });
+        }
+        NFT_ESCROW.remove(deps.storage, escrow_key);
+        messages.push(make_wasm_msg(
+            NonFungibleTokenMsg::Unescrow {
+                collection,
+                token_id: order.token_id,
+                recipient: receiver.into_string(),
+            },
+            &minter,
+            vec![],
+        )?);
+    } else {
+        // Unlike fungible orders, there is no market maker to fill the order.
+        return Err(ContractError::InvalidQuoteToken);
+    }
+    Ok((
+        TAG_ACK_SUCCESS.abi_encode().into(),
+        Response::new().add_messages(messages),
+    ))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, ContractError> {
     match reply.id {
@@ -993,7 +1439,7 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, Contrac
 #[allow(clippy::too_many_arguments)]
 fn transfer(
     deps: DepsMut,
-    _: Env,
+    env: Env,
     info: MessageInfo,
     channel_id: u32,
     receiver: Bytes,
@@ -1004,12 +1450,39 @@ fn transfer(
     timeout_height: u64,
     timeout_timestamp: u64,
     salt: H256,
+    relayer_fee: Option<Coin>,
 ) -> Result<Response, ContractError> {
     // NOTE(aeryz): We don't check whether the funds are provided here. We check it in the
     // minter because cw20 token minter doesn't require funds to be given in the native form.
     if base_amount.is_zero() {
         return Err(ContractError::InvalidAmount);
     }
+    if CHANNEL_PAUSE
+        .may_load(deps.storage, channel_id)?
+        .unwrap_or_default()
+        .send
+    {
+        return Err(ContractError::SendPaused { channel_id });
+    }
+    // The relayer fee is kept by the contract, the remaining funds go to the minter.
+    let relayer_fee = relayer_fee.filter(|fee| !fee.amount.is_zero());
+    let mut funds = info.funds.clone();
+    if let Some(fee) = &relayer_fee {
+        let coin = funds
+            .iter_mut()
+            .find(|coin| coin.denom == fee.denom && coin.amount >= fee.amount)
+            .ok_or(ContractError::MissingRelayerFee)?;
+        coin.amount -= fee.amount;
+        funds.retain(|coin| !coin.amount.is_zero());
+    }
+    record_flow(
+        deps.storage,
+        &env,
+        channel_id,
+        base_token.clone(),
+        base_amount.into(),
+        FlowDirection::Outflow,
+    )?;
     let minter = TOKEN_MINTER.load(deps.storage)?;
     // If the origin exists, the preimage exists
     let unwrapped_asset = HASH_TO_FOREIGN_TOKEN.may_load(deps.storage, base_token.clone())?;
@@ -1031,7 +1504,7 @@ fn transfer(
                         sender: info.sender.clone(),
                     },
                     &minter,
-                    info.funds,
+                    funds,
                 )?,
                 ESCROW_REPLY_ID,
             ))
@@ -1048,7 +1521,7 @@ fn transfer(
                         amount: base_amount,
                     },
                     &minter,
-                    info.funds,
+                    funds,
                 )?,
                 ESCROW_REPLY_ID,
             ));
@@ -1074,6 +1547,47 @@ fn transfer(
             })?,
         },
     ))?;
+    let data: Bytes = ZkgmPacket {
+        salt: salt.into(),
+        path: alloy::primitives::U256::ZERO,
+        instruction: Instruction {
+            version: INSTR_VERSION_1,
+            opcode: OP_FUNGIBLE_ASSET_ORDER,
+            operand: FungibleAssetOrder {
+                sender: info.sender.as_bytes().to_vec().into(),
+                receiver: Vec::from(receiver).into(),
+                base_token: base_token.as_bytes().to_vec().into(),
+                base_amount: base_amount.u128().try_into().expect("u256>u128"),
+                base_token_symbol,
+                base_token_name,
+                base_token_decimals,
+                base_token_path: origin
+                    .map(|x| alloy::primitives::U256::from_be_bytes(x.to_be_bytes()))
+                    .unwrap_or(alloy::primitives::U256::ZERO),
+                quote_token: Vec::from(quote_token).into(),
+                quote_amount: alloy::primitives::U256::from_be_bytes(quote_amount.to_be_bytes()),
+            }
+            .abi_encode_params()
+            .into(),
+        },
+    }
+    .abi_encode_params()
+    .into();
+    if let Some(fee) = relayer_fee {
+        let key = relayer_fee_key(channel_id, &data, timeout_height, timeout_timestamp);
+        // An identical packet is still in flight, its fee must not be overwritten.
+        if RELAYER_FEE.has(deps.storage, key.clone()) {
+            return Err(ContractError::RelayerFeeAlreadyEscrowed);
+        }
+        RELAYER_FEE.save(
+            deps.storage,
+            key,
+            &RelayerFeeEscrow {
+                payer: info.sender.clone(),
+                fee,
+            },
+        )?;
+    }
     let config = CONFIG.load(deps.storage)?;
     messages.push(SubMsg::new(wasm_execute(
         &config.ibc_host,
@@ -1081,40 +1595,139 @@ fn transfer(
             source_channel: channel_id,
             timeout_height,
             timeout_timestamp,
-            data: ZkgmPacket {
-                salt: salt.into(),
-                path: alloy::primitives::U256::ZERO,
-                instruction: Instruction {
-                    version: INSTR_VERSION_1,
-                    opcode: OP_FUNGIBLE_ASSET_ORDER,
-                    operand: FungibleAssetOrder {
-                        sender: info.sender.as_bytes().to_vec().into(),
-                        receiver: Vec::from(receiver).into(),
-                        base_token: base_token.as_bytes().to_vec().into(),
-                        base_amount: base_amount.u128().try_into().expect("u256>u128"),
-                        base_token_symbol,
-                        base_token_name,
-                        base_token_decimals,
-                        base_token_path: origin
-                            .map(|x| alloy::primitives::U256::from_be_bytes(x.to_be_bytes()))
-                            .unwrap_or(alloy::primitives::U256::ZERO),
-                        quote_token: Vec::from(quote_token).into(),
-                        quote_amount: alloy::primitives::U256::from_be_bytes(
-                            quote_amount.to_be_bytes(),
-                        ),
-                    }
-                    .abi_encode_params()
-                    .into(),
-                },
-            }
-            .abi_encode_params()
-            .into(),
+            data,
         }),
         vec![],
     )?));
     Ok(Response::new().add_submessages(messages))
 }
 
+#[allow(clippy::too_many_arguments)]
+fn transfer_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: u32,
+    receiver: Bytes,
+    collection: String,
+    token_id: String,
+    quote_token: Bytes,
+    timeout_height: u64,
+    timeout_timestamp: u64,
+    salt: H256,
+) -> Result<Response, ContractError> {
+    if CHANNEL_PAUSE
+        .may_load(deps.storage, channel_id)?
+        .unwrap_or_default()
+        .send
+    {
+        return Err(ContractError::SendPaused { channel_id });
+    }
+    let collection_addr = deps
+        .api
+        .addr_validate(&collection)
+        .map_err(|_| ContractError::InvalidCollection)?;
+    // zkgm transfers or burns the nft with its own approval, the sender must own it.
+    let cw721::OwnerOfResponse { owner } = deps.querier.query_wasm_smart(
+        &collection_addr,
+        &cw721::QueryMsg::OwnerOf {
+            token_id: token_id.clone(),
+            include_expired: None,
+        },
+    )?;
+    if owner != info.sender.as_str() {
+        return Err(ContractError::NotNftOwner {
+            collection,
+            token_id,
+        });
+    }
+    let minter = TOKEN_MINTER.load(deps.storage)?;
+    // The metadata must be queried before a wrapped nft is burnt.
+    let cw721::ContractInfoResponse {
+        name: base_token_name,
+        symbol: base_token_symbol,
+    } = deps
+        .querier
+        .query_wasm_smart(&collection_addr, &cw721::QueryMsg::ContractInfo {})?;
+    let cw721::NftInfoResponse { token_uri } = deps.querier.query_wasm_smart(
+        &collection_addr,
+        &cw721::QueryMsg::NftInfo {
+            token_id: token_id.clone(),
+        },
+    )?;
+    // If the origin exists, the preimage exists
+    let unwrapped_collection = HASH_TO_FOREIGN_TOKEN.may_load(deps.storage, collection.clone())?;
+    // TODO: handle forward path
+    let mut origin = TOKEN_ORIGIN.may_load(deps.storage, collection.clone())?;
+    let msg = match origin {
+        // Burn as we are going to unescrow on the counterparty
+        Some(path)
+            if path == Uint256::from(channel_id)
+                && unwrapped_collection == Some(quote_token.clone()) =>
+        {
+            NonFungibleTokenMsg::Burn {
+                from: info.sender.to_string(),
+                collection: collection.clone(),
+                token_id: token_id.clone(),
+            }
+        }
+        // Escrow, the counterparty will mint the nft
+        _ => {
+            origin = None;
+            NFT_ESCROW.save(
+                deps.storage,
+                (channel_id, collection.clone(), token_id.clone()),
+                &Empty {},
+            )?;
+            NonFungibleTokenMsg::Escrow {
+                from: info.sender.to_string(),
+                collection: collection.clone(),
+                token_id: token_id.clone(),
+                recipient: minter.to_string(),
+            }
+        }
+    };
+    let config = CONFIG.load(deps.storage)?;
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            make_wasm_msg(msg, &minter, vec![])?,
+            ESCROW_REPLY_ID,
+        ))
+        .add_message(wasm_execute(
+            &config.ibc_host,
+            &ibc_union_msg::msg::ExecuteMsg::PacketSend(MsgSendPacket {
+                source_channel: channel_id,
+                timeout_height,
+                timeout_timestamp,
+                data: ZkgmPacket {
+                    salt: salt.into(),
+                    path: alloy::primitives::U256::ZERO,
+                    instruction: Instruction {
+                        version: INSTR_VERSION_0,
+                        opcode: OP_NON_FUNGIBLE_ASSET_ORDER,
+                        operand: NonFungibleAssetOrder {
+                            sender: info.sender.as_bytes().to_vec().into(),
+                            receiver: Vec::from(receiver).into(),
+                            base_token: collection.as_bytes().to_vec().into(),
+                            base_token_name,
+                            base_token_symbol,
+                            base_token_path: origin
+                                .map(|x| alloy::primitives::U256::from_be_bytes(x.to_be_bytes()))
+                                .unwrap_or(alloy::primitives::U256::ZERO),
+                            quote_token: Vec::from(quote_token).into(),
+                            token_id,
+                            token_uri: token_uri.unwrap_or_default(),
+                        }
+                        .abi_encode_params()
+                        .into(),
+                    },
+                }
+                .abi_encode_params()
+                .into(),
+            }),
+            vec![],
+        )?))
+}
+
 #[cosmwasm_schema::cw_serde]
 pub struct TokenMinterMigration {
     // code id of the new token minter
@@ -1166,6 +1779,9 @@ pub fn migrate(
     )
 }
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 fn make_wasm_msg(
     msg: impl Into<ucs03_zkgm_token_minter_api::ExecuteMsg>,
     minter: impl Into<String>,
@@ -1195,5 +1811,102 @@ pub fn query(deps: Deps, _: Env, msg: QueryMsg) -> Result<Binary, ContractError>
                 wrapped_token: token.as_bytes().into(),
             })?)
         }
+        QueryMsg::PredictWrappedCollection {
+            path,
+            channel,
+            token,
+        } => {
+            let minter = TOKEN_MINTER.load(deps.storage)?;
+            let collection = query_predict_wrapped_collection(
+                deps,
+                &minter,
+                path.parse().map_err(ContractError::InvalidPath)?,
+                channel,
+                token,
+            )?;
+            Ok(to_json_binary(&PredictWrappedTokenResponse {
+                wrapped_token: collection.as_bytes().into(),
+            })?)
+        }
+        QueryMsg::ChannelPause { channel_id } => Ok(to_json_binary(
+            &CHANNEL_PAUSE
+                .may_load(deps.storage, channel_id)?
+                .unwrap_or_default(),
+        )?),
+        QueryMsg::RateLimit { channel_id, denom } => Ok(to_json_binary(&RateLimitResponse {
+            rate_limit: RATE_LIMIT.may_load(deps.storage, (channel_id, denom.clone()))?,
+            flow: RATE_LIMIT_FLOW.may_load(deps.storage, (channel_id, denom))?,
+        })?),
+        QueryMsg::ChannelBalance { channel_id, denom } => Ok(to_json_binary(
+            &CHANNEL_BALANCE
+                .may_load(deps.storage, (channel_id, denom))?
+                .unwrap_or_default(),
+        )?),
+        QueryMsg::ChannelBalances {
+            channel_id,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let balances = CHANNEL_BALANCE
+                .prefix(channel_id)
+                .range(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(denom, balance)| ChannelBalance { denom, balance }))
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&ChannelBalancesResponse { balances })?)
+        }
+        QueryMsg::TokenOrigin { denom } => {
+            let origin = match (
+                TOKEN_ORIGIN.may_load(deps.storage, denom.clone())?,
+                HASH_TO_FOREIGN_TOKEN.may_load(deps.storage, denom)?,
+            ) {
+                (Some(path), Some(foreign_token)) => Some(TokenOriginResponse {
+                    path,
+                    foreign_token,
+                }),
+                _ => None,
+            };
+            Ok(to_json_binary(&origin)?)
+        }
+        QueryMsg::WrappedToken {
+            path,
+            channel,
+            token,
+        } => {
+            let minter = TOKEN_MINTER.load(deps.storage)?;
+            let wrapped_token = query_predict_wrapped_token(
+                deps,
+                &minter,
+                path.parse().map_err(ContractError::InvalidPath)?,
+                channel,
+                token,
+            )?;
+            // The preimage is only stored once the wrapped token has been created
+            let wrapped_token = HASH_TO_FOREIGN_TOKEN
+                .has(deps.storage, wrapped_token.clone())
+                .then_some(wrapped_token);
+            Ok(to_json_binary(&wrapped_token)?)
+        }
+        QueryMsg::PacketInFlight {
+            channel_id,
+            packet_hash,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            // The commitment is removed by the host once the packet is acknowledged or timed out
+            let commitment = deps.querier.query_wasm_smart::<Option<H256>>(
+                &config.ibc_host,
+                &ibc_union_msg::query::QueryMsg::GetBatchPackets {
+                    channel_id,
+                    batch_hash: packet_hash,
+                },
+            )?;
+            Ok(to_json_binary(&(commitment == Some(COMMITMENT_MAGIC)))?)
+        }
     }
 }
@@ -2,8 +2,10 @@ pub mod com;
 pub mod contract;
 pub mod msg;
 mod state;
+#[cfg(test)]
+mod tests;
 use alloy::primitives::ruint::ParseError;
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint256};
 use thiserror::Error;
 use unionlabs_cosmwasm_upgradable::UpgradeError;
 
@@ -19,6 +21,33 @@ pub enum ContractError {
     OnlyIBCHost,
     #[error("invalid operation, sender must be self")]
     OnlySelf,
+    #[error("invalid operation, sender must be the admin")]
+    OnlyAdmin,
+    #[error("invalid operation, sender must be the guardian or the admin")]
+    OnlyGuardian,
+    #[error("sending on channel {channel_id} is paused")]
+    SendPaused { channel_id: u32 },
+    #[error("receiving on channel {channel_id} is paused")]
+    RecvPaused { channel_id: u32 },
+    #[error("nft {token_id} of collection {collection} is not escrowed on this channel")]
+    NftNotEscrowed {
+        collection: String,
+        token_id: String,
+    },
+    #[error("nft {token_id} of collection {collection} is not owned by the sender")]
+    NotNftOwner {
+        collection: String,
+        token_id: String,
+    },
+    #[error("the collection must be a valid address")]
+    InvalidCollection,
+    #[error("rate limit exceeded for {denom} on channel {channel_id}: {amount} > {remaining}")]
+    RateLimitExceeded {
+        channel_id: u32,
+        denom: String,
+        amount: Uint256,
+        remaining: Uint256,
+    },
     #[error(transparent)]
     Alloy(#[from] alloy::sol_types::Error),
     #[error("invalid zkgm instruction version: {version}")]
@@ -43,6 +72,10 @@ pub enum ContractError {
     InvalidAmount,
     #[error("transfer require funds to be submitted along the transaction")]
     MissingFunds,
+    #[error("the relayer fee must be submitted along the transaction")]
+    MissingRelayerFee,
+    #[error("a relayer fee is already escrowed for an identical packet, use a different salt")]
+    RelayerFeeAlreadyEscrowed,
     #[error("receiver must be a valid address")]
     InvalidReceiver,
     #[error("receiver must be a valid address")]
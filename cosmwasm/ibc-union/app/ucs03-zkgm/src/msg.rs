@@ -1,5 +1,5 @@
-use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, CosmosMsg, Uint128, Uint256};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Timestamp, Uint128, Uint256};
 use ibc_union_spec::types::Packet;
 use unionlabs::primitives::{Bytes, H256};
 
@@ -29,12 +29,15 @@ pub enum TokenMinterInitMsg {
         cw20_base_code_id: u64,
         /// The code id of the dummy contract in order to get a contract address that does not depend on the code hash of `cw20_base`
         dummy_code_id: u64,
+        /// The code id of a cw721 implementation (ie. `cw721-base`), used for wrapped nft collections. Non fungible asset orders are not supported without it.
+        #[serde(default)]
+        cw721_base_code_id: Option<u64>,
     },
     /// Instantiate `ucs03-zkgm` with a native tokenfactory minter implementation.
     Native,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Transfer {
@@ -47,6 +50,24 @@ pub enum ExecuteMsg {
         timeout_height: u64,
         timeout_timestamp: u64,
         salt: H256,
+        /// Fee for the relayers of the packet, sent along with the transaction on top of the
+        /// transferred funds. It is escrowed until the packet is acknowledged, then paid to the
+        /// payee of the recv relayer (carried in the acknowledgement of a filled order) or, if
+        /// there is none (a failure acknowledgement), to the ack relayer. It is refunded on
+        /// timeout.
+        #[serde(default)]
+        relayer_fee: Option<Coin>,
+    },
+    /// Transfer a cw721 nft. The sender must approve `ucs03-zkgm` to transfer (native) or burn (wrapped) the nft.
+    TransferNft {
+        channel_id: u32,
+        receiver: Bytes,
+        collection: String,
+        token_id: String,
+        quote_token: Bytes,
+        timeout_height: u64,
+        timeout_timestamp: u64,
+        salt: H256,
     },
     BatchExecute {
         msgs: Vec<CosmosMsg>,
@@ -54,9 +75,53 @@ pub enum ExecuteMsg {
     ExecutePacket {
         packet: Packet,
         relayer: Addr,
+        /// If not empty, the address (on the source chain) the relayer wants to receive the relayer fee at.
         relayer_msg: Bytes,
     },
     IbcUnionMsg(ibc_union_msg::module::IbcUnionMsg),
+    /// Set the guardian, allowed to pause channels. Only callable by the admin.
+    SetGuardian {
+        guardian: Addr,
+    },
+    /// Pause (or unpause) sends and/or receives on a channel. Only callable by the guardian or the admin.
+    SetChannelPause {
+        channel_id: u32,
+        pause: ChannelPause,
+    },
+    /// Set (or remove if `None`) the rate limit of a local denom on a channel. Only callable by the admin.
+    SetRateLimit {
+        channel_id: u32,
+        denom: String,
+        rate_limit: Option<RateLimit>,
+    },
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ChannelPause {
+    /// Reject `Transfer` on the channel.
+    pub send: bool,
+    /// Acknowledge received packets on the channel with a failure, refunding the sender.
+    pub recv: bool,
+}
+
+#[cw_serde]
+pub struct RateLimit {
+    /// Duration of a window, the flows are reset when a window ends.
+    pub window_seconds: u64,
+    /// Maximum amount sent (escrowed or burnt) on the channel within a window.
+    pub max_outflow: Uint256,
+    /// Maximum amount received (unescrowed or minted) on the channel within a window.
+    pub max_inflow: Uint256,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct RateLimitFlow {
+    pub outflow: Uint256,
+    pub inflow: Uint256,
+    /// End of the current window.
+    pub window_end: Timestamp,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -73,8 +138,10 @@ pub enum EurekaMsg {
 pub struct MigrateMsg {}
 
 #[cw_serde]
+#[derive(QueryResponses)]
 pub enum QueryMsg {
     /// Calculate the wrapped token denom
+    #[returns(PredictWrappedTokenResponse)]
     PredictWrappedToken {
         path: String,
         /// Destination channel id
@@ -82,6 +149,71 @@ pub enum QueryMsg {
         /// Base token denom
         token: Bytes,
     },
+    /// Calculate the wrapped cw721 collection address
+    #[returns(PredictWrappedTokenResponse)]
+    PredictWrappedCollection {
+        path: String,
+        /// Destination channel id
+        channel: u32,
+        /// Base collection address
+        token: Bytes,
+    },
+    /// The pause status of a channel
+    #[returns(ChannelPause)]
+    ChannelPause { channel_id: u32 },
+    /// The rate limit and current flows of a local denom on a channel
+    #[returns(RateLimitResponse)]
+    RateLimit { channel_id: u32, denom: String },
+    /// The amount of a local denom escrowed on a channel, 0 if unset
+    #[returns(Uint256)]
+    ChannelBalance { channel_id: u32, denom: String },
+    /// The amounts escrowed on a channel, ordered by denom. Supports pagination.
+    #[returns(ChannelBalancesResponse)]
+    ChannelBalances {
+        channel_id: u32,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The origin (path and foreign token) of a wrapped denom, `None` if the denom is not wrapped
+    #[returns(Option<TokenOriginResponse>)]
+    TokenOrigin { denom: String },
+    /// The wrapped denom of a foreign token, `None` if it has not been received yet
+    #[returns(Option<String>)]
+    WrappedToken {
+        path: String,
+        /// Destination channel id
+        channel: u32,
+        /// Base token denom
+        token: Bytes,
+    },
+    /// Whether a packet sent on the channel is in flight, i.e. neither acknowledged nor timed out
+    #[returns(bool)]
+    PacketInFlight { channel_id: u32, packet_hash: H256 },
+}
+
+#[cw_serde]
+pub struct ChannelBalance {
+    pub denom: String,
+    pub balance: Uint256,
+}
+
+#[cw_serde]
+pub struct ChannelBalancesResponse {
+    pub balances: Vec<ChannelBalance>,
+}
+
+#[cw_serde]
+pub struct TokenOriginResponse {
+    /// The path the token was received through
+    pub path: Uint256,
+    /// The base token on the counterparty
+    pub foreign_token: Bytes,
+}
+
+#[cw_serde]
+pub struct RateLimitResponse {
+    pub rate_limit: Option<RateLimit>,
+    pub flow: Option<RateLimitFlow>,
 }
 
 #[cw_serde]
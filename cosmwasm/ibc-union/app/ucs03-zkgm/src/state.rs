@@ -1,9 +1,10 @@
-use cosmwasm_std::{Addr, Uint256};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin, Empty, Uint256};
 use cw_storage_plus::{Item, Map};
 use ibc_union_spec::types::Packet;
 use unionlabs::primitives::Bytes;
 
-use crate::msg::Config;
+use crate::msg::{ChannelPause, Config, RateLimit, RateLimitFlow};
 
 // TODO: Remove? Replace with IBC_HOST? Only the ibc_host field is read
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -20,3 +21,24 @@ pub const EXECUTING_PACKET: Item<Packet> = Item::new("executing_packet");
 pub const EXECUTION_ACK: Item<Bytes> = Item::new("execution_ack");
 
 pub const HASH_TO_FOREIGN_TOKEN: Map<String, Bytes> = Map::new("hash_to_foreign_token");
+
+/// The address allowed to pause channels (next to the admin).
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+
+pub const CHANNEL_PAUSE: Map<u32, ChannelPause> = Map::new("channel_pause");
+
+pub const RATE_LIMIT: Map<(u32, String), RateLimit> = Map::new("rate_limit");
+
+pub const RATE_LIMIT_FLOW: Map<(u32, String), RateLimitFlow> = Map::new("rate_limit_flow");
+
+/// Native nfts escrowed on a channel, keyed by (channel, collection, token id).
+pub const NFT_ESCROW: Map<(u32, String, String), Empty> = Map::new("nft_escrow");
+
+#[cw_serde]
+pub struct RelayerFeeEscrow {
+    pub payer: Addr,
+    pub fee: Coin,
+}
+
+/// Relayer fees escrowed at `Transfer`, keyed by (source channel, hash of the packet data and timeouts).
+pub const RELAYER_FEE: Map<(u32, String), RelayerFeeEscrow> = Map::new("relayer_fee");
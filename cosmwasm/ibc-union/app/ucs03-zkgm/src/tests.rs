@@ -0,0 +1,845 @@
+use alloy::sol_types::SolValue;
+use cosmwasm_std::{
+    coin, from_json,
+    testing::{message_info, mock_dependencies, mock_env, MockApi},
+    to_json_binary, Addr, BankMsg, Coin, ContractResult, CosmosMsg, Deps, DepsMut, QuerierResult,
+    Response, SystemResult, Uint256, WasmMsg, WasmQuery,
+};
+use ibc_union_msg::{module::IbcUnionMsg, msg::MsgSendPacket};
+use ibc_union_spec::{path::COMMITMENT_MAGIC, types::Packet};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use ucs03_zkgm_token_minter_api::{cw721, LocalTokenMsg, MetadataResponse, NonFungibleTokenMsg};
+use unionlabs::primitives::H256;
+
+use crate::{
+    com::{
+        Ack, FungibleAssetOrderAck, FILL_TYPE_MARKETMAKER, FILL_TYPE_PROTOCOL, TAG_ACK_FAILURE,
+        TAG_ACK_SUCCESS,
+    },
+    contract::{execute, query},
+    msg::{
+        ChannelBalance, ChannelBalancesResponse, ChannelPause, Config, ExecuteMsg, QueryMsg,
+        RateLimit, TokenOriginResponse,
+    },
+    state::{
+        CHANNEL_BALANCE, CONFIG, HASH_TO_FOREIGN_TOKEN, NFT_ESCROW, TOKEN_MINTER, TOKEN_ORIGIN,
+    },
+    ContractError,
+};
+
+const ADMIN: &str = "admin";
+const IBC_HOST: &str = "ibchost";
+const MINTER: &str = "minter";
+const SENDER: &str = "sender";
+const RELAYER: &str = "relayer";
+const DENOM: &str = "muno";
+const FEE_DENOM: &str = "ufee";
+const COLLECTION: &str = "collection";
+const TOKEN_ID: &str = "1";
+const CHANNEL_ID: u32 = 1;
+
+/// Creates a mock address from a given string.
+/// Addresses are prefixed with the default [`MockApi`] prefix.
+fn mock_addr(address_seed: impl Into<String>) -> Addr {
+    let mock_api = MockApi::default();
+    mock_api.addr_make(&Into::<String>::into(address_seed))
+}
+
+fn setup(deps: DepsMut) {
+    CONFIG
+        .save(
+            deps.storage,
+            &Config {
+                admin: mock_addr(ADMIN),
+                ibc_host: mock_addr(IBC_HOST),
+                token_minter_code_id: 1,
+            },
+        )
+        .unwrap();
+    TOKEN_MINTER.save(deps.storage, &mock_addr(MINTER)).unwrap();
+}
+
+/// Answers the cw721 queries of a collection in which `owner` owns [`TOKEN_ID`].
+fn cw721_query_handler(owner: Addr) -> impl Fn(&WasmQuery) -> QuerierResult + 'static {
+    move |msg| match msg {
+        WasmQuery::Smart { msg, .. } => {
+            let res = match from_json(msg).unwrap() {
+                cw721::QueryMsg::ContractInfo {} => json!({ "name": "Union", "symbol": "UNI" }),
+                cw721::QueryMsg::NftInfo { .. } => json!({ "token_uri": null, "extension": {} }),
+                cw721::QueryMsg::OwnerOf { .. } => json!({ "owner": owner, "approvals": [] }),
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        }
+        _ => panic!("Only smart queries should be possible now. Adjust this based on your needs."),
+    }
+}
+
+fn transfer_nft(
+    deps: DepsMut,
+    sender: &Addr,
+    quote_token: &[u8],
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let res = execute(
+        deps,
+        mock_env(),
+        message_info(sender, &[]),
+        ExecuteMsg::TransferNft {
+            channel_id: CHANNEL_ID,
+            receiver: b"receiver".to_vec().into(),
+            collection: mock_addr(COLLECTION).into_string(),
+            token_id: TOKEN_ID.to_owned(),
+            quote_token: quote_token.to_vec().into(),
+            timeout_height: 0,
+            timeout_timestamp: 1,
+            salt: H256::default(),
+        },
+    )?;
+    Ok(res.messages.into_iter().map(|msg| msg.msg).collect())
+}
+
+/// Decodes a message sent to the minter.
+fn minter_msg(msg: &CosmosMsg) -> ucs03_zkgm_token_minter_api::ExecuteMsg {
+    match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => {
+            assert_eq!(contract_addr, mock_addr(MINTER).as_str());
+            from_json::<ucs03_zkgm_token_minter_api::ExecuteMsg>(msg).unwrap()
+        }
+        msg => panic!("not a minter message: {msg:?}"),
+    }
+}
+
+#[test]
+fn transfer_nft_escrows_native_nft() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier
+        .update_wasm(cw721_query_handler(mock_addr(SENDER)));
+
+    let msgs = transfer_nft(deps.as_mut(), &mock_addr(SENDER), b"wrapped").unwrap();
+
+    assert_eq!(msgs.len(), 2);
+    assert_eq!(
+        minter_msg(&msgs[0]),
+        NonFungibleTokenMsg::Escrow {
+            from: mock_addr(SENDER).into_string(),
+            collection: mock_addr(COLLECTION).into_string(),
+            token_id: TOKEN_ID.to_owned(),
+            recipient: mock_addr(MINTER).into_string(),
+        }
+        .into()
+    );
+    assert!(NFT_ESCROW.has(
+        &deps.storage,
+        (
+            CHANNEL_ID,
+            mock_addr(COLLECTION).into_string(),
+            TOKEN_ID.to_owned()
+        )
+    ));
+}
+
+#[test]
+fn transfer_nft_burns_wrapped_nft() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier
+        .update_wasm(cw721_query_handler(mock_addr(SENDER)));
+    TOKEN_ORIGIN
+        .save(
+            &mut deps.storage,
+            mock_addr(COLLECTION).into_string(),
+            &Uint256::from(CHANNEL_ID),
+        )
+        .unwrap();
+    HASH_TO_FOREIGN_TOKEN
+        .save(
+            &mut deps.storage,
+            mock_addr(COLLECTION).into_string(),
+            &b"0xcollection".to_vec().into(),
+        )
+        .unwrap();
+
+    let msgs = transfer_nft(deps.as_mut(), &mock_addr(SENDER), b"0xcollection").unwrap();
+
+    assert_eq!(msgs.len(), 2);
+    assert_eq!(
+        minter_msg(&msgs[0]),
+        NonFungibleTokenMsg::Burn {
+            from: mock_addr(SENDER).into_string(),
+            collection: mock_addr(COLLECTION).into_string(),
+            token_id: TOKEN_ID.to_owned(),
+        }
+        .into()
+    );
+    assert!(!NFT_ESCROW.has(
+        &deps.storage,
+        (
+            CHANNEL_ID,
+            mock_addr(COLLECTION).into_string(),
+            TOKEN_ID.to_owned()
+        )
+    ));
+}
+
+#[test]
+fn transfer_nft_not_owner() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    // The owner approved zkgm, but someone else tries to transfer its nft.
+    deps.querier
+        .update_wasm(cw721_query_handler(mock_addr("owner")));
+
+    assert_eq!(
+        transfer_nft(deps.as_mut(), &mock_addr(SENDER), b"wrapped"),
+        Err(ContractError::NotNftOwner {
+            collection: mock_addr(COLLECTION).into_string(),
+            token_id: TOKEN_ID.to_owned(),
+        })
+    );
+    assert!(!NFT_ESCROW.has(
+        &deps.storage,
+        (
+            CHANNEL_ID,
+            mock_addr(COLLECTION).into_string(),
+            TOKEN_ID.to_owned()
+        )
+    ));
+}
+
+/// Answers the metadata queries of the minter.
+fn minter_query_handler() -> impl Fn(&WasmQuery) -> QuerierResult + 'static {
+    move |msg| match msg {
+        WasmQuery::Smart { msg, .. } => {
+            let res = match from_json(msg).unwrap() {
+                ucs03_zkgm_token_minter_api::QueryMsg::Metadata { .. } => {
+                    to_json_binary(&MetadataResponse {
+                        name: "Union".to_owned(),
+                        symbol: "UNO".to_owned(),
+                        decimals: 6,
+                    })
+                }
+                msg => panic!("should not be called: {:?}", msg),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        }
+        _ => panic!("Only smart queries should be possible now. Adjust this based on your needs."),
+    }
+}
+
+/// Transfers 100 [`DENOM`], returning the sent packet.
+fn transfer(
+    deps: DepsMut,
+    timeout_timestamp: u64,
+    relayer_fee: Option<Coin>,
+) -> Result<Packet, ContractError> {
+    let funds = [coin(100, DENOM)]
+        .into_iter()
+        .chain(relayer_fee.clone())
+        .collect::<Vec<_>>();
+    let res = execute(
+        deps,
+        mock_env(),
+        message_info(&mock_addr(SENDER), &funds),
+        ExecuteMsg::Transfer {
+            channel_id: CHANNEL_ID,
+            receiver: b"receiver".to_vec().into(),
+            base_token: DENOM.to_owned(),
+            base_amount: 100u128.into(),
+            quote_token: b"wrapped".to_vec().into(),
+            quote_amount: 100u128.into(),
+            timeout_height: 0,
+            timeout_timestamp,
+            salt: H256::default(),
+            relayer_fee,
+        },
+    )?;
+    Ok(sent_packet(&res.messages.last().unwrap().msg))
+}
+
+/// Decodes the packet sent to the ibc host.
+fn sent_packet(msg: &CosmosMsg) -> Packet {
+    match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) if contract_addr == mock_addr(IBC_HOST).as_str() => match from_json(msg).unwrap() {
+            ibc_union_msg::msg::ExecuteMsg::PacketSend(MsgSendPacket {
+                source_channel,
+                timeout_height,
+                timeout_timestamp,
+                data,
+            }) => Packet {
+                source_channel_id: source_channel,
+                destination_channel_id: 2,
+                data,
+                timeout_height,
+                timeout_timestamp,
+            },
+            msg => panic!("not a packet send: {msg:?}"),
+        },
+        msg => panic!("not a packet send: {msg:?}"),
+    }
+}
+
+fn ibc_host_execute(deps: DepsMut, msg: IbcUnionMsg) -> Vec<CosmosMsg> {
+    execute(
+        deps,
+        mock_env(),
+        message_info(&mock_addr(IBC_HOST), &[]),
+        ExecuteMsg::IbcUnionMsg(msg),
+    )
+    .unwrap()
+    .messages
+    .into_iter()
+    .map(|msg| msg.msg)
+    .collect()
+}
+
+fn bank_send(to_address: &str, amount: Coin) -> CosmosMsg {
+    BankMsg::Send {
+        to_address: to_address.to_owned(),
+        amount: vec![amount],
+    }
+    .into()
+}
+
+#[test]
+fn relayer_fee_paid_to_recv_relayer_on_ack() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    let packet = transfer(deps.as_mut(), 1, Some(coin(10, FEE_DENOM))).unwrap();
+
+    let ack = Ack {
+        tag: TAG_ACK_SUCCESS,
+        inner_ack: FungibleAssetOrderAck {
+            fill_type: FILL_TYPE_PROTOCOL,
+            market_maker: mock_addr("payee").as_bytes().to_vec().into(),
+        }
+        .abi_encode_params()
+        .into(),
+    }
+    .abi_encode_params();
+    let msgs = ibc_host_execute(
+        deps.as_mut(),
+        IbcUnionMsg::OnAcknowledgementPacket {
+            packet,
+            acknowledgement: ack.into(),
+            relayer: mock_addr(RELAYER).into_string(),
+        },
+    );
+
+    assert_eq!(
+        msgs,
+        vec![bank_send(mock_addr("payee").as_str(), coin(10, FEE_DENOM))]
+    );
+}
+
+#[test]
+fn relayer_fee_paid_to_market_maker_on_ack() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    let packet = transfer(deps.as_mut(), 1, Some(coin(10, FEE_DENOM))).unwrap();
+
+    let ack = Ack {
+        tag: TAG_ACK_SUCCESS,
+        inner_ack: FungibleAssetOrderAck {
+            fill_type: FILL_TYPE_MARKETMAKER,
+            market_maker: mock_addr("maker").as_bytes().to_vec().into(),
+        }
+        .abi_encode_params()
+        .into(),
+    }
+    .abi_encode_params();
+    let msgs = ibc_host_execute(
+        deps.as_mut(),
+        IbcUnionMsg::OnAcknowledgementPacket {
+            packet,
+            acknowledgement: ack.into(),
+            relayer: mock_addr(RELAYER).into_string(),
+        },
+    );
+
+    // The market maker relayed the packet, filling the order itself.
+    assert_eq!(msgs.len(), 2);
+    assert_eq!(
+        minter_msg(&msgs[0]),
+        LocalTokenMsg::Unescrow {
+            denom: DENOM.to_owned(),
+            recipient: mock_addr("maker").into_string(),
+            amount: 100u128.into(),
+        }
+        .into()
+    );
+    assert_eq!(
+        msgs[1],
+        bank_send(mock_addr("maker").as_str(), coin(10, FEE_DENOM))
+    );
+}
+
+#[test]
+fn relayer_fee_paid_to_ack_relayer_on_failure_ack() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    let packet = transfer(deps.as_mut(), 1, Some(coin(10, FEE_DENOM))).unwrap();
+
+    let ack = Ack {
+        tag: TAG_ACK_FAILURE,
+        inner_ack: Default::default(),
+    }
+    .abi_encode_params();
+    let msgs = ibc_host_execute(
+        deps.as_mut(),
+        IbcUnionMsg::OnAcknowledgementPacket {
+            packet,
+            acknowledgement: ack.into(),
+            relayer: mock_addr(RELAYER).into_string(),
+        },
+    );
+
+    // The transfer is refunded, the packet has been relayed nonetheless.
+    assert_eq!(msgs.len(), 2);
+    assert_eq!(
+        minter_msg(&msgs[0]),
+        LocalTokenMsg::Unescrow {
+            denom: DENOM.to_owned(),
+            recipient: mock_addr(SENDER).into_string(),
+            amount: 100u128.into(),
+        }
+        .into()
+    );
+    assert_eq!(
+        msgs[1],
+        bank_send(mock_addr(RELAYER).as_str(), coin(10, FEE_DENOM))
+    );
+}
+
+#[test]
+fn no_relayer_fee_for_nft_order() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier
+        .update_wasm(cw721_query_handler(mock_addr(SENDER)));
+
+    let msgs = transfer_nft(deps.as_mut(), &mock_addr(SENDER), b"wrapped").unwrap();
+    let packet = sent_packet(msgs.last().unwrap());
+
+    let ack = Ack {
+        tag: TAG_ACK_SUCCESS,
+        inner_ack: Default::default(),
+    }
+    .abi_encode_params();
+    let msgs = ibc_host_execute(
+        deps.as_mut(),
+        IbcUnionMsg::OnAcknowledgementPacket {
+            packet,
+            acknowledgement: ack.into(),
+            relayer: mock_addr(RELAYER).into_string(),
+        },
+    );
+
+    // Only `Transfer` escrows a relayer fee, the nft stays escrowed.
+    assert!(msgs.is_empty());
+}
+
+#[test]
+fn relayer_fee_refunded_on_timeout() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    let packet = transfer(deps.as_mut(), 1, Some(coin(10, FEE_DENOM))).unwrap();
+
+    let msgs = ibc_host_execute(
+        deps.as_mut(),
+        IbcUnionMsg::OnTimeoutPacket {
+            packet,
+            relayer: mock_addr(RELAYER).into_string(),
+        },
+    );
+
+    assert_eq!(msgs.len(), 2);
+    assert_eq!(
+        msgs[1],
+        bank_send(mock_addr(SENDER).as_str(), coin(10, FEE_DENOM))
+    );
+}
+
+#[test]
+fn relayer_fee_not_overwritten() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    transfer(deps.as_mut(), 1, Some(coin(10, FEE_DENOM))).unwrap();
+
+    assert_eq!(
+        transfer(deps.as_mut(), 1, Some(coin(10, FEE_DENOM))),
+        Err(ContractError::RelayerFeeAlreadyEscrowed)
+    );
+    // A different timeout is a different packet.
+    transfer(deps.as_mut(), 2, Some(coin(10, FEE_DENOM))).unwrap();
+}
+
+fn execute_as(deps: DepsMut, sender: &str, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    execute(deps, mock_env(), message_info(&mock_addr(sender), &[]), msg)
+}
+
+fn set_channel_pause(
+    deps: DepsMut,
+    sender: &str,
+    channel_id: u32,
+    pause: ChannelPause,
+) -> Result<Response, ContractError> {
+    execute_as(
+        deps,
+        sender,
+        ExecuteMsg::SetChannelPause { channel_id, pause },
+    )
+}
+
+fn set_rate_limit(deps: DepsMut, max_outflow: u128, max_inflow: u128) {
+    execute_as(
+        deps,
+        ADMIN,
+        ExecuteMsg::SetRateLimit {
+            channel_id: CHANNEL_ID,
+            denom: DENOM.to_owned(),
+            rate_limit: Some(RateLimit {
+                window_seconds: 3600,
+                max_outflow: max_outflow.into(),
+                max_inflow: max_inflow.into(),
+            }),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn only_guardian_or_admin_can_pause() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+
+    let pause = ChannelPause {
+        send: true,
+        recv: false,
+    };
+
+    assert_eq!(
+        set_channel_pause(deps.as_mut(), "guardian", CHANNEL_ID, pause.clone()),
+        Err(ContractError::OnlyGuardian)
+    );
+    assert_eq!(
+        execute_as(
+            deps.as_mut(),
+            "guardian",
+            ExecuteMsg::SetGuardian {
+                guardian: mock_addr("guardian"),
+            },
+        ),
+        Err(ContractError::OnlyAdmin)
+    );
+
+    execute_as(
+        deps.as_mut(),
+        ADMIN,
+        ExecuteMsg::SetGuardian {
+            guardian: mock_addr("guardian"),
+        },
+    )
+    .unwrap();
+
+    set_channel_pause(deps.as_mut(), "guardian", CHANNEL_ID, pause.clone()).unwrap();
+    set_channel_pause(deps.as_mut(), ADMIN, CHANNEL_ID, pause).unwrap();
+    assert_eq!(
+        set_channel_pause(deps.as_mut(), SENDER, CHANNEL_ID, ChannelPause::default()),
+        Err(ContractError::OnlyGuardian)
+    );
+}
+
+#[test]
+fn transfer_send_paused() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    set_channel_pause(
+        deps.as_mut(),
+        ADMIN,
+        CHANNEL_ID,
+        ChannelPause {
+            send: true,
+            recv: false,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        transfer(deps.as_mut(), 1, None),
+        Err(ContractError::SendPaused {
+            channel_id: CHANNEL_ID
+        })
+    );
+
+    // Pausing receives only doesn't affect sends.
+    set_channel_pause(
+        deps.as_mut(),
+        ADMIN,
+        CHANNEL_ID,
+        ChannelPause {
+            send: false,
+            recv: true,
+        },
+    )
+    .unwrap();
+
+    transfer(deps.as_mut(), 1, None).unwrap();
+}
+
+#[test]
+fn execute_packet_recv_paused() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    let packet = transfer(deps.as_mut(), 1, None).unwrap();
+
+    set_channel_pause(
+        deps.as_mut(),
+        ADMIN,
+        packet.destination_channel_id,
+        ChannelPause {
+            send: false,
+            recv: true,
+        },
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let info = message_info(&env.contract.address, &[]);
+    // The error is turned into a failure acknowledgement in the reply of the execution.
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::ExecutePacket {
+                packet: packet.clone(),
+                relayer: mock_addr(RELAYER),
+                relayer_msg: Default::default(),
+            },
+        ),
+        Err(ContractError::RecvPaused {
+            channel_id: packet.destination_channel_id
+        })
+    );
+}
+
+#[test]
+fn transfer_rate_limited() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    set_rate_limit(deps.as_mut(), 250, 0);
+
+    transfer(deps.as_mut(), 1, None).unwrap();
+    transfer(deps.as_mut(), 2, None).unwrap();
+    assert_eq!(
+        transfer(deps.as_mut(), 3, None),
+        Err(ContractError::RateLimitExceeded {
+            channel_id: CHANNEL_ID,
+            denom: DENOM.to_owned(),
+            amount: 100u128.into(),
+            remaining: 50u128.into(),
+        })
+    );
+
+    // Removing the rate limit lifts it.
+    execute_as(
+        deps.as_mut(),
+        ADMIN,
+        ExecuteMsg::SetRateLimit {
+            channel_id: CHANNEL_ID,
+            denom: DENOM.to_owned(),
+            rate_limit: None,
+        },
+    )
+    .unwrap();
+    transfer(deps.as_mut(), 3, None).unwrap();
+}
+
+#[test]
+fn rate_limit_outflow_refunded_on_timeout() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    set_rate_limit(deps.as_mut(), 100, 0);
+
+    let packet = transfer(deps.as_mut(), 1, None).unwrap();
+    assert!(matches!(
+        transfer(deps.as_mut(), 2, None),
+        Err(ContractError::RateLimitExceeded { .. })
+    ));
+
+    ibc_host_execute(
+        deps.as_mut(),
+        IbcUnionMsg::OnTimeoutPacket {
+            packet,
+            relayer: mock_addr(RELAYER).into_string(),
+        },
+    );
+
+    transfer(deps.as_mut(), 2, None).unwrap();
+}
+
+fn query_as<T: DeserializeOwned>(deps: Deps, msg: QueryMsg) -> T {
+    from_json(query(deps, mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn query_channel_balance() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+    deps.querier.update_wasm(minter_query_handler());
+
+    let channel_balance = |deps: Deps| {
+        query_as::<Uint256>(
+            deps,
+            QueryMsg::ChannelBalance {
+                channel_id: CHANNEL_ID,
+                denom: DENOM.to_owned(),
+            },
+        )
+    };
+
+    assert_eq!(channel_balance(deps.as_ref()), Uint256::zero());
+
+    transfer(deps.as_mut(), 1, None).unwrap();
+
+    assert_eq!(channel_balance(deps.as_ref()), Uint256::from(100u128));
+}
+
+#[test]
+fn query_channel_balances_paginated() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+
+    for (channel_id, denom, balance) in [
+        (CHANNEL_ID, "a", 1u128),
+        (CHANNEL_ID, "b", 2),
+        (CHANNEL_ID, "c", 3),
+        (CHANNEL_ID + 1, "d", 4),
+    ] {
+        CHANNEL_BALANCE
+            .save(
+                &mut deps.storage,
+                (channel_id, denom.to_owned()),
+                &balance.into(),
+            )
+            .unwrap();
+    }
+
+    let channel_balances = |start_after: Option<&str>| {
+        query_as::<ChannelBalancesResponse>(
+            deps.as_ref(),
+            QueryMsg::ChannelBalances {
+                channel_id: CHANNEL_ID,
+                start_after: start_after.map(ToOwned::to_owned),
+                limit: Some(2),
+            },
+        )
+        .balances
+    };
+    let balance = |denom: &str, balance: u128| ChannelBalance {
+        denom: denom.to_owned(),
+        balance: balance.into(),
+    };
+
+    assert_eq!(channel_balances(None), [balance("a", 1), balance("b", 2)]);
+    assert_eq!(channel_balances(Some("b")), [balance("c", 3)]);
+    assert!(channel_balances(Some("c")).is_empty());
+}
+
+#[test]
+fn query_token_origin() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+
+    TOKEN_ORIGIN
+        .save(
+            &mut deps.storage,
+            "wrapped".to_owned(),
+            &Uint256::from(CHANNEL_ID),
+        )
+        .unwrap();
+    HASH_TO_FOREIGN_TOKEN
+        .save(
+            &mut deps.storage,
+            "wrapped".to_owned(),
+            &b"0xtoken".to_vec().into(),
+        )
+        .unwrap();
+
+    let token_origin = |denom: &str| {
+        query_as::<Option<TokenOriginResponse>>(
+            deps.as_ref(),
+            QueryMsg::TokenOrigin {
+                denom: denom.to_owned(),
+            },
+        )
+    };
+
+    assert_eq!(
+        token_origin("wrapped"),
+        Some(TokenOriginResponse {
+            path: Uint256::from(CHANNEL_ID),
+            foreign_token: b"0xtoken".to_vec().into(),
+        })
+    );
+    assert_eq!(token_origin(DENOM), None);
+}
+
+#[test]
+fn query_packet_in_flight() {
+    let mut deps = mock_dependencies();
+    setup(deps.as_mut());
+
+    let in_flight = H256::new([1; 32]);
+    // The ibc host only has a commitment for the packet that is in flight.
+    deps.querier.update_wasm(move |msg| match msg {
+        WasmQuery::Smart { contract_addr, msg } => {
+            assert_eq!(contract_addr, mock_addr(IBC_HOST).as_str());
+            let res = match from_json(msg).unwrap() {
+                ibc_union_msg::query::QueryMsg::GetBatchPackets {
+                    channel_id: CHANNEL_ID,
+                    batch_hash,
+                } => (batch_hash == in_flight).then_some(COMMITMENT_MAGIC),
+                _ => panic!("should not be called"),
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        }
+        _ => panic!("Only smart queries should be possible now. Adjust this based on your needs."),
+    });
+
+    let packet_in_flight = |packet_hash| {
+        query_as::<bool>(
+            deps.as_ref(),
+            QueryMsg::PacketInFlight {
+                channel_id: CHANNEL_ID,
+                packet_hash,
+            },
+        )
+    };
+
+    assert!(packet_in_flight(in_flight));
+    assert!(!packet_in_flight(H256::new([2; 32])));
+}
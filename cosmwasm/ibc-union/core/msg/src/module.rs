@@ -2,6 +2,7 @@ use ibc_union_spec::types::Packet;
 use unionlabs_primitives::Bytes;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields, rename_all = "snake_case")]
 pub enum IbcUnionMsg {
     OnChannelOpenInit {
@@ -57,6 +58,7 @@ pub enum IbcUnionMsg {
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields, rename_all = "snake_case")]
 pub enum ExecuteMsg {
     IbcUnionMsg(IbcUnionMsg),
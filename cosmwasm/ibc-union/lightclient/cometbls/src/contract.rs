@@ -9,6 +9,13 @@ use unionlabs_cosmwasm_upgradable::UpgradeMsg;
 
 use crate::client::CometblsLightClient;
 
+/// With the `mock-zkp-verifier` feature, zero knowledge proofs are not verified. This is only meant for end-to-end tests
+/// against a mock prover, and must never be deployed on a live network.
+#[cfg(not(feature = "mock-zkp-verifier"))]
+type LightClient = CometblsLightClient;
+#[cfg(feature = "mock-zkp-verifier")]
+type LightClient = CometblsLightClient<crate::zkp_verifier::MockZKPVerifier>;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: ()) -> StdResult<Response> {
     panic!("this contract cannot be instantiated directly, but must be migrated from an existing instantiated contract.");
@@ -16,7 +23,7 @@ pub fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: ()) -> StdResult<Respo
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    ibc_union_light_client::query::<CometblsLightClient>(deps, env, msg).map_err(Into::into)
+    ibc_union_light_client::query::<LightClient>(deps, env, msg).map_err(Into::into)
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -27,7 +34,7 @@ pub fn migrate(
     deps: DepsMut,
     _env: Env,
     msg: UpgradeMsg<InitMsg, MigrateMsg>,
-) -> Result<Response, IbcClientError<CometblsLightClient>> {
+) -> Result<Response, IbcClientError<LightClient>> {
     msg.run(
         deps,
         |deps, init_msg| {
@@ -53,11 +53,53 @@ pub enum WrappedTokenMsg {
     },
 }
 
+#[cw_serde]
+pub struct CollectionMetadata {
+    pub name: String,
+    pub symbol: String,
+}
+
+/// Messages to handle cw721 collections, both native (escrowed) and wrapped (minted).
+#[cw_serde]
+pub enum NonFungibleTokenMsg {
+    CreateCollection {
+        metadata: CollectionMetadata,
+        path: Binary,
+        channel: u32,
+        token: Binary,
+    },
+    Mint {
+        collection: String,
+        token_id: String,
+        token_uri: Option<String>,
+        owner: String,
+    },
+    /// Burn an nft owned by `from`, which approved `ucs03-zkgm`.
+    Burn {
+        from: String,
+        collection: String,
+        token_id: String,
+    },
+    /// Transfer an nft owned by `from`, which approved `ucs03-zkgm`, to `recipient`.
+    Escrow {
+        from: String,
+        collection: String,
+        token_id: String,
+        recipient: String,
+    },
+    Unescrow {
+        collection: String,
+        token_id: String,
+        recipient: String,
+    },
+}
+
 #[cw_serde]
 #[derive(Enumorph)]
 pub enum ExecuteMsg {
     Wrapped(WrappedTokenMsg),
     Local(LocalTokenMsg),
+    NonFungible(NonFungibleTokenMsg),
 }
 
 #[cw_serde]
@@ -72,6 +114,12 @@ pub enum QueryMsg {
         channel: u32,
         token: Binary,
     },
+    /// Predict the address of a wrapped cw721 collection, returns a [`PredictWrappedTokenResponse`].
+    PredictWrappedCollection {
+        path: String,
+        channel: u32,
+        token: Binary,
+    },
 }
 
 #[cw_serde]
@@ -85,3 +133,65 @@ pub struct MetadataResponse {
 pub struct PredictWrappedTokenResponse {
     pub wrapped_token: String,
 }
+
+/// The subset of the cw721 interface used to escrow, mint and burn nfts.
+pub mod cw721 {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::Empty;
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub name: String,
+        pub symbol: String,
+        pub minter: String,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        TransferNft {
+            recipient: String,
+            token_id: String,
+        },
+        Mint {
+            token_id: String,
+            owner: String,
+            token_uri: Option<String>,
+            extension: Empty,
+        },
+        Burn {
+            token_id: String,
+        },
+    }
+
+    #[cw_serde]
+    pub enum QueryMsg {
+        ContractInfo {},
+        NftInfo {
+            token_id: String,
+        },
+        OwnerOf {
+            token_id: String,
+            include_expired: Option<bool>,
+        },
+    }
+
+    // Responses ignore unknown fields, collections can extend them (ie. the nft extension).
+    #[derive(Clone, Debug, PartialEq, cosmwasm_schema::serde::Deserialize)]
+    #[serde(crate = "::cosmwasm_schema::serde")]
+    pub struct ContractInfoResponse {
+        pub name: String,
+        pub symbol: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, cosmwasm_schema::serde::Deserialize)]
+    #[serde(crate = "::cosmwasm_schema::serde")]
+    pub struct NftInfoResponse {
+        pub token_uri: Option<String>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, cosmwasm_schema::serde::Deserialize)]
+    #[serde(crate = "::cosmwasm_schema::serde")]
+    pub struct OwnerOfResponse {
+        pub owner: String,
+    }
+}
@@ -0,0 +1,552 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+use async_sqlite::rusqlite::{self, params, OptionalExtension, Transaction, TransactionBehavior};
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbuseConfig {
+    /// Header set by the reverse proxy in front of drip containing the ip of the client, such as
+    /// `cf-connecting-ip` or `x-forwarded-for`. If not set, the peer address of the connection is used.
+    pub trusted_proxy_header: Option<String>,
+    /// The window for the per ip and per subnet limits.
+    pub window_seconds: u32,
+    pub max_requests_per_ip: Option<u32>,
+    pub max_requests_per_subnet: Option<u32>,
+    pub ipv4_subnet_prefix: u8,
+    pub ipv6_subnet_prefix: u8,
+    /// Maximum number of requests per chain id per (UTC) day. Can be overridden at runtime with the
+    /// `setDailyBudget` mutation.
+    pub daily_budgets: HashMap<String, u32>,
+}
+
+impl Default for AbuseConfig {
+    fn default() -> Self {
+        Self {
+            trusted_proxy_header: None,
+            window_seconds: 24 * 60 * 60,
+            max_requests_per_ip: None,
+            max_requests_per_subnet: None,
+            ipv4_subnet_prefix: 24,
+            ipv6_subnet_prefix: 64,
+            daily_budgets: HashMap::new(),
+        }
+    }
+}
+
+impl AbuseConfig {
+    fn subnet(&self, ip: IpAddr) -> Cidr {
+        let prefix = match ip {
+            IpAddr::V4(_) => self.ipv4_subnet_prefix,
+            IpAddr::V6(_) => self.ipv6_subnet_prefix,
+        };
+        Cidr::new(ip, prefix)
+    }
+}
+
+/// The ip of the client that made the request, attached to each GraphQL request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl ClientIp {
+    /// Reads the ip from the trusted proxy header, falling back to the peer address. For headers containing a
+    /// list (`x-forwarded-for`), the last entry is used, as that is the one appended by the trusted proxy.
+    pub fn from_request(
+        headers: &HeaderMap,
+        peer: SocketAddr,
+        trusted_proxy_header: Option<&str>,
+    ) -> Self {
+        let Some(header) = trusted_proxy_header else {
+            return Self(peer.ip());
+        };
+
+        let ip = headers
+            .get(header)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit(',').next())
+            .and_then(|ip| ip.trim().parse().ok());
+
+        match ip {
+            Some(ip) => Self(ip),
+            None => {
+                warn!(%header, %peer, "missing or invalid proxy header, using the peer address");
+                Self(peer.ip())
+            }
+        }
+    }
+}
+
+/// An ip range, such as `10.0.0.0/8`. A single ip parses as a range containing only that ip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Creates the range of `prefix` bits containing `ip`. The prefix is capped at the length of the address.
+    pub fn new(ip: IpAddr, prefix: u8) -> Self {
+        match ip {
+            IpAddr::V4(ip) => {
+                let prefix = prefix.min(32);
+                let mask = u32::MAX.checked_shl((32 - prefix).into()).unwrap_or(0);
+                Self {
+                    addr: IpAddr::V4((u32::from(ip) & mask).into()),
+                    prefix,
+                }
+            }
+            IpAddr::V6(ip) => {
+                let prefix = prefix.min(128);
+                let mask = u128::MAX.checked_shl((128 - prefix).into()).unwrap_or(0);
+                Self {
+                    addr: IpAddr::V6((u128::from(ip) & mask).into()),
+                    prefix,
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => {
+                Cidr::new(ip, self.prefix) == *self
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (
+                addr,
+                Some(
+                    prefix
+                        .parse::<u8>()
+                        .map_err(|err| format!("invalid prefix in {s}: {err}"))?,
+                ),
+            ),
+            None => (s, None),
+        };
+
+        let addr = addr
+            .parse::<IpAddr>()
+            .map_err(|err| format!("invalid ip in {s}: {err}"))?;
+
+        let max_prefix = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+
+        match prefix {
+            Some(prefix) if prefix > max_prefix => Err(format!("invalid prefix in {s}")),
+            prefix => Ok(Cidr::new(addr, prefix.unwrap_or(max_prefix))),
+        }
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// Why a request was refused by [`check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    Denied,
+    IpLimit,
+    SubnetLimit,
+    BudgetExhausted,
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Denied => f.write_str("denied"),
+            Refusal::IpLimit | Refusal::SubnetLimit => f.write_str("ratelimited"),
+            Refusal::BudgetExhausted => f.write_str("daily budget exhausted"),
+        }
+    }
+}
+
+/// Adds the columns and tables used by the abuse controls. Databases created before these existed are
+/// migrated in place.
+pub fn create_tables(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    for column in ["ip", "subnet"] {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('requests') WHERE name = ?1",
+            [column],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE requests ADD COLUMN {column} TEXT"),
+                (),
+            )?;
+        }
+    }
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS denylist (
+            entry TEXT PRIMARY KEY,
+            reason TEXT,
+            time TEXT
+        )",
+        (),
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS daily_budgets (
+            chain_id TEXT PRIMARY KEY,
+            budget INTEGER NOT NULL
+        )",
+        (),
+    )?;
+
+    Ok(())
+}
+
+/// Checks a request against the denylist, the ip and subnet limits and the daily budget of the chain.
+/// The ip and subnet limits are only applied if `ip_limits` is set.
+pub fn check(
+    conn: &rusqlite::Connection,
+    config: &AbuseConfig,
+    chain_id: &str,
+    address: &str,
+    ip: Option<IpAddr>,
+    ip_limits: bool,
+) -> rusqlite::Result<Option<Refusal>> {
+    let mut statement = conn.prepare_cached("SELECT entry FROM denylist")?;
+    let entries = statement
+        .query_map((), |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let denied = entries.iter().any(|entry| {
+        entry.eq_ignore_ascii_case(address)
+            || ip.is_some_and(|ip| entry.parse::<Cidr>().is_ok_and(|cidr| cidr.contains(ip)))
+    });
+    if denied {
+        return Ok(Some(Refusal::Denied));
+    }
+
+    if let Some(ip) = ip.filter(|_| ip_limits) {
+        let window = format!("-{} seconds", config.window_seconds);
+
+        if let Some(max) = config.max_requests_per_ip {
+            let count: u32 = conn.query_row(
+                "SELECT COUNT(*) FROM requests WHERE ip = ?1 AND time > datetime('now', ?2)",
+                (ip.to_string(), &window),
+                |row| row.get(0),
+            )?;
+            if count >= max {
+                return Ok(Some(Refusal::IpLimit));
+            }
+        }
+
+        if let Some(max) = config.max_requests_per_subnet {
+            let count: u32 = conn.query_row(
+                "SELECT COUNT(*) FROM requests WHERE subnet = ?1 AND time > datetime('now', ?2)",
+                (config.subnet(ip).to_string(), &window),
+                |row| row.get(0),
+            )?;
+            if count >= max {
+                return Ok(Some(Refusal::SubnetLimit));
+            }
+        }
+    }
+
+    let budget: Option<u32> = conn
+        .query_row(
+            "SELECT budget FROM daily_budgets WHERE chain_id = ?1",
+            [chain_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .or_else(|| config.daily_budgets.get(chain_id).copied());
+
+    if let Some(budget) = budget {
+        // failed requests don't count towards the budget
+        let count: u32 = conn.query_row(
+            "SELECT COUNT(*) FROM requests
+             WHERE chain_id = ?1
+             AND time >= date('now')
+             AND (tx_hash IS NULL OR tx_hash NOT LIKE 'ERROR%')",
+            [chain_id],
+            |row| row.get(0),
+        )?;
+        if count >= budget {
+            return Ok(Some(Refusal::BudgetExhausted));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Checks a request with [`check`] and, if it is not refused, inserts it and returns its id. Both happen in a
+/// single `BEGIN IMMEDIATE` transaction, so that concurrent requests can't all pass the limits before any of them
+/// is inserted.
+pub fn check_and_insert(
+    conn: &rusqlite::Connection,
+    config: &AbuseConfig,
+    chain_id: &str,
+    denom: &str,
+    address: &str,
+    ip: Option<IpAddr>,
+    ip_limits: bool,
+) -> rusqlite::Result<Result<i64, Refusal>> {
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+
+    if let Some(refusal) = check(&tx, config, chain_id, address, ip, ip_limits)? {
+        return Ok(Err(refusal));
+    }
+
+    let (ip, subnet) = request_columns(config, ip);
+
+    let id = tx
+        .prepare_cached(
+            "INSERT INTO requests (chain_id, denom, address, time, ip, subnet) VALUES (?, ?, ?, datetime('now'), ?, ?) RETURNING id",
+        )?
+        .query_row(params![chain_id, denom, address, ip, subnet], |row| {
+            row.get(0)
+        })?;
+
+    tx.commit()?;
+
+    Ok(Ok(id))
+}
+
+/// The `ip` and `subnet` columns stored with a request.
+fn request_columns(config: &AbuseConfig, ip: Option<IpAddr>) -> (Option<String>, Option<String>) {
+    (
+        ip.map(|ip| ip.to_string()),
+        ip.map(|ip| config.subnet(ip).to_string()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    const CHAIN_ID: &str = "union-devnet-1";
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    fn cidr(cidr: &str) -> Cidr {
+        cidr.parse().unwrap()
+    }
+
+    #[test]
+    fn cidr_parse() {
+        assert_eq!(cidr("10.0.0.0/8").to_string(), "10.0.0.0/8");
+        assert_eq!(cidr("10.1.2.3/8").to_string(), "10.0.0.0/8");
+        assert_eq!(cidr("10.1.2.3").to_string(), "10.1.2.3/32");
+        assert_eq!(cidr("2001:db8::1/32").to_string(), "2001:db8::/32");
+        assert_eq!(cidr("2001:db8::1").to_string(), "2001:db8::1/128");
+        assert_eq!(cidr("0.0.0.0/0").to_string(), "0.0.0.0/0");
+
+        "10.0.0.0/33".parse::<Cidr>().unwrap_err();
+        "2001:db8::/129".parse::<Cidr>().unwrap_err();
+        "10.0.0.0/x".parse::<Cidr>().unwrap_err();
+        "union1abc".parse::<Cidr>().unwrap_err();
+    }
+
+    #[test]
+    fn cidr_contains() {
+        assert!(cidr("10.0.0.0/8").contains(ip("10.255.0.1")));
+        assert!(!cidr("10.0.0.0/8").contains(ip("11.0.0.1")));
+        assert!(cidr("10.1.2.3").contains(ip("10.1.2.3")));
+        assert!(!cidr("10.1.2.3").contains(ip("10.1.2.4")));
+        assert!(cidr("0.0.0.0/0").contains(ip("192.168.1.1")));
+        assert!(cidr("2001:db8::/32").contains(ip("2001:db8:1::1")));
+        assert!(!cidr("2001:db8::/32").contains(ip("2001:db9::1")));
+        // families never match each other
+        assert!(!cidr("0.0.0.0/0").contains(ip("::1")));
+        assert!(!cidr("::/0").contains(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn client_ip_from_request() {
+        let peer: SocketAddr = "192.168.1.1:1234".parse().unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("1.1.1.1, 2.2.2.2"),
+        );
+
+        // the header is only trusted if configured
+        assert_eq!(
+            ClientIp::from_request(&headers, peer, None),
+            ClientIp(peer.ip())
+        );
+        // the last entry is appended by the trusted proxy
+        assert_eq!(
+            ClientIp::from_request(&headers, peer, Some("x-forwarded-for")),
+            ClientIp(ip("2.2.2.2"))
+        );
+        // missing header
+        assert_eq!(
+            ClientIp::from_request(&headers, peer, Some("cf-connecting-ip")),
+            ClientIp(peer.ip())
+        );
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("1.1.1.1, nope"));
+        assert_eq!(
+            ClientIp::from_request(&headers, peer, Some("x-forwarded-for")),
+            ClientIp(peer.ip())
+        );
+    }
+
+    fn conn() -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE requests (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                chain_id TEXT NOT NULL,
+                denom TEXT NOT NULL,
+                address TEXT NOT NULL,
+                time TEXT,
+                tx_hash TEXT
+            )",
+            (),
+        )
+        .unwrap();
+        create_tables(&conn).unwrap();
+        conn
+    }
+
+    fn request(
+        conn: &rusqlite::Connection,
+        config: &AbuseConfig,
+        address: &str,
+        ip: &str,
+    ) -> Result<i64, Refusal> {
+        check_and_insert(
+            conn,
+            config,
+            CHAIN_ID,
+            "muno",
+            address,
+            Some(self::ip(ip)),
+            true,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn ip_and_subnet_limits() {
+        let conn = conn();
+        let config = AbuseConfig {
+            max_requests_per_ip: Some(2),
+            max_requests_per_subnet: Some(3),
+            ..Default::default()
+        };
+
+        request(&conn, &config, "union1a", "10.0.0.1").unwrap();
+        request(&conn, &config, "union1b", "10.0.0.1").unwrap();
+        assert_eq!(
+            request(&conn, &config, "union1c", "10.0.0.1"),
+            Err(Refusal::IpLimit)
+        );
+
+        request(&conn, &config, "union1c", "10.0.0.2").unwrap();
+        assert_eq!(
+            request(&conn, &config, "union1d", "10.0.0.3"),
+            Err(Refusal::SubnetLimit)
+        );
+
+        // other subnets are not affected
+        request(&conn, &config, "union1d", "10.0.1.1").unwrap();
+
+        // refused requests are not inserted
+        let count: u32 = conn
+            .query_row("SELECT COUNT(*) FROM requests", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 4);
+
+        // the limits are not applied to the bypass secret
+        check_and_insert(
+            &conn,
+            &config,
+            CHAIN_ID,
+            "muno",
+            "union1e",
+            Some(ip("10.0.0.1")),
+            false,
+        )
+        .unwrap()
+        .unwrap();
+    }
+
+    #[test]
+    fn denylist() {
+        let conn = conn();
+        let config = AbuseConfig::default();
+
+        for entry in ["union1denied", "10.0.0.0/8"] {
+            conn.execute("INSERT INTO denylist (entry) VALUES (?1)", [entry])
+                .unwrap();
+        }
+
+        assert_eq!(
+            request(&conn, &config, "UNION1DENIED", "192.168.1.1"),
+            Err(Refusal::Denied)
+        );
+        assert_eq!(
+            request(&conn, &config, "union1a", "10.1.2.3"),
+            Err(Refusal::Denied)
+        );
+        request(&conn, &config, "union1a", "192.168.1.1").unwrap();
+    }
+
+    #[test]
+    fn daily_budget() {
+        let conn = conn();
+        let config = AbuseConfig {
+            daily_budgets: [(CHAIN_ID.to_owned(), 2)].into(),
+            ..Default::default()
+        };
+
+        let id = request(&conn, &config, "union1a", "10.0.0.1").unwrap();
+        request(&conn, &config, "union1b", "10.0.0.2").unwrap();
+        assert_eq!(
+            request(&conn, &config, "union1c", "10.0.0.3"),
+            Err(Refusal::BudgetExhausted)
+        );
+
+        // failed requests don't count towards the budget
+        conn.execute(
+            "UPDATE requests SET tx_hash = 'ERROR: failed' WHERE id = ?1",
+            [id],
+        )
+        .unwrap();
+        request(&conn, &config, "union1c", "10.0.0.3").unwrap();
+
+        // the budget set at runtime overrides the configured one
+        conn.execute(
+            "INSERT INTO daily_budgets (chain_id, budget) VALUES (?1, 3)",
+            [CHAIN_ID],
+        )
+        .unwrap();
+        request(&conn, &config, "union1d", "10.0.0.4").unwrap();
+        assert_eq!(
+            request(&conn, &config, "union1e", "10.0.0.5"),
+            Err(Refusal::BudgetExhausted)
+        );
+    }
+}
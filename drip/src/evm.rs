@@ -0,0 +1,400 @@
+use alloy::{
+    network::{AnyNetwork, EthereumWallet, ReceiptResponse},
+    primitives::{Address, U256},
+    providers::{fillers::RecommendedFillers, DynProvider, Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use unionlabs::primitives::{H160, H256};
+
+use crate::SendRequest;
+
+alloy::sol! {
+    #[sol(rpc)]
+    contract Multicall3 {
+        struct Call3Value {
+            address target;
+            bool allowFailure;
+            uint256 value;
+            bytes callData;
+        }
+
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3Value(Call3Value[] calldata calls)
+            public
+            payable
+            returns (Result[] memory returnData);
+    }
+
+    #[sol(rpc)]
+    contract ERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvmChain {
+    /// The EIP-155 chain id, checked against the rpc on startup.
+    pub id: String,
+    pub rpc_url: String,
+    pub signer: H256,
+    /// A [Multicall3](https://github.com/mds1/multicall) deployment, used to batch native transfers.
+    pub multicall_address: H160,
+    pub coins: Vec<EvmCoin>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvmCoin {
+    pub denom: String,
+    /// The amount in the smallest unit of the token (wei for the native token).
+    pub amount: u64,
+    /// The ERC-20 contract of this coin. The native token is sent if this is not set.
+    #[serde(default)]
+    pub erc20_address: Option<H160>,
+}
+
+impl EvmChain {
+    pub fn coin(&self, denom: &str) -> Option<&EvmCoin> {
+        self.coins.iter().find(|coin| coin.denom == denom)
+    }
+
+    /// Validates a `0x` prefixed address, verifying the EIP-55 checksum if it is mixed case. The address is
+    /// returned lowercased, so that ratelimits can't be bypassed by changing the case.
+    pub fn normalize_address(address: &str) -> Result<String, String> {
+        let Some(hex) = address.strip_prefix("0x") else {
+            return Err(format!(
+                "invalid address {address}, expected a 0x prefixed address"
+            ));
+        };
+
+        let is_mixed_case = hex.chars().any(|c| c.is_ascii_uppercase())
+            && hex.chars().any(|c| c.is_ascii_lowercase());
+
+        let parsed = if is_mixed_case {
+            Address::parse_checksummed(address, None).map_err(|err| err.to_string())?
+        } else {
+            address
+                .parse::<Address>()
+                .map_err(|err| format!("invalid address {address}: {err}"))?
+        };
+
+        Ok(parsed.to_string().to_lowercase())
+    }
+
+    /// Native transfers are batched in a single multicall, ERC-20 transfers are sent one per transaction.
+    ///
+    /// Batching ERC-20 transfers through the multicall would require the faucet to approve the multicall
+    /// contract, which anyone can call, so these are sent directly from the faucet account instead.
+    pub fn batch(&self, mut requests: Vec<SendRequest>) -> Vec<SendRequest> {
+        let Some(first) = requests.first() else {
+            return requests;
+        };
+
+        match self.coin(&first.denom) {
+            Some(EvmCoin {
+                erc20_address: Some(_),
+                ..
+            }) => {
+                requests.truncate(1);
+                requests
+            }
+            _ => {
+                let denom = first.denom.clone();
+                requests.retain(|req| req.denom == denom);
+                requests
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EvmChainClient {
+    pub chain: EvmChain,
+    pub provider: DynProvider<AnyNetwork>,
+}
+
+impl EvmChainClient {
+    pub async fn new(chain: &EvmChain) -> Self {
+        let signer = PrivateKeySigner::from_bytes(&(*chain.signer.get()).into())
+            .expect("signer is a valid private key");
+
+        let provider = DynProvider::new(
+            ProviderBuilder::new()
+                .network::<AnyNetwork>()
+                .filler(AnyNetwork::recommended_fillers())
+                .wallet(EthereumWallet::new(signer))
+                .on_builtin(&chain.rpc_url)
+                .await
+                .unwrap(),
+        );
+
+        let chain_id = provider.get_chain_id().await.unwrap();
+
+        // Check if we are connected to a chain with the correct chain_id
+        assert_eq!(
+            chain_id.to_string(),
+            chain.id,
+            "rpc_url {} is not for chain {}",
+            chain.rpc_url,
+            chain.id
+        );
+
+        Self {
+            chain: chain.clone(),
+            provider,
+        }
+    }
+
+    /// Sends a batch as selected by [`EvmChain::batch`], returning the result of every request: the hash of the
+    /// transaction, or the reason the transfer failed.
+    ///
+    /// Native transfers are simulated first. A transfer that fails (for instance to a contract rejecting the native
+    /// token) is marked as failed and left out of the transaction, since Multicall3 keeps the value of a failed call.
+    /// The other transfers are sent without being allowed to fail: if one fails regardless (the state changed since
+    /// the simulation), the transaction reverts and the batch is retried, instead of being reported as sent.
+    pub async fn send(
+        &self,
+        requests: &[SendRequest],
+    ) -> anyhow::Result<Vec<(i64, Result<H256, String>)>> {
+        let Some(first) = requests.first() else {
+            anyhow::bail!("empty batch");
+        };
+
+        let coin = self
+            .chain
+            .coin(&first.denom)
+            .ok_or_else(|| anyhow::anyhow!("unknown denom {}", first.denom))?;
+
+        let mut results = vec![];
+
+        let (pending, sent) = match coin.erc20_address {
+            Some(erc20_address) => {
+                let [req] = requests else {
+                    anyhow::bail!("erc20 transfers can't be batched");
+                };
+
+                let pending = ERC20::new(erc20_address.into(), &self.provider)
+                    .transfer(req.receiver.parse()?, U256::from(req.amount))
+                    .send()
+                    .await?;
+
+                (pending, vec![req])
+            }
+            None => {
+                let multicall =
+                    Multicall3::new(self.chain.multicall_address.into(), &self.provider);
+
+                let calls = requests
+                    .iter()
+                    .map(|req| {
+                        Ok(Multicall3::Call3Value {
+                            target: req.receiver.parse()?,
+                            allowFailure: true,
+                            value: U256::from(req.amount),
+                            callData: Default::default(),
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+
+                let simulated = multicall
+                    .aggregate3Value(calls.clone())
+                    .value(calls.iter().map(|call| call.value).sum::<U256>())
+                    .call()
+                    .await?
+                    .returnData;
+
+                let mut sent = vec![];
+                let mut sent_calls = vec![];
+                for ((req, call), result) in requests.iter().zip(calls).zip(simulated) {
+                    if result.success {
+                        sent.push(req);
+                        sent_calls.push(Multicall3::Call3Value {
+                            allowFailure: false,
+                            ..call
+                        });
+                    } else {
+                        warn!(?req, "transfer failed in simulation");
+                        results.push((req.id, Err(format!("transfer to {} failed", req.receiver))));
+                    }
+                }
+
+                if sent_calls.is_empty() {
+                    return Ok(results);
+                }
+
+                let total_amount = sent_calls.iter().map(|call| call.value).sum::<U256>();
+
+                let pending = multicall
+                    .aggregate3Value(sent_calls)
+                    .value(total_amount)
+                    .send()
+                    .await?;
+
+                (pending, sent)
+            }
+        };
+
+        let receipt = pending.get_receipt().await?;
+        let tx_hash = <H256>::from(receipt.transaction_hash);
+
+        if !receipt.status() {
+            anyhow::bail!("transaction {tx_hash} reverted");
+        }
+
+        info!(
+            requests = ?sent,
+            %tx_hash,
+            gas_used = %receipt.gas_used,
+            "submitted transfer"
+        );
+
+        results.extend(sent.into_iter().map(|req| (req.id, Ok(tx_hash))));
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        node_bindings::{Anvil, AnvilInstance},
+        primitives::{hex, Bytes},
+    };
+
+    use super::*;
+
+    /// A stand-in for Multicall3's `aggregate3Value`, since anvil has no Multicall3 deployed: sends the value of
+    /// every call (ignoring the call data), reverts if a call that is not allowed to fail fails, and returns the
+    /// `Result[]` with empty return data.
+    const MULTICALL_RUNTIME: &str = "60243560206000528060205260005b81811015610071578060051b604401356044016000600060006000846040013585355af18082602001351761004257600080fd5b8360051b8360600201808460051b6040015260400190815260408160200152600090604001525060010161000e565b5060071b6040016000f3";
+
+    /// Reverts on every call, rejecting native transfers.
+    const REJECT_RUNTIME: &str = "60006000fd";
+
+    const MULTICALL: Address = Address::repeat_byte(0xca);
+
+    async fn client(anvil: &AnvilInstance) -> EvmChainClient {
+        let client = EvmChainClient::new(&EvmChain {
+            id: anvil.chain_id().to_string(),
+            rpc_url: anvil.endpoint(),
+            signer: H256::new(anvil.keys()[0].to_bytes().into()),
+            multicall_address: H160::new(MULTICALL.into()),
+            coins: vec![EvmCoin {
+                denom: "wei".to_owned(),
+                amount: 1000,
+                erc20_address: None,
+            }],
+        })
+        .await;
+
+        set_code(&client, MULTICALL, MULTICALL_RUNTIME).await;
+
+        client
+    }
+
+    async fn set_code(client: &EvmChainClient, address: Address, code: &str) {
+        client
+            .provider
+            .raw_request::<_, ()>(
+                "anvil_setCode".into(),
+                (address, Bytes::from(hex::decode(code).unwrap())),
+            )
+            .await
+            .unwrap();
+    }
+
+    fn request(id: i64, receiver: Address) -> SendRequest {
+        SendRequest {
+            id,
+            receiver: receiver.to_string(),
+            denom: "wei".to_owned(),
+            amount: 1000,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil"]
+    async fn send_native_batch() {
+        let anvil = Anvil::new().spawn();
+        let client = client(&anvil).await;
+
+        let (alice, bob) = (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
+
+        let results = client
+            .send(&[request(1, alice), request(2, bob)])
+            .await
+            .unwrap();
+
+        let [(1, Ok(first)), (2, Ok(second))] = results.as_slice() else {
+            panic!("unexpected results {results:?}");
+        };
+        assert_eq!(first, second);
+
+        assert_eq!(
+            client.provider.get_balance(alice).await.unwrap(),
+            U256::from(1000)
+        );
+        assert_eq!(
+            client.provider.get_balance(bob).await.unwrap(),
+            U256::from(1000)
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil"]
+    async fn send_native_marks_failed_transfer() {
+        let anvil = Anvil::new().spawn();
+        let client = client(&anvil).await;
+
+        let (alice, rejecting) = (Address::repeat_byte(0x11), Address::repeat_byte(0x33));
+        set_code(&client, rejecting, REJECT_RUNTIME).await;
+
+        let results = client
+            .send(&[request(1, rejecting), request(2, alice)])
+            .await
+            .unwrap();
+
+        let [(1, Err(_)), (2, Ok(_))] = results.as_slice() else {
+            panic!("unexpected results {results:?}");
+        };
+
+        assert_eq!(
+            client.provider.get_balance(alice).await.unwrap(),
+            U256::from(1000)
+        );
+        assert_eq!(
+            client.provider.get_balance(rejecting).await.unwrap(),
+            U256::ZERO
+        );
+        // the value of the failed transfer is not sent to the multicall contract
+        assert_eq!(
+            client.provider.get_balance(MULTICALL).await.unwrap(),
+            U256::ZERO
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil"]
+    async fn send_native_all_failed() {
+        let anvil = Anvil::new().spawn();
+        let client = client(&anvil).await;
+
+        let rejecting = Address::repeat_byte(0x33);
+        set_code(&client, rejecting, REJECT_RUNTIME).await;
+
+        let block = client.provider.get_block_number().await.unwrap();
+
+        let results = client.send(&[request(1, rejecting)]).await.unwrap();
+
+        let [(1, Err(_))] = results.as_slice() else {
+            panic!("unexpected results {results:?}");
+        };
+        // no transaction is sent
+        assert_eq!(client.provider.get_block_number().await.unwrap(), block);
+    }
+}
@@ -1,14 +1,17 @@
 use std::{
-    collections::HashMap, ffi::OsString, fmt, fs::read_to_string, rc::Rc, sync::Arc, time::Duration,
+    collections::HashMap, ffi::OsString, fmt, fs::read_to_string, net::SocketAddr, rc::Rc,
+    sync::Arc, time::Duration,
 };
 
 use async_graphql::{http::GraphiQLSource, *};
-use async_graphql_axum::GraphQL;
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use async_sqlite::{
     rusqlite::{self, params, OptionalExtension},
     JournalMode, Pool, PoolBuilder,
 };
 use axum::{
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
     response::{self, IntoResponse},
     routing::get,
     Router,
@@ -22,7 +25,8 @@ use cosmos_client::{
     TxClient,
 };
 use prost::{Message, Name};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use subtle::ConstantTimeEq;
 use tokio::net::TcpListener;
 use tracing::{debug, error, info, info_span, warn};
 use tracing_subscriber::EnvFilter;
@@ -31,6 +35,13 @@ use unionlabs::{
     ErrorReporter,
 };
 
+use crate::{
+    abuse::{AbuseConfig, ClientIp},
+    evm::{EvmChain, EvmChainClient},
+};
+
+mod abuse;
+mod evm;
 mod turnstile;
 
 const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
@@ -82,6 +93,7 @@ async fn main() {
             )",
             (), // empty list of parameters.
         )?;
+        abuse::create_tables(conn)?;
         Ok(())
     })
     .await
@@ -92,10 +104,12 @@ async fn main() {
         Mutation {
             ratelimit_seconds: config.ratelimit_seconds,
             chains: config.clone().chains,
+            abuse: config.abuse.clone(),
         },
         EmptySubscription,
     )
     .data(pool.clone())
+    .data(config.admin_secret.clone().map(AdminSecret))
     .data(MaxRequestPolls(config.max_request_polls))
     .data(config.bypass_secret.clone().map(CaptchaBypassSecret))
     .data(MaxPaginatedResponses(max_paginated_responses))
@@ -105,7 +119,7 @@ async fn main() {
     let config = config.clone();
 
     for chain in config.chains.clone() {
-        let _chain_polling_span = info_span!("chain_polling", chain_id = chain.id).entered();
+        let _chain_polling_span = info_span!("chain_polling", chain_id = chain.id()).entered();
         info!("spawning worker for chain");
         let pool = pool.clone();
         tokio::spawn(async move {
@@ -127,12 +141,12 @@ async fn main() {
                                 let mut stmt = conn
                                     .prepare_cached(
                                         "SELECT id, denom, address FROM requests 
-                                     WHERE tx_hash IS NULL AND chain_id IS ?1 LIMIT ?2",
+                                     WHERE tx_hash IS NULL AND chain_id IS ?1 ORDER BY id LIMIT ?2",
                                     )
                                     .expect("SQL statement is valid");
 
                                 let mut rows = stmt
-                                    .query((&chain.id, batch_size as i64))
+                                    .query((chain.id(), batch_size as i64))
                                     .expect("can't query rows");
 
                                 let mut requests = vec![];
@@ -143,9 +157,7 @@ async fn main() {
                                     let receiver: String =
                                         row.get(2).expect("could not read address");
 
-                                    let Some(coin) =
-                                        chain.coins.iter().find(|coin| coin.denom == denom)
-                                    else {
+                                    let Some(amount) = chain.amount(&denom) else {
                                         error!(
                                         %denom,
                                         "dropping request for unknown denom");
@@ -156,11 +168,11 @@ async fn main() {
                                         id,
                                         receiver,
                                         denom,
-                                        amount: coin.amount,
+                                        amount,
                                     });
                                 }
 
-                                Ok(requests)
+                                Ok(chain.batch(requests))
                             })
                             .await
                             .expect("pool error");
@@ -173,13 +185,17 @@ async fn main() {
                         let mut i = 0;
 
                         // try sending batch 5 times
-                        let result = loop {
+                        let results = loop {
                             let send_res = chain_client.send(&requests).await;
 
                             match send_res {
                                 Err(err) => {
                                     if i >= 5 {
-                                        break format!("ERROR: {}", ErrorReporter(&*err));
+                                        let result = format!("ERROR: {}", ErrorReporter(&*err));
+                                        break requests
+                                            .iter()
+                                            .map(|req| (req.id, result.clone()))
+                                            .collect::<Vec<_>>();
                                     }
                                     warn!(
                                         err = %ErrorReporter(&*err),
@@ -188,16 +204,15 @@ async fn main() {
                                     );
                                     i += 1;
                                 }
-                                // this will be displayed to users, print the hash in the same way that cosmos sdk does
-                                Ok(tx_hash) => {
-                                    break tx_hash
-                                        .into_encoding::<HexUnprefixed>()
-                                        .to_string()
-                                        .to_uppercase()
-                                }
+                                Ok(results) => break results,
                             };
                         };
 
+                        let mut ids_by_result = HashMap::<String, Vec<i64>>::new();
+                        for (id, result) in results {
+                            ids_by_result.entry(result).or_default().push(id);
+                        }
+
                         pool.conn(move |conn| {
                             debug!("loading vtab array module required for `IN (1,42,76,...)`");
                             rusqlite::vtab::array::load_module(conn)
@@ -210,18 +225,19 @@ async fn main() {
                                 .expect("???");
 
                             // https://docs.rs/rusqlite/latest/rusqlite/vtab/array/index.html
-                            let rows_modified = stmt
-                                .execute((
-                                    &result,
-                                    Rc::new(
-                                        requests
-                                            .iter()
-                                            .map(|req| req.id)
-                                            .map(rusqlite::types::Value::from)
-                                            .collect::<Vec<rusqlite::types::Value>>(),
-                                    ),
-                                ))
-                                .expect("can't query rows");
+                            let mut rows_modified = 0;
+                            for (result, ids) in ids_by_result {
+                                rows_modified += stmt
+                                    .execute((
+                                        &result,
+                                        Rc::new(
+                                            ids.into_iter()
+                                                .map(rusqlite::types::Value::from)
+                                                .collect::<Vec<rusqlite::types::Value>>(),
+                                        ),
+                                    ))
+                                    .expect("can't query rows");
+                            }
 
                             info!(rows_modified, "updated requests");
 
@@ -244,12 +260,43 @@ async fn main() {
         });
     }
 
-    let router = Router::new().route("/", get(graphiql).post_service(GraphQL::new(schema)));
+    let router = Router::new()
+        .route("/", get(graphiql).post(graphql))
+        .with_state(AppState {
+            schema,
+            trusted_proxy_header: config.abuse.trusted_proxy_header.clone(),
+        });
 
     info!("starting server");
-    axum::serve(TcpListener::bind("0.0.0.0:8000").await.unwrap(), router)
+    axum::serve(
+        TcpListener::bind("0.0.0.0:8000").await.unwrap(),
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
+}
+
+type DripSchema = Schema<Query, Mutation, EmptySubscription>;
+
+#[derive(Clone)]
+struct AppState {
+    schema: DripSchema,
+    trusted_proxy_header: Option<String>,
+}
+
+async fn graphql(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let client_ip = ClientIp::from_request(&headers, peer, state.trusted_proxy_header.as_deref());
+
+    state
+        .schema
+        .execute(request.into_inner().data(client_ip))
         .await
-        .unwrap();
+        .into()
 }
 
 #[derive(Debug, Parser)]
@@ -294,10 +341,97 @@ pub struct Config {
     pub max_request_polls: u32,
     #[serde(default)]
     pub ratelimit_seconds: u32,
+    #[serde(default)]
+    pub abuse: AbuseConfig,
+    /// Secret required by the admin mutations. The admin mutations are disabled if not set.
+    #[serde(default)]
+    pub admin_secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Chain {
+    Cosmos(CosmosChain),
+    Evm(EvmChain),
+}
+
+impl<'de> Deserialize<'de> for Chain {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(tag = "type", rename_all = "snake_case")]
+        enum TaggedChain {
+            Cosmos(CosmosChain),
+            Evm(EvmChain),
+        }
+
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+
+        // configs from before evm chains were supported have no `type`, these are all cosmos chains
+        if let Some(object) = value.as_object_mut() {
+            object.entry("type").or_insert_with(|| "cosmos".into());
+        }
+
+        Ok(
+            match TaggedChain::deserialize(value).map_err(serde::de::Error::custom)? {
+                TaggedChain::Cosmos(chain) => Chain::Cosmos(chain),
+                TaggedChain::Evm(chain) => Chain::Evm(chain),
+            },
+        )
+    }
+}
+
+impl Chain {
+    pub fn id(&self) -> &str {
+        match self {
+            Chain::Cosmos(chain) => &chain.id,
+            Chain::Evm(chain) => &chain.id,
+        }
+    }
+
+    /// The amount sent per request for `denom`, or `None` if the denom is not supported on this chain.
+    pub fn amount(&self, denom: &str) -> Option<u64> {
+        match self {
+            Chain::Cosmos(chain) => chain
+                .coins
+                .iter()
+                .find(|coin| coin.denom == denom)
+                .map(|coin| coin.amount),
+            Chain::Evm(chain) => chain.coin(denom).map(|coin| coin.amount),
+        }
+    }
+
+    /// Validates that `address` is an address on this chain, returning it in its canonical form.
+    pub fn normalize_address(&self, address: &str) -> Result<String, String> {
+        match self {
+            Chain::Cosmos(chain) => {
+                let (hrp, _bz) = subtle_encoding::bech32::Bech32::lower_case()
+                    .decode(address)
+                    .map_err(|err| err.to_string())?;
+
+                if hrp != chain.bech32_prefix {
+                    return Err(format!(
+                        "incorrect bech32 prefix, expected `{}` but found `{hrp}`",
+                        chain.bech32_prefix
+                    ));
+                }
+
+                Ok(address.to_owned())
+            }
+            Chain::Evm(_) => EvmChain::normalize_address(address),
+        }
+    }
+
+    /// Selects the requests of the queue that will be sent in the next transaction.
+    fn batch(&self, requests: Vec<SendRequest>) -> Vec<SendRequest> {
+        match self {
+            Chain::Cosmos(_) => requests,
+            Chain::Evm(chain) => chain.batch(requests),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Chain {
+pub struct CosmosChain {
     pub id: String,
     pub bech32_prefix: String,
     pub rpc_url: String,
@@ -316,15 +450,61 @@ pub struct Coin {
 pub struct MaxRequestPolls(pub u32);
 // pub struct Bech32Prefix(pub String);
 pub struct CaptchaBypassSecret(pub String);
+pub struct AdminSecret(pub String);
 
 #[derive(Clone)]
-struct ChainClient {
-    pub chain: Chain,
-    pub cosmos_ctx: Arc<TxClient<LocalSigner, Rpc, GasConfig>>,
+enum ChainClient {
+    Cosmos(CosmosChainClient),
+    Evm(EvmChainClient),
 }
 
 impl ChainClient {
     pub async fn new(chain: &Chain) -> Self {
+        match chain {
+            Chain::Cosmos(chain) => ChainClient::Cosmos(CosmosChainClient::new(chain).await),
+            Chain::Evm(chain) => ChainClient::Evm(EvmChainClient::new(chain).await),
+        }
+    }
+
+    /// Sends the batch, returning the result of every request: the transaction hash as it will be displayed to
+    /// users, or the reason the request failed.
+    async fn send(&self, requests: &Vec<SendRequest>) -> anyhow::Result<Vec<(i64, String)>> {
+        match self {
+            ChainClient::Cosmos(client) => {
+                // print the hash in the same way that cosmos sdk does
+                let tx_hash = client
+                    .send(requests)
+                    .await?
+                    .into_encoding::<HexUnprefixed>()
+                    .to_string()
+                    .to_uppercase();
+
+                Ok(requests
+                    .iter()
+                    .map(|req| (req.id, tx_hash.clone()))
+                    .collect())
+            }
+            ChainClient::Evm(client) => Ok(client
+                .send(requests)
+                .await?
+                .into_iter()
+                .map(|(id, result)| match result {
+                    Ok(tx_hash) => (id, tx_hash.to_string()),
+                    Err(err) => (id, format!("ERROR: {err}")),
+                })
+                .collect()),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct CosmosChainClient {
+    pub chain: CosmosChain,
+    pub cosmos_ctx: Arc<TxClient<LocalSigner, Rpc, GasConfig>>,
+}
+
+impl CosmosChainClient {
+    pub async fn new(chain: &CosmosChain) -> Self {
         let rpc = Rpc::new(chain.rpc_url.clone()).await.unwrap();
 
         let bech32_prefix = rpc
@@ -404,7 +584,7 @@ impl SendRequestAggregator for Vec<SendRequest> {
     }
 }
 
-impl ChainClient {
+impl CosmosChainClient {
     /// `MultiSend` to the specified addresses. Will return `None` if there are no signers available.
     async fn send(&self, requests: &Vec<SendRequest>) -> anyhow::Result<H256> {
         let agg_reqs = requests.aggregate_by_denom();
@@ -457,6 +637,20 @@ impl ChainClient {
 struct Mutation {
     ratelimit_seconds: u32,
     chains: Vec<Chain>,
+    abuse: AbuseConfig,
+}
+
+fn verify_admin_secret(ctx: &Context<'_>, admin_secret: &str) -> Result<()> {
+    match ctx.data::<Option<AdminSecret>>().unwrap() {
+        // compared in constant time, so that the secret can't be guessed from the response time
+        Some(AdminSecret(secret))
+            if bool::from(secret.as_bytes().ct_eq(admin_secret.as_bytes())) =>
+        {
+            Ok(())
+        }
+        Some(_) => Err("invalid admin secret".into()),
+        None => Err("admin mutations are disabled".into()),
+    }
 }
 
 #[derive(Debug)]
@@ -477,12 +671,12 @@ impl Mutation {
         let max_request_polls = ctx.data::<MaxRequestPolls>().unwrap();
 
         // Get chain config
-        let Some(chain) = self.chains.iter().find(|c| c.id == chain_id) else {
+        let Some(chain) = self.chains.iter().find(|c| c.id() == chain_id) else {
             return Err(format!("invalid chain_id {chain_id}").into());
         };
 
         // Ensure denom exists for chain
-        if !chain.coins.iter().any(|coin| coin.denom == denom) {
+        if chain.amount(&denom).is_none() {
             return Err(format!("invalid denom {denom}").into());
         };
 
@@ -502,18 +696,7 @@ impl Mutation {
             }
         }
 
-        match subtle_encoding::bech32::Bech32::lower_case().decode(&address) {
-            Ok((hrp, _bz)) => {
-                if hrp != chain.bech32_prefix {
-                    return Err(format!(
-                        "incorrect bech32 prefix, expected `{}` but found `{hrp}`",
-                        chain.bech32_prefix
-                    )
-                    .into());
-                }
-            }
-            Err(err) => return Err(err.into()),
-        };
+        let address = chain.normalize_address(&address)?;
 
         let db = ctx.data::<Pool>().unwrap();
 
@@ -564,15 +747,37 @@ impl Mutation {
             }
         }
 
-        let id: i64 = db
-            .conn(move |conn| {
-                let mut statement = conn.prepare_cached(
-                    "INSERT INTO requests (chain_id, denom, address, time) VALUES (?, ?, ?, datetime('now')) RETURNING id",
-                )?;
-                let id = statement.query_row([&chain_id, &denom, &address], |row| row.get(0))?;
-                Ok(id)
+        let ip = ctx.data_opt::<ClientIp>().map(|ClientIp(ip)| *ip);
+
+        let inserted = db
+            .conn({
+                let config = self.abuse.clone();
+                let chain_id = chain_id.clone();
+                let address = address.clone();
+
+                // the bypass secret is used by our own services, which would otherwise share an ip
+                move |conn| {
+                    abuse::check_and_insert(
+                        conn,
+                        &config,
+                        &chain_id,
+                        &denom,
+                        &address,
+                        ip,
+                        !allow_bypass,
+                    )
+                }
             })
             .await?;
+
+        let id = match inserted {
+            Ok(id) => id,
+            Err(refusal) => {
+                info!(%chain_id, %address, ?ip, %refusal, "refused request");
+
+                return Ok(format!("ERROR: {refusal}"));
+            }
+        };
         let mut counter = 0;
         let tx_hash = loop {
             let tx_hash: Option<String> = db
@@ -599,6 +804,81 @@ impl Mutation {
 
         Ok(tx_hash)
     }
+
+    /// Adds an address, ip or ip range (`10.0.0.0/8`) to the denylist.
+    async fn deny<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        admin_secret: String,
+        entry: String,
+        reason: Option<String>,
+    ) -> Result<bool> {
+        verify_admin_secret(ctx, &admin_secret)?;
+
+        let db = ctx.data::<Pool>().unwrap();
+
+        info!(%entry, ?reason, "adding denylist entry");
+
+        db.conn(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO denylist (entry, reason, time) VALUES (?, ?, datetime('now'))",
+                params![entry.trim(), reason],
+            )
+        })
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Removes an entry from the denylist, returning whether it was present.
+    async fn allow<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        admin_secret: String,
+        entry: String,
+    ) -> Result<bool> {
+        verify_admin_secret(ctx, &admin_secret)?;
+
+        let db = ctx.data::<Pool>().unwrap();
+
+        info!(%entry, "removing denylist entry");
+
+        let rows_modified = db
+            .conn(move |conn| conn.execute("DELETE FROM denylist WHERE entry = ?", [entry.trim()]))
+            .await?;
+
+        Ok(rows_modified > 0)
+    }
+
+    /// Overrides the daily budget of the chain from the config. Passing no budget resets it to the config.
+    async fn set_daily_budget<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        admin_secret: String,
+        chain_id: String,
+        budget: Option<u32>,
+    ) -> Result<bool> {
+        verify_admin_secret(ctx, &admin_secret)?;
+
+        if !self.chains.iter().any(|c| c.id() == chain_id) {
+            return Err(format!("invalid chain_id {chain_id}").into());
+        }
+
+        let db = ctx.data::<Pool>().unwrap();
+
+        info!(%chain_id, ?budget, "setting daily budget");
+
+        db.conn(move |conn| match budget {
+            Some(budget) => conn.execute(
+                "INSERT OR REPLACE INTO daily_budgets (chain_id, budget) VALUES (?, ?)",
+                params![chain_id, budget],
+            ),
+            None => conn.execute("DELETE FROM daily_budgets WHERE chain_id = ?", [chain_id]),
+        })
+        .await?;
+
+        Ok(true)
+    }
 }
 
 #[derive(SimpleObject)]
@@ -734,3 +1014,56 @@ impl Query {
 async fn graphiql() -> impl IntoResponse {
     response::Html(GraphiQLSource::build().endpoint("/").finish())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COSMOS_CHAIN: &str = r#"{
+        "id": "union-devnet-1",
+        "bech32_prefix": "union",
+        "rpc_url": "http://localhost:26657",
+        "gas_config": {
+            "gas_price": 1.0,
+            "gas_denom": "muno",
+            "gas_multiplier": 1.1,
+            "max_gas": 40000000
+        },
+        "signer": "0xaa820fa947beb242032a41b6dc9a8b9c37d8f5fbcda0966b1ec80335b10a7d6f",
+        "coins": [{ "denom": "muno", "amount": 13370 }],
+        "memo": "drip"
+    }"#;
+
+    fn with_type(chain: &str, ty: &str) -> String {
+        let mut chain: serde_json::Value = serde_json::from_str(chain).unwrap();
+        chain["type"] = ty.into();
+        chain.to_string()
+    }
+
+    #[test]
+    fn chain_without_type_is_cosmos() {
+        let chain: Chain = serde_json::from_str(COSMOS_CHAIN).unwrap();
+        assert!(matches!(chain, Chain::Cosmos(_)));
+    }
+
+    #[test]
+    fn chain_with_type() {
+        let chain: Chain = serde_json::from_str(&with_type(COSMOS_CHAIN, "cosmos")).unwrap();
+        assert!(matches!(chain, Chain::Cosmos(_)));
+
+        let chain: Chain = serde_json::from_str(&with_type(
+            r#"{
+                "id": "32382",
+                "rpc_url": "http://localhost:8545",
+                "signer": "0xaa820fa947beb242032a41b6dc9a8b9c37d8f5fbcda0966b1ec80335b10a7d6f",
+                "multicall_address": "0xca11bde05977b3631167028862be2a173976ca11",
+                "coins": [{ "denom": "eth", "amount": 1000 }]
+            }"#,
+            "evm",
+        ))
+        .unwrap();
+        assert!(matches!(chain, Chain::Evm(_)));
+
+        serde_json::from_str::<Chain>(&with_type(COSMOS_CHAIN, "evm")).unwrap_err();
+    }
+}
@@ -68,6 +68,10 @@ pub enum IndexerConfig {
     Tendermint(indexer::tendermint::config::Config),
     #[serde(rename = "aptos")]
     Aptos(indexer::aptos::config::Config),
+    #[serde(rename = "movement")]
+    Movement(indexer::movement::config::Config),
+    #[serde(rename = "sui")]
+    Sui(indexer::sui::config::Config),
 }
 
 impl IndexerConfig {
@@ -77,6 +81,8 @@ impl IndexerConfig {
             Self::Ethereum(cfg) => &cfg.indexer_id,
             Self::Tendermint(cfg) => &cfg.indexer_id,
             Self::Aptos(cfg) => &cfg.indexer_id,
+            Self::Movement(cfg) => &cfg.indexer_id,
+            Self::Sui(cfg) => &cfg.indexer_id,
         }
     }
 }
@@ -121,6 +127,22 @@ impl IndexerConfig {
                     .instrument(indexer_span)
                     .await
             }
+            Self::Movement(cfg) => {
+                cfg.build(db)
+                    .instrument(initializer_span)
+                    .await?
+                    .index()
+                    .instrument(indexer_span)
+                    .await
+            }
+            Self::Sui(cfg) => {
+                cfg.build(db)
+                    .instrument(initializer_span)
+                    .await?
+                    .index()
+                    .instrument(indexer_span)
+                    .await
+            }
         }
     }
 }
@@ -133,6 +133,7 @@ pub trait FetcherClient: Display + Send + Sync + Clone + Sized + 'static {
 
     async fn create(
         pg_pool: sqlx::PgPool,
+        indexer_id: IndexerId,
         join_set: &mut JoinSet<Result<(), IndexerError>>,
         context: Self::Context,
     ) -> Result<Self, IndexerError>;
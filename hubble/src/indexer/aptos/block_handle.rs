@@ -25,6 +25,9 @@ use crate::indexer::{
         },
         provider::RpcProviderId,
     },
+    packet::{
+        delete_packet_events_of_block, event::from_move_event, insert_packet_events_of_block,
+    },
 };
 
 impl BlockReferenceProvider for Block {
@@ -171,6 +174,19 @@ impl BlockHandle for AptosBlockHandle {
                 transactions.len()
             );
 
+            let packet_events = transactions
+                .iter()
+                .flat_map(|transaction| {
+                    transaction.events.iter().filter_map(|event| {
+                        from_move_event(
+                            transaction.transaction_hash.clone(),
+                            &event.typ,
+                            &event.data,
+                        )
+                    })
+                })
+                .collect_vec();
+
             insert_aptos_block(
                 tx,
                 PgBlock {
@@ -184,6 +200,8 @@ impl BlockHandle for AptosBlockHandle {
                 },
             )
             .await?;
+            insert_packet_events_of_block(tx, self.internal_chain_id, &reference, packet_events)
+                .await?;
         } else {
             trace!("{}: no matching events: ignore", reference);
         }
@@ -198,6 +216,7 @@ impl BlockHandle for AptosBlockHandle {
 
         delete_aptos_block_transactions_events(tx, self.internal_chain_id, self.reference.height)
             .await?;
+        delete_packet_events_of_block(tx, self.internal_chain_id, self.reference.height).await?;
         self.insert(tx).await?;
 
         debug!("{}: done", reference);
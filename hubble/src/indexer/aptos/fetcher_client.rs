@@ -15,7 +15,7 @@ use crate::{
     indexer::{
         api::{
             BlockHeight, BlockReferenceProvider, BlockSelection, FetchMode, FetcherClient,
-            IndexerError,
+            IndexerError, IndexerId,
         },
         aptos::{
             block_handle::{AptosBlockHandle, BlockDetails},
@@ -76,7 +76,7 @@ impl AptosFetcherClient {
             .await
     }
 
-    async fn fetch_at_height(
+    pub async fn fetch_at_height(
         &self,
         mode: FetchMode,
         provider_id: Option<RpcProviderId>,
@@ -239,10 +239,17 @@ impl FetcherClient for AptosFetcherClient {
 
     async fn create(
         pg_pool: sqlx::PgPool,
-        _join_set: &mut JoinSet<Result<(), IndexerError>>,
+        indexer_id: IndexerId,
+        join_set: &mut JoinSet<Result<(), IndexerError>>,
         context: AptosContext,
     ) -> Result<Self, IndexerError> {
-        let provider = Provider::new(context.rpc_urls);
+        let provider = Provider::new(indexer_id, context.rpc_urls);
+
+        let monitor_provider = provider.clone();
+        join_set.spawn(
+            async move { monitor_provider.monitor_heads().await }
+                .instrument(info_span!("rpc-health")),
+        );
 
         info!("fetching chain-id from node");
         let chain_id = provider
@@ -3,10 +3,10 @@ use color_eyre::eyre::Report;
 
 use super::api::IndexerError;
 
-mod block_handle;
+pub(crate) mod block_handle;
 pub mod config;
-mod context;
-mod fetcher_client;
+pub(crate) mod context;
+pub(crate) mod fetcher_client;
 mod postgres;
 mod provider;
 
@@ -8,7 +8,7 @@ use aptos_rest_client::{
 use url::Url;
 
 use crate::{
-    indexer::api::BlockHeight,
+    indexer::api::{BlockHeight, IndexerError, IndexerId},
     race_client::{RaceClient, RaceClientId, RaceClientResponse},
 };
 
@@ -50,17 +50,28 @@ impl<T> From<RaceClientResponse<T>> for RpcResult<T> {
 }
 
 impl Provider {
-    pub fn new(rpc_urls: Vec<Url>) -> Self {
+    pub fn new(indexer_id: IndexerId, rpc_urls: Vec<Url>) -> Self {
         Self {
             rpc_client: RaceClient::new(
+                indexer_id,
                 rpc_urls
                     .into_iter()
-                    .map(aptos_rest_client::Client::new)
+                    .map(|url| (url.clone(), aptos_rest_client::Client::new(url)))
                     .collect(),
             ),
         }
     }
 
+    pub async fn monitor_heads(&self) -> Result<(), IndexerError> {
+        self.rpc_client
+            .monitor_heads(|c| async move {
+                c.get_index()
+                    .await
+                    .map(|response| response.inner().block_height.into())
+            })
+            .await
+    }
+
     // RPC
     pub async fn get_index(
         &self,
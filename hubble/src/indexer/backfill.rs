@@ -0,0 +1,273 @@
+use color_eyre::eyre::{eyre, Report};
+use futures::future::try_join_all;
+use itertools::Itertools;
+use tracing::{debug, info, info_span, Instrument};
+
+use crate::indexer::{
+    api::{BlockHandle, BlockRange, FetchMode, FetcherClient, IndexerError},
+    postgres::{
+        delete_backfill_segments, get_backfill_segments, insert_backfill_segments,
+        update_backfill_segment, update_current_height, BackfillSegment,
+    },
+    BackfillConfig, HappyRangeFetcher, Indexer,
+};
+
+impl<T: FetcherClient> Indexer<T> {
+    /// Indexes a historical range using concurrent workers. The range is split in segments
+    /// (one per worker) which are persisted, together with the progress of each segment, so
+    /// that an interrupted backfill resumes where it stopped. Each block is stored in the same
+    /// transaction as the progress of its segment, so every block is indexed exactly once.
+    ///
+    /// The current height of the indexer is only updated when all segments are completed.
+    pub(crate) async fn run_backfill(
+        &self,
+        backfill_config: &BackfillConfig,
+        last_finalized: &T::BlockHandle,
+        catch_up_range: BlockRange,
+    ) -> Result<(), IndexerError> {
+        let segments = self
+            .backfill_segments(backfill_config, catch_up_range)
+            .await?;
+
+        let pending = segments
+            .into_iter()
+            .filter(|segment| segment.next_height < segment.range.end_exclusive)
+            .collect_vec();
+
+        info!("pending segments: {}", pending.len());
+
+        try_join_all(pending.into_iter().map(|segment| {
+            let span = info_span!("worker", segment = %segment.range);
+            self.run_backfill_segment(last_finalized, segment)
+                .instrument(span)
+        }))
+        .await?;
+
+        self.complete_backfill().await
+    }
+
+    /// Returns true if there is an interrupted backfill that needs to be resumed.
+    pub(crate) async fn has_backfill_segments(&self) -> Result<bool, IndexerError> {
+        let mut tx = self.pg_pool.begin().await?;
+        let segments = get_backfill_segments(&mut tx, self.indexer_id.clone()).await?;
+        tx.commit().await?;
+
+        Ok(!segments.is_empty())
+    }
+
+    /// Returns the persisted segments. When there are none, the catch up range is
+    /// split between the workers.
+    async fn backfill_segments(
+        &self,
+        backfill_config: &BackfillConfig,
+        catch_up_range: BlockRange,
+    ) -> Result<Vec<BackfillSegment>, IndexerError> {
+        let mut tx = self.pg_pool.begin().await?;
+
+        let mut segments = get_backfill_segments(&mut tx, self.indexer_id.clone()).await?;
+
+        if segments.is_empty() {
+            segments = plan_segments(catch_up_range, backfill_config.workers, self.chunk_size);
+            info!(
+                "planned segments: {}",
+                segments.iter().map(|segment| &segment.range).join(", ")
+            );
+            insert_backfill_segments(&mut tx, self.indexer_id.clone(), &segments).await?;
+        } else {
+            info!(
+                "resuming segments: {}",
+                segments
+                    .iter()
+                    .map(|segment| format!("{} (next: {})", segment.range, segment.next_height))
+                    .join(", ")
+            );
+        }
+
+        tx.commit().await?;
+
+        Ok(segments)
+    }
+
+    async fn run_backfill_segment(
+        &self,
+        last_finalized: &T::BlockHandle,
+        segment: BackfillSegment,
+    ) -> Result<(), IndexerError> {
+        let start_height = segment.range.start_inclusive;
+        let remaining: BlockRange = (segment.next_height..segment.range.end_exclusive).into();
+
+        for slice in remaining.range_chunks(self.chunk_size) {
+            info!("{slice}: handling chunk");
+
+            last_finalized
+                .fetch_range_expect_all(slice.clone(), FetchMode::Eager, |block| {
+                    self.store_backfill_block(start_height, block)
+                })
+                .instrument(info_span!("store"))
+                .await?;
+
+            info!("{slice}: handled chunk");
+        }
+
+        info!("completed");
+
+        Ok(())
+    }
+
+    async fn store_backfill_block(
+        &self,
+        start_height: u64,
+        block_handle: T::BlockHandle,
+    ) -> Result<(), Report> {
+        let reference = block_handle.reference();
+        debug!("store: {}", reference);
+
+        let mut tx = self.pg_pool.begin().await?;
+
+        block_handle.insert(&mut tx).await?;
+
+        update_backfill_segment(
+            &mut tx,
+            self.indexer_id.clone(),
+            start_height,
+            reference.height + 1,
+            reference.timestamp,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Moves the current height to the end of the backfilled range and removes the segments.
+    async fn complete_backfill(&self) -> Result<(), IndexerError> {
+        let mut tx = self.pg_pool.begin().await?;
+
+        let segments = get_backfill_segments(&mut tx, self.indexer_id.clone()).await?;
+
+        if let Some(incomplete) = segments
+            .iter()
+            .find(|segment| segment.next_height < segment.range.end_exclusive)
+        {
+            return Err(eyre!(
+                "segment {} is not completed (next: {})",
+                incomplete.range,
+                incomplete.next_height
+            )
+            .into());
+        }
+
+        if let Some(last) = segments
+            .iter()
+            .max_by_key(|segment| segment.range.end_exclusive)
+        {
+            let timestamp = last
+                .timestamp
+                .ok_or_else(|| eyre!("segment {} has no timestamp", last.range))?;
+
+            info!("completed backfill: {}", last.range.end_exclusive - 1);
+
+            update_current_height(
+                &mut tx,
+                self.indexer_id.clone(),
+                last.range.end_exclusive - 1,
+                timestamp,
+            )
+            .await?;
+        }
+
+        delete_backfill_segments(&mut tx, self.indexer_id.clone()).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Splits the range in (at most) one segment per worker. Segments are aligned to the chunk size.
+fn plan_segments(range: BlockRange, workers: usize, chunk_size: usize) -> Vec<BackfillSegment> {
+    let workers: u64 = workers.max(1).try_into().unwrap();
+    let chunk_size: u64 = chunk_size.max(1).try_into().unwrap();
+
+    let chunks = range.len().div_ceil(chunk_size);
+    let segment_size = chunks.div_ceil(workers).max(1) * chunk_size;
+
+    range
+        .range_chunks(segment_size.try_into().unwrap())
+        .map(|range| BackfillSegment {
+            next_height: range.start_inclusive,
+            range,
+            timestamp: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plan_segments;
+
+    /// Plans the segments, asserting that they cover the range exactly, without overlap, and that
+    /// they start unindexed. Returns the segments as (start_inclusive, end_exclusive).
+    fn plan(start: u64, end: u64, workers: usize, chunk_size: usize) -> Vec<(u64, u64)> {
+        let segments = plan_segments((start..end).into(), workers, chunk_size);
+
+        let mut next = start;
+        for segment in &segments {
+            assert_eq!(segment.range.start_inclusive, next, "gap or overlap");
+            assert!(segment.range.len() > 0, "empty segment");
+            assert_eq!(segment.next_height, segment.range.start_inclusive);
+            assert!(segment.timestamp.is_none());
+            next = segment.range.end_exclusive;
+        }
+        if !segments.is_empty() {
+            assert_eq!(next, end, "range not covered");
+        }
+        assert!(segments.len() <= workers.max(1));
+
+        segments
+            .into_iter()
+            .map(|segment| (segment.range.start_inclusive, segment.range.end_exclusive))
+            .collect()
+    }
+
+    #[test]
+    fn empty_range() {
+        assert_eq!(plan(100, 100, 4, 10), vec![]);
+    }
+
+    #[test]
+    fn range_smaller_than_chunk() {
+        assert_eq!(plan(100, 105, 4, 10), vec![(100, 105)]);
+    }
+
+    #[test]
+    fn more_workers_than_chunks() {
+        assert_eq!(
+            plan(100, 130, 8, 10),
+            vec![(100, 110), (110, 120), (120, 130)]
+        );
+    }
+
+    #[test]
+    fn aligned_to_chunks() {
+        assert_eq!(
+            plan(100, 180, 4, 10),
+            vec![(100, 120), (120, 140), (140, 160), (160, 180)]
+        );
+    }
+
+    #[test]
+    fn non_divisible_tail() {
+        // 10 chunks (the last one partial) over 3 workers: 4 chunks per segment
+        assert_eq!(
+            plan(100, 195, 3, 10),
+            vec![(100, 140), (140, 180), (180, 195)]
+        );
+    }
+
+    #[test]
+    fn no_workers_is_one_worker() {
+        assert_eq!(plan(100, 195, 0, 10), vec![(100, 195)]);
+    }
+}
@@ -3,13 +3,14 @@ use sqlx::PgPool;
 use unionlabs::aptos::block_info::BlockHeight;
 
 use super::dummy::{DummyContext, DummyFetcherClient};
-use crate::indexer::{api::IndexerId, FinalizerConfig, Indexer};
+use crate::indexer::{api::IndexerId, BackfillConfig, FinalizerConfig, Indexer};
 
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct Config {
     pub indexer_id: IndexerId,
     pub start_height: BlockHeight,
     pub finalizer: FinalizerConfig,
+    pub backfill: Option<BackfillConfig>,
 }
 
 impl Config {
@@ -20,6 +21,7 @@ impl Config {
             self.start_height,
             5,
             self.finalizer,
+            self.backfill,
             DummyContext { bla: 42 },
         ))
     }
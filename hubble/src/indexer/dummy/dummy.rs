@@ -9,7 +9,8 @@ use tokio::task::JoinSet;
 use tracing::{debug, info};
 
 use crate::indexer::api::{
-    BlockHandle, BlockRange, BlockReference, BlockSelection, FetchMode, FetcherClient, IndexerError,
+    BlockHandle, BlockRange, BlockReference, BlockSelection, FetchMode, FetcherClient,
+    IndexerError, IndexerId,
 };
 
 #[derive(Clone)]
@@ -139,6 +140,7 @@ impl FetcherClient for DummyFetcherClient {
 
     async fn create(
         _pg_pool: sqlx::PgPool,
+        _indexer_id: IndexerId,
         _join_set: &mut JoinSet<Result<(), IndexerError>>,
         _context: DummyContext,
     ) -> Result<Self, IndexerError> {
@@ -15,6 +15,10 @@ use crate::{
             postgres::{delete_eth_log, insert_batch_logs},
             provider::RpcProviderId,
         },
+        packet::{
+            delete_packet_events_of_block, event::from_ethereum_log,
+            insert_packet_events_of_block, PacketEvent,
+        },
     },
     postgres::{ChainId, InsertMode},
 };
@@ -58,6 +62,20 @@ pub struct EthBlockHandle {
     pub provider_id: RpcProviderId,
 }
 
+impl BlockInsert {
+    fn packet_events(&self) -> Vec<PacketEvent> {
+        self.transactions
+            .iter()
+            .flat_map(|transaction| {
+                transaction
+                    .events
+                    .iter()
+                    .filter_map(|event| from_ethereum_log(transaction.hash.clone(), &event.data))
+            })
+            .collect()
+    }
+}
+
 impl EthBlockHandle {
     async fn get_block_insert(&self) -> Result<Option<BlockInsert>, Report> {
         Ok(match self.details.clone() {
@@ -111,7 +129,15 @@ impl BlockHandle for EthBlockHandle {
                     block_to_insert.transactions.len()
                 );
 
+                let packet_events = block_to_insert.packet_events();
                 insert_batch_logs(tx, vec![block_to_insert.into()], InsertMode::Insert).await?;
+                insert_packet_events_of_block(
+                    tx,
+                    self.eth_client.chain_id.db,
+                    &reference,
+                    packet_events,
+                )
+                .await?;
             }
             None => {
                 debug!("{}: block without transactions => ignore", reference);
@@ -129,13 +155,23 @@ impl BlockHandle for EthBlockHandle {
 
         let block_to_insert = self.get_block_insert().await?;
 
+        delete_packet_events_of_block(tx, self.eth_client.chain_id.db, reference.height).await?;
+
         if let Some(block_to_insert) = block_to_insert {
             debug!(
                 "{}: block with transactions ({}) => upsert",
                 reference,
                 block_to_insert.transactions.len()
             );
+            let packet_events = block_to_insert.packet_events();
             insert_batch_logs(tx, vec![block_to_insert.into()], InsertMode::Upsert).await?;
+            insert_packet_events_of_block(
+                tx,
+                self.eth_client.chain_id.db,
+                &reference,
+                packet_events,
+            )
+            .await?;
         } else {
             debug!("{}: block without transactions => delete", reference);
             delete_eth_log(tx, self.eth_client.chain_id.db, reference.height).await?;
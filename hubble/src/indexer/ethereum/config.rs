@@ -5,7 +5,7 @@ use url::Url;
 use crate::indexer::{
     api::{BlockHeight, IndexerId},
     ethereum::{context::EthContext, fetcher_client::EthFetcherClient},
-    FinalizerConfig, Indexer,
+    BackfillConfig, FinalizerConfig, Indexer,
 };
 
 const DEFAULT_CHUNK_SIZE: usize = 200;
@@ -18,6 +18,7 @@ pub struct Config {
     pub rpc_urls: Vec<Url>,
     #[serde(default)]
     pub finalizer: FinalizerConfig,
+    pub backfill: Option<BackfillConfig>,
 }
 
 impl Config {
@@ -28,6 +29,7 @@ impl Config {
             self.start_height,
             self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
             self.finalizer,
+            self.backfill,
             EthContext {
                 rpc_urls: self.rpc_urls,
             },
@@ -16,6 +16,7 @@ use crate::{
     indexer::{
         api::{
             BlockHeight, BlockReference, BlockSelection, FetchMode, FetcherClient, IndexerError,
+            IndexerId,
         },
         ethereum::{
             block_handle::{
@@ -259,10 +260,17 @@ impl FetcherClient for EthFetcherClient {
 
     async fn create(
         pg_pool: sqlx::PgPool,
-        _join_set: &mut JoinSet<Result<(), IndexerError>>,
+        indexer_id: IndexerId,
+        join_set: &mut JoinSet<Result<(), IndexerError>>,
         context: EthContext,
     ) -> Result<Self, IndexerError> {
-        let provider = Provider::new(context.rpc_urls);
+        let provider = Provider::new(indexer_id, context.rpc_urls);
+
+        let monitor_provider = provider.clone();
+        join_set.spawn(
+            async move { monitor_provider.monitor_heads().await }
+                .instrument(info_span!("rpc-health")),
+        );
 
         info!("fetching chain-id from node");
         let chain_id = provider.get_chain_id(None).await?.response;
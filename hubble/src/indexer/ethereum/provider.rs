@@ -7,7 +7,10 @@ use alloy::{
 };
 use url::Url;
 
-use crate::race_client::{RaceClient, RaceClientId, RaceClientResponse};
+use crate::{
+    indexer::api::{IndexerError, IndexerId},
+    race_client::{RaceClient, RaceClientId, RaceClientResponse},
+};
 
 #[derive(Clone, Debug)]
 pub struct Provider {
@@ -47,14 +50,18 @@ impl<T> From<RaceClientResponse<T>> for RpcResult<T> {
 }
 
 impl Provider {
-    pub fn new(rpc_urls: Vec<Url>) -> Self {
+    pub fn new(indexer_id: IndexerId, rpc_urls: Vec<Url>) -> Self {
         Self {
             rpc_client: RaceClient::new(
+                indexer_id,
                 rpc_urls
                     .into_iter()
                     .map(|url| {
-                        DynProvider::new(
-                            ProviderBuilder::new().network::<AnyNetwork>().on_http(url),
+                        (
+                            url.clone(),
+                            DynProvider::new(
+                                ProviderBuilder::new().network::<AnyNetwork>().on_http(url),
+                            ),
                         )
                     })
                     .collect(),
@@ -62,6 +69,12 @@ impl Provider {
         }
     }
 
+    pub async fn monitor_heads(&self) -> Result<(), IndexerError> {
+        self.rpc_client
+            .monitor_heads(|c| c.get_block_number())
+            .await
+    }
+
     pub async fn get_chain_id(
         &self,
         provider_id: Option<RpcProviderId>,
@@ -75,6 +75,20 @@ impl<T: FetcherClient> Indexer<T> {
                     (next_height..last_finalized.reference().height).into();
                 info!("missing blocks: {catch_up_range}");
 
+                if let Some(backfill_config) = &self.backfill_config {
+                    if catch_up_range.len() >= backfill_config.min_blocks
+                        || self.has_backfill_segments().await?
+                    {
+                        info!("{catch_up_range}: backfilling");
+
+                        self.run_backfill(backfill_config, &last_finalized, catch_up_range)
+                            .instrument(info_span!("backfill"))
+                            .await?;
+
+                        return Ok(RunToFinalizedLoopResult::RunAgain);
+                    }
+                }
+
                 for slice in catch_up_range.range_chunks(self.chunk_size) {
                     info!("{slice}: handling chunk");
 
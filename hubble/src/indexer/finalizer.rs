@@ -1,6 +1,7 @@
-use std::cmp::min;
+use std::{cmp::min, collections::VecDeque, sync::Mutex};
 
 use color_eyre::eyre::Report;
+use serde_json::json;
 use tokio::time::sleep;
 use tracing::{debug, info, info_span, trace, warn, Instrument};
 
@@ -9,13 +10,16 @@ use super::{
     postgres::get_next_block_to_monitor,
     Indexer,
 };
-use crate::indexer::{
-    api::{BlockHandle, BlockHeight, BlockSelection, FetchMode},
-    postgres::{
-        delete_block_status, get_block_range_to_finalize, get_block_status_hash,
-        update_block_status,
+use crate::{
+    indexer::{
+        api::{BlockHandle, BlockHash, BlockHeight, BlockReference, BlockSelection, FetchMode},
+        postgres::{
+            delete_block_status, get_block_range_to_finalize, get_block_status_hash, insert_reorg,
+            notify_reorg, update_block_status,
+        },
+        HappyRangeFetcher,
     },
-    HappyRangeFetcher,
+    metrics::REORG_DEPTH,
 };
 
 enum FinalizerLoopResult {
@@ -23,14 +27,52 @@ enum FinalizerLoopResult {
     TryAgainLater,
 }
 
+/// Consecutive blocks whose hash changed before they were finalized.
+#[derive(Clone)]
+struct Reorg {
+    /// The first block that changed.
+    first: BlockReference,
+    old_hash: BlockHash,
+    last_height: BlockHeight,
+}
+
+impl Reorg {
+    fn depth(&self) -> u64 {
+        self.last_height - self.first.height + 1
+    }
+}
+
+#[derive(Default)]
+struct Reorgs {
+    /// The reorg that the last finalized block is part of. It ends once the block following it is
+    /// unchanged, which may be checked in a later range.
+    pending: Option<Reorg>,
+    /// Reorgs that ended but have not been recorded yet. They are only removed once recorded, so a
+    /// reorg whose recording failed is retried.
+    ended: VecDeque<Reorg>,
+}
+
 impl<T: FetcherClient> Indexer<T> {
     pub async fn run_finalizer(&self, fetcher_client: T) -> Result<(), IndexerError> {
+        let reorgs = Mutex::new(Reorgs::default());
+
         loop {
-            match self.run_finalizer_loop(&fetcher_client).await {
+            match self.run_finalizer_loop(&fetcher_client, &reorgs).await {
                 Ok(FinalizerLoopResult::RunAgain) => {
                     debug!("run again");
                 }
                 Ok(FinalizerLoopResult::TryAgainLater) => {
+                    // all tracked blocks have been checked, the reorg has ended
+                    {
+                        let mut reorgs = reorgs.lock().expect("lock is not poisoned");
+                        if let Some(reorg) = reorgs.pending.take() {
+                            reorgs.ended.push_back(reorg);
+                        }
+                    }
+                    if let Err(error) = self.record_ended_reorgs(&reorgs).await {
+                        warn!("error recording reorg, retrying later: {error}");
+                    }
+
                     debug!(
                         "try again later (sleep {}s)",
                         self.finalizer_config.retry_later_sleep.as_secs()
@@ -51,6 +93,7 @@ impl<T: FetcherClient> Indexer<T> {
     async fn run_finalizer_loop(
         &self,
         fetcher_client: &T,
+        reorgs: &Mutex<Reorgs>,
     ) -> Result<FinalizerLoopResult, IndexerError> {
         let chunk_size: u64 = self.chunk_size.try_into().unwrap();
 
@@ -97,6 +140,7 @@ impl<T: FetcherClient> Indexer<T> {
                                 &last_finalized,
                                 range_to_finalize.clone(),
                                 consensus_height_with_safety_margin,
+                                reorgs,
                             )
                             .instrument(info_span!("finalize"))
                             .await?;
@@ -121,6 +165,7 @@ impl<T: FetcherClient> Indexer<T> {
                                     &last_finalized,
                                     range_to_monitor,
                                     consensus_height_with_safety_margin,
+                                    reorgs,
                                 )
                                 .instrument(info_span!("monitor"))
                                 .await?;
@@ -167,10 +212,11 @@ impl<T: FetcherClient> Indexer<T> {
         last_finalized: &T::BlockHandle,
         block_range: BlockRange,
         height_considered_to_be_finalized: BlockHeight,
+        reorgs: &Mutex<Reorgs>,
     ) -> Result<(), Report> {
         last_finalized
             .fetch_range_expect_all(block_range.clone(), FetchMode::Lazy, |block| {
-                self.finalize_block(block, height_considered_to_be_finalized)
+                self.finalize_block(block, height_considered_to_be_finalized, reorgs)
             })
             .instrument(info_span!("finalize"))
             .await?;
@@ -184,6 +230,7 @@ impl<T: FetcherClient> Indexer<T> {
         &self,
         block: T::BlockHandle,
         last_finalized_height: BlockHeight,
+        reorgs: &Mutex<Reorgs>,
     ) -> Result<(), Report> {
         let reference = block.reference();
         debug!("{}: finalizing", reference);
@@ -191,6 +238,7 @@ impl<T: FetcherClient> Indexer<T> {
         let mut tx = self.pg_pool.begin().await?;
 
         let is_finalized = last_finalized_height >= reference.height;
+        let mut changed_from = None;
 
         if let Some(old_hash) = match is_finalized {
             true => delete_block_status(&mut tx, self.indexer_id.clone(), reference.height).await?,
@@ -198,13 +246,19 @@ impl<T: FetcherClient> Indexer<T> {
                 get_block_status_hash(&mut tx, self.indexer_id.clone(), reference.height).await?
             }
         } {
+            let changed = old_hash != reference.hash;
+
+            if changed {
+                changed_from = Some(old_hash.clone());
+            }
+
             if is_finalized && self.finalizer_config.reload {
                 debug!("{}: finalized (reloading)", reference.height,);
                 block
                     .update(&mut tx)
                     .instrument(info_span!("reload"))
                     .await?;
-            } else if old_hash != reference.hash {
+            } else if changed {
                 debug!(
                     "{}: changed ({} > {} => updating)",
                     reference.height, old_hash, reference.hash,
@@ -237,6 +291,107 @@ impl<T: FetcherClient> Indexer<T> {
 
         debug!("{}: finalized", reference);
 
+        // only tracked once committed, so a retried block is not counted twice
+        {
+            let mut reorgs = reorgs.lock().expect("lock is not poisoned");
+            let continues = reorgs
+                .pending
+                .as_ref()
+                .is_some_and(|reorg| reorg.last_height + 1 == reference.height);
+
+            let ended = match changed_from {
+                Some(_) if continues => {
+                    reorgs
+                        .pending
+                        .as_mut()
+                        .expect("reorg continues")
+                        .last_height = reference.height;
+                    None
+                }
+                Some(old_hash) => reorgs.pending.replace(Reorg {
+                    first: reference.clone(),
+                    old_hash,
+                    last_height: reference.height,
+                }),
+                None if continues => reorgs.pending.take(),
+                None => None,
+            };
+
+            reorgs.ended.extend(ended);
+        }
+
+        self.record_ended_reorgs(reorgs).await?;
+
+        Ok(())
+    }
+
+    /// Records the reorgs that ended in order, removing each one only once it has been recorded.
+    async fn record_ended_reorgs(&self, reorgs: &Mutex<Reorgs>) -> Result<(), Report> {
+        loop {
+            let Some(reorg) = reorgs
+                .lock()
+                .expect("lock is not poisoned")
+                .ended
+                .front()
+                .cloned()
+            else {
+                return Ok(());
+            };
+
+            self.record_reorg(&reorg).await?;
+
+            reorgs
+                .lock()
+                .expect("lock is not poisoned")
+                .ended
+                .pop_front();
+        }
+    }
+
+    /// Records a reorg once it ended. The depth is the number of consecutive blocks that changed.
+    async fn record_reorg(&self, reorg: &Reorg) -> Result<(), Report> {
+        let Reorg {
+            first,
+            old_hash,
+            last_height,
+        } = reorg;
+        let depth = reorg.depth();
+
+        warn!(
+            "{}: reorg detected ({} > {}, until: {}, depth: {})",
+            first.height, old_hash, first.hash, last_height, depth,
+        );
+
+        let mut tx = self.pg_pool.begin().await?;
+
+        insert_reorg(
+            &mut tx,
+            self.indexer_id.clone(),
+            first.height,
+            old_hash.clone(),
+            first.hash.clone(),
+            depth,
+        )
+        .await?;
+
+        notify_reorg(
+            &mut tx,
+            &json!({
+                "indexer_id": self.indexer_id,
+                "height": first.height,
+                "old_hash": old_hash,
+                "new_hash": first.hash,
+                "depth": depth,
+            }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        REORG_DEPTH
+            .with_label_values(&[&self.indexer_id])
+            .observe(depth as f64);
+
         Ok(())
     }
 
@@ -1,11 +1,15 @@
 pub mod api;
 pub mod aptos;
+mod backfill;
 pub mod dummy;
 pub mod ethereum;
 mod fetcher;
 mod finalizer;
 mod fixer;
+pub mod movement;
+mod packet;
 mod postgres;
+pub mod sui;
 pub mod tendermint;
 
 use std::{future::Future, time::Duration};
@@ -31,6 +35,7 @@ pub struct Indexer<T: FetcherClient> {
     pub start_height: BlockHeight,
     pub chunk_size: usize,
     pub finalizer_config: FinalizerConfig,
+    pub backfill_config: Option<BackfillConfig>,
     pub context: T::Context,
 }
 
@@ -102,6 +107,29 @@ impl Default for FinalizerConfig {
     }
 }
 
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct BackfillConfig {
+    // number of workers that concurrently index a part of the historical range.
+    // default: 4
+    #[serde(default = "BackfillConfig::default_workers")]
+    pub workers: usize,
+    // minimum number of blocks behind the finalized height before backfilling is used
+    // instead of sequentially catching up.
+    // default: 10000
+    #[serde(default = "BackfillConfig::default_min_blocks")]
+    pub min_blocks: u64,
+}
+
+impl BackfillConfig {
+    pub fn default_workers() -> usize {
+        4
+    }
+
+    pub fn default_min_blocks() -> u64 {
+        10_000
+    }
+}
+
 impl<T> Indexer<T>
 where
     T: FetcherClient,
@@ -112,6 +140,7 @@ where
         start_height: BlockHeight,
         chunk_size: usize,
         finalizer_config: FinalizerConfig,
+        backfill_config: Option<BackfillConfig>,
         context: T::Context,
     ) -> Self {
         Indexer {
@@ -120,6 +149,7 @@ where
             start_height,
             chunk_size,
             finalizer_config,
+            backfill_config,
             context,
         }
     }
@@ -177,7 +207,14 @@ where
         context: T::Context,
     ) -> Option<T> {
         info!("creating client (context: {})", self.context);
-        match T::create(self.pg_pool.clone(), join_set, context).await {
+        match T::create(
+            self.pg_pool.clone(),
+            self.indexer_id.clone(),
+            join_set,
+            context,
+        )
+        .await
+        {
             Ok(client) => {
                 info!("created client: {}", client);
                 Some(client)
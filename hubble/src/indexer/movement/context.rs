@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use crate::indexer::aptos::context::AptosContext;
+
+#[derive(Clone)]
+pub struct MovementContext {
+    pub aptos: AptosContext,
+    pub finality: MovementFinality,
+}
+
+/// Movement blocks are produced by a sequencer and only become final once they are settled. Nodes
+/// that do not run with settlement enabled only report the latest block.
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementFinality {
+    /// Use the finalized block reported by the node (`/movement/v1/get-finalized-block-info`).
+    #[default]
+    Settlement,
+    /// Consider the latest block to be finalized (relying on the finalizer `delay_blocks`).
+    Latest,
+}
+
+impl Display for MovementFinality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MovementFinality::Settlement => write!(f, "settlement"),
+            MovementFinality::Latest => write!(f, "latest"),
+        }
+    }
+}
+
+impl Display for MovementContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, finality: {}", self.aptos, self.finality)
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt::Display;
+
+use axum::async_trait;
+use tokio::task::JoinSet;
+use tracing::{debug, trace};
+
+use crate::indexer::{
+    api::{BlockSelection, FetchMode, FetcherClient, IndexerError, IndexerId},
+    aptos::{block_handle::AptosBlockHandle, fetcher_client::AptosFetcherClient},
+    movement::{
+        context::{MovementContext, MovementFinality},
+        provider::Provider,
+    },
+};
+
+/// Movement exposes the aptos rest api, so blocks are fetched and stored like aptos blocks. Only
+/// the finalized block is determined differently.
+#[derive(Clone)]
+pub struct MovementFetcherClient {
+    pub aptos_client: AptosFetcherClient,
+    pub provider: Provider,
+    pub finality: MovementFinality,
+}
+
+impl Display for MovementFetcherClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, finality: {}", self.aptos_client, self.finality)
+    }
+}
+
+impl MovementFetcherClient {
+    async fn fetch_last_finalized(
+        &self,
+        mode: FetchMode,
+    ) -> Result<AptosBlockHandle, IndexerError> {
+        let selection = last_finalized_selection(&self.provider, self.finality).await?;
+
+        self.aptos_client
+            .fetch_single_with_provider(selection, mode, None)
+            .await
+    }
+}
+
+/// Selects the aptos block that is considered to be the last finalized one: the finalized block
+/// reported by the node, or the latest block.
+async fn last_finalized_selection(
+    provider: &Provider,
+    finality: MovementFinality,
+) -> Result<BlockSelection, IndexerError> {
+    match finality {
+        MovementFinality::Settlement => {
+            let height = provider
+                .get_finalized_block_height(None)
+                .await
+                .map_err(IndexerError::ProviderError)?
+                .response;
+
+            trace!("finalized height: {height}");
+
+            Ok(BlockSelection::Height(height))
+        }
+        MovementFinality::Latest => Ok(BlockSelection::LastFinalized),
+    }
+}
+
+#[async_trait]
+impl FetcherClient for MovementFetcherClient {
+    type BlockHandle = AptosBlockHandle;
+    type Context = MovementContext;
+
+    async fn create(
+        pg_pool: sqlx::PgPool,
+        indexer_id: IndexerId,
+        join_set: &mut JoinSet<Result<(), IndexerError>>,
+        context: MovementContext,
+    ) -> Result<Self, IndexerError> {
+        let provider = Provider::new(indexer_id.clone(), context.aptos.rpc_urls.clone());
+        let aptos_client =
+            AptosFetcherClient::create(pg_pool, indexer_id, join_set, context.aptos).await?;
+
+        Ok(MovementFetcherClient {
+            aptos_client,
+            provider,
+            finality: context.finality,
+        })
+    }
+
+    async fn fetch_single(
+        &self,
+        selection: BlockSelection,
+        mode: FetchMode,
+    ) -> Result<Self::BlockHandle, IndexerError> {
+        debug!("{}: fetching", selection);
+
+        match selection {
+            BlockSelection::LastFinalized => self.fetch_last_finalized(mode).await,
+            BlockSelection::Height(_) => {
+                self.aptos_client
+                    .fetch_single_with_provider(selection, mode, None)
+                    .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{http::StatusCode, routing::get, Router};
+    use serde_json::json;
+    use url::Url;
+
+    use crate::indexer::{
+        api::BlockSelection,
+        movement::{
+            context::MovementFinality, fetcher_client::last_finalized_selection, provider::Provider,
+        },
+    };
+
+    /// Serves the finalized block info of a movement node, returning its url.
+    async fn serve(router: Router) -> Url {
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(router.into_make_service());
+        let url = format!("http://{}/", server.local_addr()).parse().unwrap();
+        tokio::spawn(server);
+        url
+    }
+
+    fn finalized_block_info(height: u64) -> Router {
+        Router::new().route(
+            "/movement/v1/get-finalized-block-info",
+            get(move || async move {
+                axum::Json(json!({
+                    "block_height": height.to_string(),
+                    "block_hash": "0x00",
+                    "block_timestamp": "0"
+                }))
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn settlement_uses_finalized_block_info() {
+        let provider = Provider::new(
+            "movement".to_string(),
+            vec![serve(finalized_block_info(42)).await],
+        );
+
+        let selection = last_finalized_selection(&provider, MovementFinality::Settlement)
+            .await
+            .unwrap();
+
+        assert!(matches!(selection, BlockSelection::Height(42)));
+    }
+
+    #[tokio::test]
+    async fn settlement_fails_without_finalized_block_info() {
+        let provider = Provider::new(
+            "movement".to_string(),
+            vec![serve(Router::new().fallback(|| async { StatusCode::NOT_FOUND })).await],
+        );
+
+        // the latest block is not used instead, it may not be final
+        assert!(
+            last_finalized_selection(&provider, MovementFinality::Settlement)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn latest_does_not_query_finalized_block_info() {
+        // nothing listens on the discard port
+        let provider = Provider::new(
+            "movement".to_string(),
+            vec!["http://127.0.0.1:9/".parse().unwrap()],
+        );
+
+        let selection = last_finalized_selection(&provider, MovementFinality::Latest)
+            .await
+            .unwrap();
+
+        assert!(matches!(selection, BlockSelection::LastFinalized));
+    }
+
+    #[test]
+    fn finality_defaults_to_settlement() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            #[serde(default)]
+            finality: MovementFinality,
+        }
+
+        let config: Config = serde_json::from_value(json!({})).unwrap();
+        assert!(matches!(config.finality, MovementFinality::Settlement));
+
+        let config: Config = serde_json::from_value(json!({ "finality": "latest" })).unwrap();
+        assert!(matches!(config.finality, MovementFinality::Latest));
+    }
+}
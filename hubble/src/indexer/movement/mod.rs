@@ -0,0 +1,4 @@
+pub mod config;
+mod context;
+mod fetcher_client;
+mod provider;
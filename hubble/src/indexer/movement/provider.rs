@@ -0,0 +1,111 @@
+use std::result::Result;
+
+use aptos_rest_client::aptos_api_types::U64;
+use color_eyre::eyre::Report;
+use serde::Deserialize;
+use url::Url;
+
+use crate::{
+    indexer::api::{BlockHeight, IndexerId},
+    race_client::{RaceClient, RaceClientId, RaceClientResponse},
+};
+
+const FINALIZED_BLOCK_INFO_PATH: &str = "movement/v1/get-finalized-block-info";
+
+#[derive(Clone, Debug)]
+pub struct MovementClient {
+    client: reqwest::Client,
+    url: Url,
+}
+
+#[derive(Clone, Debug)]
+pub struct Provider {
+    pub rpc_client: RaceClient<MovementClient>,
+}
+
+#[derive(Clone, Debug, Copy)]
+pub struct RpcProviderId {
+    race_client_id: RaceClientId,
+}
+
+impl From<RpcProviderId> for RaceClientId {
+    fn from(value: RpcProviderId) -> Self {
+        value.race_client_id
+    }
+}
+
+#[derive(Debug)]
+pub struct RpcResult<T> {
+    pub provider_id: RpcProviderId,
+    pub response: T,
+}
+
+impl<T> From<RaceClientResponse<T>> for RpcResult<T> {
+    fn from(value: RaceClientResponse<T>) -> Self {
+        RpcResult {
+            provider_id: RpcProviderId {
+                race_client_id: value.race_client_id,
+            },
+            response: value.response,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FinalizedBlockInfo {
+    block_height: U64,
+}
+
+impl MovementClient {
+    async fn get_finalized_block_height(&self) -> Result<BlockHeight, Report> {
+        let url = self.url.join(FINALIZED_BLOCK_INFO_PATH)?;
+
+        let block_info: FinalizedBlockInfo = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(block_info.block_height.into())
+    }
+}
+
+impl Provider {
+    pub fn new(indexer_id: IndexerId, rpc_urls: Vec<Url>) -> Self {
+        let client = reqwest::Client::new();
+
+        Self {
+            rpc_client: RaceClient::new(
+                indexer_id,
+                rpc_urls
+                    .into_iter()
+                    .map(|url| {
+                        (
+                            url.clone(),
+                            MovementClient {
+                                client: client.clone(),
+                                url,
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    // RPC
+    pub async fn get_finalized_block_height(
+        &self,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<BlockHeight>, Report> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.get_finalized_block_height()
+            })
+            .await
+            .map(Into::into)
+    }
+}
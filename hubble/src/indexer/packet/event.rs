@@ -0,0 +1,320 @@
+use alloy::rpc::types::Log;
+use ibc_solidity::Ibc;
+use ibc_union_spec::types::Packet;
+use serde_json::Value;
+use tracing::trace;
+use unionlabs::primitives::Bytes;
+
+use crate::indexer::packet::{PacketEvent, PacketStage};
+
+// tendermint event types emitted by the ibc-union core contract (prefixed with 'wasm-').
+const TM_PACKET_SEND: &str = "wasm-packet_send";
+const TM_PACKET_RECV: &str = "wasm-packet_recv";
+const TM_INTENT_PACKET_RECV: &str = "wasm-intent_packet_recv";
+const TM_WRITE_ACK: &str = "wasm-write_ack";
+const TM_PACKET_ACK: &str = "wasm-packet_ack";
+const TM_PACKET_TIMEOUT: &str = "wasm-packet_timeout";
+
+const TM_ATTRIBUTE_PACKET: &str = "packet";
+const TM_ATTRIBUTE_ACKNOWLEDGEMENT: &str = "acknowledgement";
+
+// move event types emitted by the ibc module on aptos/movement and sui ('<address>::ibc::<name>').
+const MOVE_IBC_MODULE: &str = "ibc";
+const APTOS_PACKET_SEND: &str = "PacketSend";
+const APTOS_PACKET_RECV: &str = "PacketRecv";
+const APTOS_WRITE_ACK: &str = "WriteAck";
+const APTOS_PACKET_ACK: &str = "PacketAck";
+const SUI_SEND_PACKET: &str = "SendPacket";
+const SUI_RECV_PACKET: &str = "RecvPacket";
+const SUI_WRITE_ACK: &str = "WriteAcknowledgement";
+const SUI_ACKNOWLEDGE_PACKET: &str = "AcknowledgePacket";
+const MOVE_INTENT_PACKET_RECV: &str = "RecvIntentPacket";
+const MOVE_TIMEOUT_PACKET: &str = "TimeoutPacket";
+
+/// Extracts a packet lifecycle event from a tendermint event (as stored in v1_cosmos.events).
+///
+/// {
+///     "type": "wasm-packet_ack",
+///     "attributes": [
+///       { "key": "packet", "value": "{\"source_channel_id\":1,...}" },
+///       { "key": "acknowledgement", "value": "0000..." },
+///       ...
+///     ]
+/// }
+pub fn from_tendermint_event(transaction_hash: String, event_data: &Value) -> Option<PacketEvent> {
+    let stage = match event_data.get("type")?.as_str()? {
+        TM_PACKET_SEND => PacketStage::Send,
+        TM_PACKET_RECV | TM_INTENT_PACKET_RECV => PacketStage::Recv,
+        TM_WRITE_ACK => PacketStage::WriteAck,
+        TM_PACKET_ACK => PacketStage::Ack,
+        TM_PACKET_TIMEOUT => PacketStage::Timeout,
+        _ => return None,
+    };
+
+    let attribute = |key: &str| {
+        event_data
+            .get("attributes")?
+            .as_array()?
+            .iter()
+            .find(|attribute| attribute.get("key").and_then(Value::as_str) == Some(key))?
+            .get("value")?
+            .as_str()
+    };
+
+    let packet = match serde_json::from_str::<Packet>(attribute(TM_ATTRIBUTE_PACKET)?) {
+        Ok(packet) => packet,
+        Err(error) => {
+            trace!("{stage}: cannot parse packet attribute: {error} => ignore");
+            return None;
+        }
+    };
+
+    let acknowledgement = match attribute(TM_ATTRIBUTE_ACKNOWLEDGEMENT) {
+        Some(acknowledgement) => match hex::decode(acknowledgement) {
+            Ok(acknowledgement) => Some(acknowledgement.into()),
+            Err(error) => {
+                trace!("{stage}: cannot parse acknowledgement attribute: {error} => ignore");
+                return None;
+            }
+        },
+        None => None,
+    };
+
+    Some(PacketEvent {
+        stage,
+        packet,
+        acknowledgement,
+        transaction_hash,
+    })
+}
+
+/// Extracts a packet lifecycle event from an ethereum log (as stored in v1_evm.logs).
+pub fn from_ethereum_log(transaction_hash: String, log_data: &Value) -> Option<PacketEvent> {
+    let log: Log = serde_json::from_value(log_data.clone()).ok()?;
+
+    let (stage, packet, acknowledgement): (_, _, Option<Bytes>) =
+        match Ibc::IbcEvents::decode_log(&log.inner, true).ok()?.data {
+            Ibc::IbcEvents::PacketSend(event) => (PacketStage::Send, event.packet, None),
+            Ibc::IbcEvents::PacketRecv(event) => (PacketStage::Recv, event.packet, None),
+            Ibc::IbcEvents::IntentPacketRecv(event) => (PacketStage::Recv, event.packet, None),
+            Ibc::IbcEvents::WriteAck(event) => (
+                PacketStage::WriteAck,
+                event.packet,
+                Some(event.acknowledgement.to_vec().into()),
+            ),
+            Ibc::IbcEvents::PacketAck(event) => (
+                PacketStage::Ack,
+                event.packet,
+                Some(event.acknowledgement.to_vec().into()),
+            ),
+            Ibc::IbcEvents::PacketTimeout(event) => (PacketStage::Timeout, event.packet, None),
+            _ => return None,
+        };
+
+    Some(PacketEvent {
+        stage,
+        packet: packet.into(),
+        acknowledgement,
+        transaction_hash,
+    })
+}
+
+/// Extracts a packet lifecycle event from a move event (as stored in v1_aptos.events and
+/// v1_sui.events). The send events carry the packet fields directly, the other events carry a
+/// nested packet. u64s are encoded as strings, and byte vectors as hex (aptos) or as an array
+/// (sui).
+///
+/// {
+///     "packet": {
+///       "source_channel_id": 1,
+///       "destination_channel_id": 2,
+///       "data": "0x...",
+///       "timeout_height": "0",
+///       "timeout_timestamp": "1737000000000000000"
+///     },
+///     "acknowledgement": "0x...",
+///     ...
+/// }
+pub fn from_move_event(transaction_hash: String, typ: &str, data: &Value) -> Option<PacketEvent> {
+    let mut path = typ.split("::");
+    let (_address, module, name) = (path.next()?, path.next()?, path.next()?);
+
+    if module != MOVE_IBC_MODULE {
+        return None;
+    }
+
+    let stage = match name {
+        APTOS_PACKET_SEND | SUI_SEND_PACKET => PacketStage::Send,
+        APTOS_PACKET_RECV | SUI_RECV_PACKET | MOVE_INTENT_PACKET_RECV => PacketStage::Recv,
+        APTOS_WRITE_ACK | SUI_WRITE_ACK => PacketStage::WriteAck,
+        APTOS_PACKET_ACK | SUI_ACKNOWLEDGE_PACKET => PacketStage::Ack,
+        MOVE_TIMEOUT_PACKET => PacketStage::Timeout,
+        _ => return None,
+    };
+
+    let packet = match name {
+        APTOS_PACKET_SEND => move_packet(data, "source_channel_id", "destination_channel_id"),
+        SUI_SEND_PACKET => move_packet(data, "source_channel", "destination_channel"),
+        _ => move_packet(
+            data.get("packet")?,
+            "source_channel_id",
+            "destination_channel_id",
+        ),
+    };
+
+    let Some(packet) = packet else {
+        trace!("{stage}: cannot parse packet of {typ} => ignore");
+        return None;
+    };
+
+    let acknowledgement = match stage {
+        PacketStage::WriteAck | PacketStage::Ack => {
+            match data.get("acknowledgement").and_then(move_bytes) {
+                Some(acknowledgement) => Some(acknowledgement),
+                None => {
+                    trace!("{stage}: cannot parse acknowledgement of {typ} => ignore");
+                    return None;
+                }
+            }
+        }
+        _ => None,
+    };
+
+    Some(PacketEvent {
+        stage,
+        packet,
+        acknowledgement,
+        transaction_hash,
+    })
+}
+
+fn move_packet(
+    value: &Value,
+    source_channel_key: &str,
+    destination_channel_key: &str,
+) -> Option<Packet> {
+    Some(Packet {
+        source_channel_id: value.get(source_channel_key)?.as_u64()?.try_into().ok()?,
+        destination_channel_id: value
+            .get(destination_channel_key)?
+            .as_u64()?
+            .try_into()
+            .ok()?,
+        data: move_bytes(value.get("data")?)?,
+        timeout_height: move_u64(value.get("timeout_height")?)?,
+        timeout_timestamp: move_u64(value.get("timeout_timestamp")?)?,
+    })
+}
+
+fn move_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(value) => value.parse().ok(),
+        value => value.as_u64(),
+    }
+}
+
+fn move_bytes(value: &Value) -> Option<Bytes> {
+    match value {
+        Value::String(value) => hex::decode(value.strip_prefix("0x").unwrap_or(value))
+            .ok()
+            .map(Into::into),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| value.as_u64()?.try_into().ok())
+            .collect::<Option<Vec<u8>>>()
+            .map(Into::into),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{from_move_event, from_tendermint_event};
+    use crate::indexer::packet::PacketStage;
+
+    #[test]
+    fn parses_tendermint_packet_ack() {
+        let event = from_tendermint_event(
+            "tx".to_string(),
+            &json!({
+                "type": "wasm-packet_ack",
+                "attributes": [
+                    {
+                        "key": "packet",
+                        "value": "{\"source_channel_id\":1,\"destination_channel_id\":2,\"data\":\"0x01\",\"timeout_height\":0,\"timeout_timestamp\":5}"
+                    },
+                    { "key": "acknowledgement", "value": "0102" },
+                    { "key": "maker", "value": "union1relayer" }
+                ]
+            }),
+        )
+        .expect("packet event");
+
+        assert_eq!(event.stage, PacketStage::Ack);
+        assert_eq!(event.packet.source_channel_id, 1);
+        assert_eq!(event.packet.destination_channel_id, 2);
+        assert_eq!(event.acknowledgement.unwrap(), [1, 2]);
+    }
+
+    #[test]
+    fn ignores_non_packet_events() {
+        assert!(from_tendermint_event(
+            "tx".to_string(),
+            &json!({ "type": "wasm-channel_open_init", "attributes": [] }),
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn parses_aptos_packet_send() {
+        let event = from_move_event(
+            "tx".to_string(),
+            "0x1234::ibc::PacketSend",
+            &json!({
+                "source_channel_id": 1,
+                "destination_channel_id": 2,
+                "data": "0x0102",
+                "timeout_height": "0",
+                "timeout_timestamp": "5"
+            }),
+        )
+        .expect("packet event");
+
+        assert_eq!(event.stage, PacketStage::Send);
+        assert_eq!(event.packet.source_channel_id, 1);
+        assert_eq!(event.packet.data, [1, 2]);
+        assert_eq!(event.packet.timeout_timestamp, 5);
+    }
+
+    #[test]
+    fn parses_sui_write_ack() {
+        let event = from_move_event(
+            "tx".to_string(),
+            "0x1234::ibc::WriteAcknowledgement",
+            &json!({
+                "packet": {
+                    "source_channel_id": 1,
+                    "destination_channel_id": 2,
+                    "data": [1, 2],
+                    "timeout_height": "0",
+                    "timeout_timestamp": "5"
+                },
+                "acknowledgement": [3]
+            }),
+        )
+        .expect("packet event");
+
+        assert_eq!(event.stage, PacketStage::WriteAck);
+        assert_eq!(event.packet.destination_channel_id, 2);
+        assert_eq!(event.acknowledgement.unwrap(), [3]);
+    }
+
+    #[test]
+    fn ignores_move_events_of_other_modules() {
+        assert!(
+            from_move_event("tx".to_string(), "0x1234::zkgm::PacketSend", &json!({}),).is_none()
+        );
+    }
+}
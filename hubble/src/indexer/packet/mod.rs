@@ -0,0 +1,223 @@
+//! Normalized packet lifecycle tracking.
+//!
+//! The chain specific block handles extract ibc-union packet events while inserting a block. Each
+//! event is stored in `hubble.packet_events`, keyed by the block it was observed in, so that a
+//! rewrite of a block (by the fixer or the finalizer) can remove exactly the events of that block.
+//! `hubble.packets` and `hubble.transfers` are derived from the events of a packet and are
+//! recalculated every time one of its events changes.
+pub mod event;
+mod postgres;
+pub mod status;
+pub mod zkgm;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use ibc_union_spec::types::Packet;
+use itertools::Itertools;
+use sqlx::Postgres;
+use tracing::{debug, trace};
+use unionlabs::primitives::Bytes;
+
+use crate::indexer::{
+    api::{BlockHeight, BlockReference, IndexerError},
+    packet::{
+        postgres::{
+            delete_packet_events, delete_packets, get_packet_events, insert_packet_events,
+            lock_packets, upsert_packet, upsert_transfers, PgPacket, PgPacketEvent, PgPacketStage,
+        },
+        status::TransferStatus,
+        zkgm::decode_transfers,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PacketStage {
+    Send,
+    Recv,
+    WriteAck,
+    Ack,
+    Timeout,
+}
+
+impl PacketStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PacketStage::Send => "send",
+            PacketStage::Recv => "recv",
+            PacketStage::WriteAck => "write_ack",
+            PacketStage::Ack => "ack",
+            PacketStage::Timeout => "timeout",
+        }
+    }
+
+    pub fn parse(stage: &str) -> Option<Self> {
+        Some(match stage {
+            "send" => PacketStage::Send,
+            "recv" => PacketStage::Recv,
+            "write_ack" => PacketStage::WriteAck,
+            "ack" => PacketStage::Ack,
+            "timeout" => PacketStage::Timeout,
+            _ => return None,
+        })
+    }
+}
+
+impl Display for PacketStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A packet lifecycle event, extracted from a chain specific event.
+#[derive(Clone, Debug)]
+pub struct PacketEvent {
+    pub stage: PacketStage,
+    pub packet: Packet,
+    pub acknowledgement: Option<Bytes>,
+    pub transaction_hash: String,
+}
+
+/// Packets are identified by their source channel and hash.
+pub type PacketKey = (i32, String);
+
+/// Stores the packet events of a block and updates the derived packets and transfers.
+pub async fn insert_packet_events_of_block(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    chain_id: i32,
+    reference: &BlockReference,
+    events: impl IntoIterator<Item = PacketEvent>,
+) -> Result<(), IndexerError> {
+    let events = events
+        .into_iter()
+        .map(|event| PgPacketEvent {
+            chain_id,
+            height: reference.height.try_into().unwrap(),
+            timestamp: reference.timestamp,
+            transaction_hash: event.transaction_hash,
+            stage: event.stage.as_str().to_string(),
+            source_channel_id: event.packet.source_channel_id.try_into().unwrap(),
+            destination_channel_id: event.packet.destination_channel_id.try_into().unwrap(),
+            packet_hash: event.packet.hash().to_string(),
+            data: event.packet.data.to_vec(),
+            timeout_height: event.packet.timeout_height.into(),
+            timeout_timestamp: event.packet.timeout_timestamp.into(),
+            acknowledgement: event.acknowledgement.map(|ack| ack.to_vec()),
+        })
+        .collect_vec();
+
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    trace!("{reference}: packet events: {}", events.len());
+
+    let keys = events
+        .iter()
+        .map(|event| (event.source_channel_id, event.packet_hash.clone()))
+        .collect::<BTreeSet<_>>();
+
+    insert_packet_events(tx, events).await?;
+    refresh_packets(tx, keys).await
+}
+
+/// Removes the packet events of a block and updates the derived packets and transfers. Used
+/// when a block is rewritten (the new events are inserted afterwards).
+pub async fn delete_packet_events_of_block(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    chain_id: i32,
+    height: BlockHeight,
+) -> Result<(), IndexerError> {
+    let keys = delete_packet_events(tx, chain_id, height).await?;
+
+    if !keys.is_empty() {
+        debug!("{height}: removed events of {} packet(s)", keys.len());
+        refresh_packets(tx, keys).await?;
+    }
+
+    Ok(())
+}
+
+/// Recalculates the packet lifecycle and transfer status of the packets from their events.
+async fn refresh_packets(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    keys: BTreeSet<PacketKey>,
+) -> Result<(), IndexerError> {
+    // events read after acquiring the locks include those committed by the previous holder
+    lock_packets(tx, &keys).await?;
+
+    let mut events_by_packet: BTreeMap<PacketKey, Vec<PgPacketEvent>> = BTreeMap::new();
+    for event in get_packet_events(tx, &keys).await? {
+        events_by_packet
+            .entry((event.source_channel_id, event.packet_hash.clone()))
+            .or_default()
+            .push(event);
+    }
+
+    let removed = keys
+        .iter()
+        .filter(|key| !events_by_packet.contains_key(key))
+        .cloned()
+        .collect_vec();
+
+    if !removed.is_empty() {
+        trace!("packets without events: {}", removed.len());
+        delete_packets(tx, &removed).await?;
+    }
+
+    for (key, events) in events_by_packet {
+        let packet = PgPacket::from_events(&events);
+        let status = TransferStatus::derive(&packet);
+        let transfers = decode_transfers(&packet.data);
+
+        trace!(
+            "{}/{}: status {status} (transfers: {})",
+            key.0,
+            key.1,
+            transfers.len()
+        );
+
+        upsert_packet(tx, &packet).await?;
+        upsert_transfers(tx, &key, transfers, status).await?;
+    }
+
+    Ok(())
+}
+
+impl PgPacket {
+    /// Combines the events of a single packet. When a stage is observed more than once, the
+    /// first observation wins (ordered by chain, height and transaction).
+    fn from_events(events: &[PgPacketEvent]) -> Self {
+        let first = events.first().expect("at least one event per packet");
+
+        let stage = |stage: PacketStage| {
+            events
+                .iter()
+                .filter(|event| PacketStage::parse(&event.stage) == Some(stage))
+                .min_by_key(|event| (event.chain_id, event.height, event.transaction_hash.clone()))
+                .map(|event| PgPacketStage {
+                    chain_id: event.chain_id,
+                    height: event.height,
+                    timestamp: event.timestamp,
+                    transaction_hash: event.transaction_hash.clone(),
+                    acknowledgement: event.acknowledgement.clone(),
+                })
+        };
+
+        PgPacket {
+            source_channel_id: first.source_channel_id,
+            destination_channel_id: first.destination_channel_id,
+            packet_hash: first.packet_hash.clone(),
+            data: first.data.clone(),
+            timeout_height: first.timeout_height.clone(),
+            timeout_timestamp: first.timeout_timestamp.clone(),
+            send: stage(PacketStage::Send),
+            recv: stage(PacketStage::Recv),
+            write_ack: stage(PacketStage::WriteAck),
+            ack: stage(PacketStage::Ack),
+            timeout: stage(PacketStage::Timeout),
+        }
+    }
+}
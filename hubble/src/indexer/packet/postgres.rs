@@ -0,0 +1,392 @@
+use std::collections::BTreeSet;
+
+use itertools::Itertools;
+use sqlx::{types::BigDecimal, Postgres};
+use time::OffsetDateTime;
+
+use crate::indexer::{
+    api::BlockHeight,
+    packet::{status::TransferStatus, zkgm::Transfer, PacketKey},
+};
+
+/// DTO corresponding to the hubble.packet_events table.
+#[derive(Clone, Debug)]
+pub struct PgPacketEvent {
+    pub chain_id: i32,
+    pub height: i64,
+    pub timestamp: OffsetDateTime,
+    pub transaction_hash: String,
+    pub stage: String,
+    pub source_channel_id: i32,
+    pub destination_channel_id: i32,
+    pub packet_hash: String,
+    pub data: Vec<u8>,
+    pub timeout_height: BigDecimal,
+    pub timeout_timestamp: BigDecimal,
+    pub acknowledgement: Option<Vec<u8>>,
+}
+
+/// Where and when a packet reached a stage of its lifecycle.
+#[derive(Clone, Debug)]
+pub struct PgPacketStage {
+    pub chain_id: i32,
+    pub height: i64,
+    pub timestamp: OffsetDateTime,
+    pub transaction_hash: String,
+    pub acknowledgement: Option<Vec<u8>>,
+}
+
+/// DTO corresponding to the hubble.packets table.
+#[derive(Clone, Debug)]
+pub struct PgPacket {
+    pub source_channel_id: i32,
+    pub destination_channel_id: i32,
+    pub packet_hash: String,
+    pub data: Vec<u8>,
+    pub timeout_height: BigDecimal,
+    pub timeout_timestamp: BigDecimal,
+    pub send: Option<PgPacketStage>,
+    pub recv: Option<PgPacketStage>,
+    pub write_ack: Option<PgPacketStage>,
+    pub ack: Option<PgPacketStage>,
+    pub timeout: Option<PgPacketStage>,
+}
+
+pub async fn insert_packet_events(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    events: impl IntoIterator<Item = PgPacketEvent>,
+) -> sqlx::Result<()> {
+    #![allow(clippy::type_complexity)]
+    let (
+        chain_ids,
+        heights,
+        timestamps,
+        transaction_hashes,
+        stages,
+        source_channel_ids,
+        destination_channel_ids,
+        packet_hashes,
+        data,
+        timeout_heights,
+        timeout_timestamps,
+        acknowledgements,
+    ): (
+        Vec<i32>,
+        Vec<i64>,
+        Vec<OffsetDateTime>,
+        Vec<String>,
+        Vec<String>,
+        Vec<i32>,
+        Vec<i32>,
+        Vec<String>,
+        Vec<Vec<u8>>,
+        Vec<BigDecimal>,
+        Vec<BigDecimal>,
+        Vec<Option<Vec<u8>>>,
+    ) = events
+        .into_iter()
+        .map(|e| {
+            (
+                e.chain_id,
+                e.height,
+                e.timestamp,
+                e.transaction_hash,
+                e.stage,
+                e.source_channel_id,
+                e.destination_channel_id,
+                e.packet_hash,
+                e.data,
+                e.timeout_height,
+                e.timeout_timestamp,
+                e.acknowledgement,
+            )
+        })
+        .multiunzip();
+
+    sqlx::query!("
+        INSERT INTO hubble.packet_events (chain_id, height, timestamp, transaction_hash, stage, source_channel_id, destination_channel_id, packet_hash, data, timeout_height, timeout_timestamp, acknowledgement)
+        SELECT unnest($1::int[]), unnest($2::bigint[]), unnest($3::timestamptz[]), unnest($4::text[]), unnest($5::text[]), unnest($6::int[]), unnest($7::int[]), unnest($8::text[]), unnest($9::bytea[]), unnest($10::numeric[]), unnest($11::numeric[]), unnest($12::bytea[])
+        ON CONFLICT DO NOTHING
+        ",
+        &chain_ids, &heights, &timestamps, &transaction_hashes, &stages, &source_channel_ids, &destination_channel_ids, &packet_hashes, &data, &timeout_heights, &timeout_timestamps, &acknowledgements as _)
+    .execute(tx.as_mut()).await?;
+
+    Ok(())
+}
+
+/// Deletes the packet events of a block, returning the packets that were affected.
+pub async fn delete_packet_events(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    chain_id: i32,
+    height: BlockHeight,
+) -> sqlx::Result<BTreeSet<PacketKey>> {
+    let height: i64 = height.try_into().unwrap();
+
+    let result = sqlx::query!(
+        "
+        DELETE FROM hubble.packet_events WHERE chain_id = $1 AND height = $2
+        RETURNING source_channel_id, packet_hash
+        ",
+        chain_id,
+        height,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| (record.source_channel_id, record.packet_hash))
+    .collect();
+
+    Ok(result)
+}
+
+/// Locks the packets until the transaction ends. The events of a packet are inserted by the indexers
+/// of both its chains (and by backfill workers), so the derived rows must be recalculated by one
+/// transaction at a time, otherwise a late writer drops the stages another indexer just set. The
+/// keys are locked in order to avoid deadlocks.
+pub async fn lock_packets(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    keys: &BTreeSet<PacketKey>,
+) -> sqlx::Result<()> {
+    for (source_channel_id, packet_hash) in keys {
+        // not using the query! macro, because it does not support the 'void' result of pg_advisory_xact_lock.
+        sqlx::query("SELECT pg_advisory_xact_lock($1, hashtext($2))")
+            .bind(source_channel_id)
+            .bind(packet_hash)
+            .execute(tx.as_mut())
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn get_packet_events(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    keys: &BTreeSet<PacketKey>,
+) -> sqlx::Result<Vec<PgPacketEvent>> {
+    let (source_channel_ids, packet_hashes): (Vec<i32>, Vec<String>) =
+        keys.iter().cloned().unzip();
+
+    let result = sqlx::query!(
+        "
+        SELECT chain_id, height, timestamp, transaction_hash, stage, source_channel_id, destination_channel_id, packet_hash, data, timeout_height, timeout_timestamp, acknowledgement
+        FROM   hubble.packet_events
+        WHERE  (source_channel_id, packet_hash) IN (SELECT unnest($1::int[]), unnest($2::text[]))
+        ",
+        &source_channel_ids,
+        &packet_hashes,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| PgPacketEvent {
+        chain_id: record.chain_id,
+        height: record.height,
+        timestamp: record.timestamp,
+        transaction_hash: record.transaction_hash,
+        stage: record.stage,
+        source_channel_id: record.source_channel_id,
+        destination_channel_id: record.destination_channel_id,
+        packet_hash: record.packet_hash,
+        data: record.data,
+        timeout_height: record.timeout_height,
+        timeout_timestamp: record.timeout_timestamp,
+        acknowledgement: record.acknowledgement,
+    })
+    .collect();
+
+    Ok(result)
+}
+
+pub async fn delete_packets(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    keys: &[PacketKey],
+) -> sqlx::Result<()> {
+    let (source_channel_ids, packet_hashes): (Vec<i32>, Vec<String>) =
+        keys.iter().cloned().unzip();
+
+    sqlx::query!(
+        "
+        DELETE FROM hubble.transfers
+        WHERE (source_channel_id, packet_hash) IN (SELECT unnest($1::int[]), unnest($2::text[]))
+        ",
+        &source_channel_ids,
+        &packet_hashes,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    sqlx::query!(
+        "
+        DELETE FROM hubble.packets
+        WHERE (source_channel_id, packet_hash) IN (SELECT unnest($1::int[]), unnest($2::text[]))
+        ",
+        &source_channel_ids,
+        &packet_hashes,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_packet(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    packet: &PgPacket,
+) -> sqlx::Result<()> {
+    let send = packet.send.as_ref();
+    let recv = packet.recv.as_ref();
+    let write_ack = packet.write_ack.as_ref();
+    let ack = packet.ack.as_ref();
+    let timeout = packet.timeout.as_ref();
+
+    let acknowledgement = ack
+        .or(write_ack)
+        .and_then(|stage| stage.acknowledgement.clone());
+
+    sqlx::query!(
+        "
+        INSERT INTO hubble.packets (
+            source_channel_id, packet_hash, destination_channel_id, data, timeout_height, timeout_timestamp,
+            send_chain_id, send_height, send_timestamp, send_transaction_hash,
+            recv_chain_id, recv_height, recv_timestamp, recv_transaction_hash,
+            write_ack_chain_id, write_ack_height, write_ack_timestamp, write_ack_transaction_hash,
+            ack_chain_id, ack_height, ack_timestamp, ack_transaction_hash,
+            timeout_chain_id, timeout_height_observed, timeout_timestamp_observed, timeout_transaction_hash,
+            acknowledgement
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27)
+        ON CONFLICT (source_channel_id, packet_hash) DO
+        UPDATE SET
+            send_chain_id = excluded.send_chain_id,
+            send_height = excluded.send_height,
+            send_timestamp = excluded.send_timestamp,
+            send_transaction_hash = excluded.send_transaction_hash,
+            recv_chain_id = excluded.recv_chain_id,
+            recv_height = excluded.recv_height,
+            recv_timestamp = excluded.recv_timestamp,
+            recv_transaction_hash = excluded.recv_transaction_hash,
+            write_ack_chain_id = excluded.write_ack_chain_id,
+            write_ack_height = excluded.write_ack_height,
+            write_ack_timestamp = excluded.write_ack_timestamp,
+            write_ack_transaction_hash = excluded.write_ack_transaction_hash,
+            ack_chain_id = excluded.ack_chain_id,
+            ack_height = excluded.ack_height,
+            ack_timestamp = excluded.ack_timestamp,
+            ack_transaction_hash = excluded.ack_transaction_hash,
+            timeout_chain_id = excluded.timeout_chain_id,
+            timeout_height_observed = excluded.timeout_height_observed,
+            timeout_timestamp_observed = excluded.timeout_timestamp_observed,
+            timeout_transaction_hash = excluded.timeout_transaction_hash,
+            acknowledgement = excluded.acknowledgement,
+            updated_at = now()
+        ",
+        packet.source_channel_id,
+        packet.packet_hash,
+        packet.destination_channel_id,
+        packet.data,
+        packet.timeout_height,
+        packet.timeout_timestamp,
+        send.map(|s| s.chain_id),
+        send.map(|s| s.height),
+        send.map(|s| s.timestamp),
+        send.map(|s| s.transaction_hash.clone()),
+        recv.map(|s| s.chain_id),
+        recv.map(|s| s.height),
+        recv.map(|s| s.timestamp),
+        recv.map(|s| s.transaction_hash.clone()),
+        write_ack.map(|s| s.chain_id),
+        write_ack.map(|s| s.height),
+        write_ack.map(|s| s.timestamp),
+        write_ack.map(|s| s.transaction_hash.clone()),
+        ack.map(|s| s.chain_id),
+        ack.map(|s| s.height),
+        ack.map(|s| s.timestamp),
+        ack.map(|s| s.transaction_hash.clone()),
+        timeout.map(|s| s.chain_id),
+        timeout.map(|s| s.height),
+        timeout.map(|s| s.timestamp),
+        timeout.map(|s| s.transaction_hash.clone()),
+        acknowledgement,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Replaces the transfers of a packet.
+pub async fn upsert_transfers(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    key: &PacketKey,
+    transfers: Vec<Transfer>,
+    status: TransferStatus,
+) -> sqlx::Result<()> {
+    #![allow(clippy::type_complexity)]
+    let (source_channel_id, packet_hash) = key;
+
+    sqlx::query!(
+        "
+        DELETE FROM hubble.transfers WHERE source_channel_id = $1 AND packet_hash = $2
+        ",
+        source_channel_id,
+        packet_hash,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    if transfers.is_empty() {
+        return Ok(());
+    }
+
+    let (
+        instruction_indexes,
+        senders,
+        receivers,
+        base_tokens,
+        base_amounts,
+        base_token_symbols,
+        base_token_names,
+        base_token_decimals,
+        base_token_paths,
+        quote_tokens,
+        quote_amounts,
+    ): (
+        Vec<i32>,
+        Vec<String>,
+        Vec<String>,
+        Vec<String>,
+        Vec<BigDecimal>,
+        Vec<String>,
+        Vec<String>,
+        Vec<i32>,
+        Vec<BigDecimal>,
+        Vec<String>,
+        Vec<BigDecimal>,
+    ) = transfers
+        .into_iter()
+        .map(|t| {
+            (
+                t.instruction_index,
+                t.sender,
+                t.receiver,
+                t.base_token,
+                t.base_amount,
+                t.base_token_symbol,
+                t.base_token_name,
+                t.base_token_decimals,
+                t.base_token_path,
+                t.quote_token,
+                t.quote_amount,
+            )
+        })
+        .multiunzip();
+
+    sqlx::query!("
+        INSERT INTO hubble.transfers (source_channel_id, packet_hash, status, instruction_index, sender, receiver, base_token, base_amount, base_token_symbol, base_token_name, base_token_decimals, base_token_path, quote_token, quote_amount)
+        SELECT $1, $2, $3, unnest($4::int[]), unnest($5::text[]), unnest($6::text[]), unnest($7::text[]), unnest($8::numeric[]), unnest($9::text[]), unnest($10::text[]), unnest($11::int[]), unnest($12::numeric[]), unnest($13::text[]), unnest($14::numeric[])
+        ",
+        source_channel_id, packet_hash, status.as_str(), &instruction_indexes, &senders, &receivers, &base_tokens, &base_amounts, &base_token_symbols, &base_token_names, &base_token_decimals, &base_token_paths, &quote_tokens, &quote_amounts)
+    .execute(tx.as_mut()).await?;
+
+    Ok(())
+}
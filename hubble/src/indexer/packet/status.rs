@@ -0,0 +1,162 @@
+use std::fmt::Display;
+
+use crate::indexer::packet::{postgres::PgPacket, zkgm::is_successful_ack};
+
+/// Status of a transfer, derived from the lifecycle of the packet that carries it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferStatus {
+    /// Sent, but not (yet) received on the destination.
+    Pending,
+    /// Received on the destination, but not (yet) acknowledged on the source.
+    Received,
+    /// Acknowledged with a successful acknowledgement.
+    Acked,
+    /// Acknowledged (or written) with a failure acknowledgement, the funds are refunded.
+    Failed,
+    /// Timed out on the source, the funds are refunded.
+    TimedOut,
+}
+
+impl TransferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferStatus::Pending => "pending",
+            TransferStatus::Received => "received",
+            TransferStatus::Acked => "acked",
+            TransferStatus::Failed => "failed",
+            TransferStatus::TimedOut => "timed-out",
+        }
+    }
+
+    pub fn derive(packet: &PgPacket) -> Self {
+        let acknowledgement = packet
+            .ack
+            .as_ref()
+            .or(packet.write_ack.as_ref())
+            .and_then(|stage| stage.acknowledgement.as_deref());
+
+        if packet.timeout.is_some() {
+            TransferStatus::TimedOut
+        } else if packet.ack.is_some() {
+            match acknowledgement.is_some_and(is_successful_ack) {
+                true => TransferStatus::Acked,
+                false => TransferStatus::Failed,
+            }
+        } else if packet.write_ack.is_some() {
+            match acknowledgement.is_some_and(is_successful_ack) {
+                true => TransferStatus::Received,
+                false => TransferStatus::Failed,
+            }
+        } else if packet.recv.is_some() {
+            TransferStatus::Received
+        } else {
+            TransferStatus::Pending
+        }
+    }
+}
+
+impl Display for TransferStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::sol_types::SolValue;
+    use time::OffsetDateTime;
+    use ucs03_zkgm::com::{Ack, TAG_ACK_FAILURE, TAG_ACK_SUCCESS};
+
+    use super::TransferStatus;
+    use crate::indexer::packet::postgres::{PgPacket, PgPacketStage};
+
+    fn stage(acknowledgement: Option<Vec<u8>>) -> Option<PgPacketStage> {
+        Some(PgPacketStage {
+            chain_id: 1,
+            height: 1,
+            timestamp: OffsetDateTime::UNIX_EPOCH,
+            transaction_hash: "tx".to_string(),
+            acknowledgement,
+        })
+    }
+
+    fn ack(tag: alloy::primitives::U256) -> Option<Vec<u8>> {
+        Some(
+            Ack {
+                tag,
+                inner_ack: Default::default(),
+            }
+            .abi_encode_params(),
+        )
+    }
+
+    fn sent() -> PgPacket {
+        PgPacket {
+            source_channel_id: 1,
+            destination_channel_id: 2,
+            packet_hash: "0x00".to_string(),
+            data: vec![],
+            timeout_height: 0.into(),
+            timeout_timestamp: 0.into(),
+            send: stage(None),
+            recv: None,
+            write_ack: None,
+            ack: None,
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn pending_when_only_sent() {
+        assert_eq!(TransferStatus::derive(&sent()), TransferStatus::Pending);
+    }
+
+    #[test]
+    fn received_when_recv_or_successful_write_ack() {
+        let recv = PgPacket {
+            recv: stage(None),
+            ..sent()
+        };
+        assert_eq!(TransferStatus::derive(&recv), TransferStatus::Received);
+
+        let write_ack = PgPacket {
+            write_ack: stage(ack(TAG_ACK_SUCCESS)),
+            ..recv
+        };
+        assert_eq!(TransferStatus::derive(&write_ack), TransferStatus::Received);
+    }
+
+    #[test]
+    fn acked_or_failed_depending_on_ack() {
+        let acked = PgPacket {
+            ack: stage(ack(TAG_ACK_SUCCESS)),
+            ..sent()
+        };
+        assert_eq!(TransferStatus::derive(&acked), TransferStatus::Acked);
+
+        let failed = PgPacket {
+            ack: stage(ack(TAG_ACK_FAILURE)),
+            ..sent()
+        };
+        assert_eq!(TransferStatus::derive(&failed), TransferStatus::Failed);
+    }
+
+    #[test]
+    fn failed_when_write_ack_is_failure() {
+        let failed = PgPacket {
+            write_ack: stage(ack(TAG_ACK_FAILURE)),
+            ..sent()
+        };
+        assert_eq!(TransferStatus::derive(&failed), TransferStatus::Failed);
+    }
+
+    #[test]
+    fn timed_out_wins() {
+        let timed_out = PgPacket {
+            recv: stage(None),
+            timeout: stage(None),
+            ..sent()
+        };
+        assert_eq!(TransferStatus::derive(&timed_out), TransferStatus::TimedOut);
+    }
+}
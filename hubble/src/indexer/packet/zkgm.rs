@@ -0,0 +1,185 @@
+use alloy::{primitives::U256, sol_types::SolValue};
+use color_eyre::eyre::{eyre, Report};
+use sqlx::types::BigDecimal;
+use tracing::trace;
+use ucs03_zkgm::com::{
+    decode_fungible_asset, Ack, Batch, Instruction, ZkgmPacket, INSTR_VERSION_0, INSTR_VERSION_1,
+    OP_BATCH, OP_FUNGIBLE_ASSET_ORDER, TAG_ACK_SUCCESS,
+};
+
+/// A fungible asset order, decoded from the zkgm packet that carried it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transfer {
+    /// Position of the order in the flattened instruction tree of the packet.
+    pub instruction_index: i32,
+    pub sender: String,
+    pub receiver: String,
+    pub base_token: String,
+    pub base_amount: BigDecimal,
+    pub base_token_symbol: String,
+    pub base_token_name: String,
+    pub base_token_decimals: i32,
+    pub base_token_path: BigDecimal,
+    pub quote_token: String,
+    pub quote_amount: BigDecimal,
+}
+
+/// Decodes all fungible asset orders in a zkgm packet. Packets that are not zkgm
+/// packets (ie. sent by another ibc-union app) yield no transfers.
+pub fn decode_transfers(data: &[u8]) -> Vec<Transfer> {
+    let Ok(zkgm_packet) = ZkgmPacket::abi_decode_params(data, true) else {
+        trace!("not a zkgm packet => no transfers");
+        return vec![];
+    };
+
+    let mut transfers = vec![];
+    if let Err(error) = collect_transfers(&zkgm_packet.instruction, &mut transfers) {
+        trace!("error decoding zkgm instruction: {error} => ignoring remaining instructions");
+    }
+
+    transfers
+}
+
+fn collect_transfers(
+    instruction: &Instruction,
+    transfers: &mut Vec<Transfer>,
+) -> Result<(), Report> {
+    match (instruction.version, instruction.opcode) {
+        (INSTR_VERSION_0, OP_BATCH) => {
+            let batch = Batch::abi_decode_params(&instruction.operand, true)?;
+            for instruction in &batch.instructions {
+                collect_transfers(instruction, transfers)?;
+            }
+        }
+        (INSTR_VERSION_0 | INSTR_VERSION_1, OP_FUNGIBLE_ASSET_ORDER) => {
+            let order = decode_fungible_asset(instruction)?;
+
+            transfers.push(Transfer {
+                instruction_index: transfers.len().try_into().unwrap(),
+                sender: to_hex(&order.sender),
+                receiver: to_hex(&order.receiver),
+                base_token: to_hex(&order.base_token),
+                base_amount: to_big_decimal(order.base_amount)?,
+                base_token_symbol: order.base_token_symbol,
+                base_token_name: order.base_token_name,
+                base_token_decimals: order.base_token_decimals.into(),
+                base_token_path: to_big_decimal(order.base_token_path)?,
+                quote_token: to_hex(&order.quote_token),
+                quote_amount: to_big_decimal(order.quote_amount)?,
+            });
+        }
+        (version, opcode) => {
+            trace!("ignoring instruction (version: {version}, opcode: {opcode})");
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns true if the acknowledgement is a successful zkgm acknowledgement. Anything
+/// that cannot be decoded is considered to be a failure.
+pub fn is_successful_ack(acknowledgement: &[u8]) -> bool {
+    Ack::abi_decode_params(acknowledgement, true).is_ok_and(|ack| ack.tag == TAG_ACK_SUCCESS)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn to_big_decimal(value: U256) -> Result<BigDecimal, Report> {
+    value
+        .to_string()
+        .parse()
+        .map_err(|error| eyre!("cannot convert {value} to decimal: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{primitives::U256, sol_types::SolValue};
+    use ucs03_zkgm::com::{
+        Ack, Batch, FungibleAssetOrder, Instruction, ZkgmPacket, INSTR_VERSION_0, INSTR_VERSION_1,
+        OP_BATCH, OP_FUNGIBLE_ASSET_ORDER, TAG_ACK_FAILURE, TAG_ACK_SUCCESS,
+    };
+
+    use super::{decode_transfers, is_successful_ack};
+
+    fn order(amount: u64) -> Instruction {
+        Instruction {
+            version: INSTR_VERSION_1,
+            opcode: OP_FUNGIBLE_ASSET_ORDER,
+            operand: FungibleAssetOrder {
+                sender: b"sender".to_vec().into(),
+                receiver: b"receiver".to_vec().into(),
+                base_token: b"muno".to_vec().into(),
+                base_amount: U256::from(amount),
+                base_token_symbol: "UNO".into(),
+                base_token_name: "Union".into(),
+                base_token_decimals: 6,
+                base_token_path: U256::ZERO,
+                quote_token: b"quote".to_vec().into(),
+                quote_amount: U256::from(amount),
+            }
+            .abi_encode_params()
+            .into(),
+        }
+    }
+
+    fn packet(instruction: Instruction) -> Vec<u8> {
+        ZkgmPacket {
+            salt: Default::default(),
+            path: U256::ZERO,
+            instruction,
+        }
+        .abi_encode_params()
+    }
+
+    #[test]
+    fn decodes_single_order() {
+        let transfers = decode_transfers(&packet(order(100)));
+
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].instruction_index, 0);
+        assert_eq!(transfers[0].base_amount, 100.into());
+        assert_eq!(transfers[0].base_token_decimals, 6);
+        assert_eq!(transfers[0].sender, format!("0x{}", hex::encode("sender")));
+    }
+
+    #[test]
+    fn decodes_orders_in_batch() {
+        let batch = Instruction {
+            version: INSTR_VERSION_0,
+            opcode: OP_BATCH,
+            operand: Batch {
+                instructions: vec![order(1), order(2)],
+            }
+            .abi_encode_params()
+            .into(),
+        };
+
+        let transfers = decode_transfers(&packet(batch));
+
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[1].instruction_index, 1);
+        assert_eq!(transfers[1].base_amount, 2.into());
+    }
+
+    #[test]
+    fn ignores_non_zkgm_packets() {
+        assert!(decode_transfers(b"ping").is_empty());
+    }
+
+    #[test]
+    fn recognizes_ack_tags() {
+        let ack = |tag| {
+            Ack {
+                tag,
+                inner_ack: Default::default(),
+            }
+            .abi_encode_params()
+        };
+
+        assert!(is_successful_ack(&ack(TAG_ACK_SUCCESS)));
+        assert!(!is_successful_ack(&ack(TAG_ACK_FAILURE)));
+        assert!(!is_successful_ack(b"garbage"));
+    }
+}
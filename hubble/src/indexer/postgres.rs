@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use itertools::Itertools;
 use sqlx::Postgres;
 use time::OffsetDateTime;
 
@@ -241,3 +242,166 @@ pub async fn update_block_status(
 
     Ok(())
 }
+
+/// Channel on which detected reorgs are announced (see `notify_reorg`).
+pub const REORG_NOTIFICATION_CHANNEL: &str = "hubble_reorg";
+
+pub async fn insert_reorg(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: IndexerId,
+    height: BlockHeight,
+    old_hash: BlockHash,
+    new_hash: BlockHash,
+    depth: u64,
+) -> sqlx::Result<()> {
+    let height: i64 = height.try_into().unwrap();
+    let depth: i64 = depth.try_into().unwrap();
+
+    sqlx::query!(
+        "
+        INSERT INTO hubble.reorgs (indexer_id, height, old_hash, new_hash, depth)
+        VALUES ($1, $2, $3, $4, $5)
+        ",
+        indexer_id,
+        height,
+        old_hash,
+        new_hash,
+        depth,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Notifies listeners of the reorg. Notifications are delivered when the transaction commits, so
+/// listeners never observe a reorg before the rewritten block data is visible.
+pub async fn notify_reorg(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    payload: &serde_json::Value,
+) -> sqlx::Result<()> {
+    // not using the query! macro, because it does not support the 'void' result of pg_notify.
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(REORG_NOTIFICATION_CHANNEL)
+        .bind(payload.to_string())
+        .execute(tx.as_mut())
+        .await?;
+
+    Ok(())
+}
+
+/// A part of a historical range, indexed by a single backfill worker.
+#[derive(Clone, Debug)]
+pub struct BackfillSegment {
+    pub range: BlockRange,
+    // next height to index in this segment (== range.end_exclusive when done)
+    pub next_height: BlockHeight,
+    // timestamp of the last indexed block in this segment
+    pub timestamp: Option<OffsetDateTime>,
+}
+
+pub async fn get_backfill_segments(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: IndexerId,
+) -> sqlx::Result<Vec<BackfillSegment>> {
+    let result = sqlx::query!(
+        "
+        SELECT start_height, end_height, next_height, timestamp
+        FROM hubble.backfill_status
+        WHERE indexer_id = $1
+        ORDER BY start_height
+        ",
+        indexer_id,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|r| {
+        let start_inclusive: BlockHeight = r.start_height.try_into().unwrap();
+        let end_exclusive: BlockHeight = r.end_height.try_into().unwrap();
+        BackfillSegment {
+            range: (start_inclusive..end_exclusive).into(),
+            next_height: r.next_height.try_into().unwrap(),
+            timestamp: r.timestamp,
+        }
+    })
+    .collect();
+
+    Ok(result)
+}
+
+pub async fn insert_backfill_segments(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: IndexerId,
+    segments: &[BackfillSegment],
+) -> sqlx::Result<()> {
+    let (start_heights, end_heights, next_heights): (Vec<i64>, Vec<i64>, Vec<i64>) = segments
+        .iter()
+        .map(|segment| {
+            (
+                i64::try_from(segment.range.start_inclusive).unwrap(),
+                i64::try_from(segment.range.end_exclusive).unwrap(),
+                i64::try_from(segment.next_height).unwrap(),
+            )
+        })
+        .multiunzip();
+
+    sqlx::query!(
+        "
+        INSERT INTO hubble.backfill_status (indexer_id, start_height, end_height, next_height)
+        SELECT $1, unnest($2::bigint[]), unnest($3::bigint[]), unnest($4::bigint[])
+        ",
+        indexer_id,
+        &start_heights,
+        &end_heights,
+        &next_heights,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+pub async fn update_backfill_segment(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: IndexerId,
+    start_height: BlockHeight,
+    next_height: BlockHeight,
+    timestamp: OffsetDateTime,
+) -> sqlx::Result<()> {
+    let start_height: i64 = start_height.try_into().unwrap();
+    let next_height: i64 = next_height.try_into().unwrap();
+
+    sqlx::query!(
+        "
+        UPDATE hubble.backfill_status
+        SET next_height = $3, timestamp = $4
+        WHERE indexer_id = $1 AND start_height = $2
+        ",
+        indexer_id,
+        start_height,
+        next_height,
+        timestamp,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_backfill_segments(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: IndexerId,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        "
+        DELETE FROM hubble.backfill_status
+        WHERE indexer_id = $1
+        ",
+        indexer_id,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,322 @@
+use std::collections::HashSet;
+
+use axum::async_trait;
+use futures::{stream::FuturesOrdered, Stream};
+use itertools::Itertools;
+use sqlx::Postgres;
+use tracing::{debug, trace};
+
+use crate::indexer::{
+    api::{BlockHandle, BlockRange, BlockReference, BlockSelection, FetchMode, IndexerError},
+    packet::{
+        delete_packet_events_of_block, event::from_move_event, insert_packet_events_of_block,
+    },
+    sui::{
+        fetcher_client::SuiFetcherClient,
+        postgres::{
+            active_contracts, delete_sui_checkpoint_transactions_events, insert_sui_checkpoint,
+            PgCheckpoint, PgEvent, PgTransaction,
+        },
+        provider::{Checkpoint, RpcProviderId, TransactionBlock},
+    },
+};
+
+#[derive(Clone)]
+pub enum BlockDetails {
+    Lazy(Checkpoint),
+    Eager(Checkpoint, Vec<TransactionBlock>),
+}
+
+#[derive(Clone)]
+pub struct SuiBlockHandle {
+    pub internal_chain_id: i32,
+    pub reference: BlockReference,
+    pub details: BlockDetails,
+    pub sui_client: SuiFetcherClient,
+    pub provider_id: RpcProviderId,
+}
+
+#[async_trait]
+impl BlockHandle for SuiBlockHandle {
+    fn reference(&self) -> BlockReference {
+        self.reference.clone()
+    }
+
+    fn fetch_range(
+        &self,
+        block_range: BlockRange,
+        fetch_mode: FetchMode,
+    ) -> Result<impl Stream<Item = Result<Self, IndexerError>> + Send, IndexerError> {
+        debug!("{}: fetching", block_range);
+
+        Ok(FuturesOrdered::from_iter(
+            block_range.clone().into_iter().map(|height| async move {
+                self.sui_client
+                    .fetch_single_with_provider(
+                        BlockSelection::Height(height),
+                        fetch_mode,
+                        Some(self.provider_id),
+                    )
+                    .await
+            }),
+        ))
+    }
+
+    async fn insert(&self, tx: &mut sqlx::Transaction<'_, Postgres>) -> Result<(), IndexerError> {
+        let reference = self.reference();
+        debug!("{}: inserting", reference);
+
+        let transactions = match &self.details {
+            BlockDetails::Lazy(checkpoint) => {
+                self.sui_client
+                    .fetch_transactions(checkpoint, self.provider_id)
+                    .await?
+            }
+            BlockDetails::Eager(_, transactions) => transactions.clone(),
+        };
+
+        let height: i64 = self.reference.height.try_into().unwrap();
+        let active_contracts =
+            active_contracts(tx, self.internal_chain_id, self.reference.height).await?;
+        trace!("{reference}: active contracts: {}", active_contracts.len());
+
+        let transactions = pg_transactions(
+            &reference,
+            self.internal_chain_id,
+            transactions,
+            &active_contracts,
+        );
+
+        if !transactions.is_empty() {
+            trace!(
+                "{}: transactions with matching events: {}",
+                reference,
+                transactions.len()
+            );
+
+            let packet_events = transactions
+                .iter()
+                .flat_map(|transaction| {
+                    transaction.events.iter().filter_map(|event| {
+                        from_move_event(
+                            transaction.transaction_digest.clone(),
+                            &event.typ,
+                            &event.data,
+                        )
+                    })
+                })
+                .collect_vec();
+
+            insert_sui_checkpoint(
+                tx,
+                PgCheckpoint {
+                    internal_chain_id: self.internal_chain_id,
+                    height,
+                    checkpoint_digest: self.reference.hash.clone(),
+                    timestamp: self.reference.timestamp,
+                    transactions,
+                },
+            )
+            .await?;
+            insert_packet_events_of_block(tx, self.internal_chain_id, &reference, packet_events)
+                .await?;
+        } else {
+            trace!("{}: no matching events: ignore", reference);
+        }
+
+        debug!("{}: done", reference);
+        Ok(())
+    }
+
+    async fn update(&self, tx: &mut sqlx::Transaction<'_, Postgres>) -> Result<(), IndexerError> {
+        let reference = self.reference();
+        debug!("{}: updating", reference);
+
+        delete_sui_checkpoint_transactions_events(
+            tx,
+            self.internal_chain_id,
+            self.reference.height,
+        )
+        .await?;
+        delete_packet_events_of_block(tx, self.internal_chain_id, self.reference.height).await?;
+        self.insert(tx).await?;
+
+        debug!("{}: done", reference);
+        Ok(())
+    }
+}
+
+/// Maps the events of the active contracts to rows, leaving out the transactions without any. Events
+/// are indexed in the order of the checkpoint.
+fn pg_transactions(
+    reference: &BlockReference,
+    internal_chain_id: i32,
+    transactions: Vec<TransactionBlock>,
+    active_contracts: &HashSet<String>,
+) -> Vec<PgTransaction> {
+    let height: i64 = reference.height.try_into().unwrap();
+    let mut event_index_iter = 0..;
+
+    transactions
+        .into_iter()
+        .enumerate()
+        .filter_map(|(transaction_index, transaction)| {
+            let events = transaction
+                .events
+                .into_iter()
+                .filter(|event| active_contracts.contains(&event.package_id))
+                .map(|event| PgEvent {
+                    internal_chain_id,
+                    height,
+                    transaction_digest: transaction.digest.clone(),
+                    event_sequence: event.id.event_seq.try_into().unwrap(),
+                    index: event_index_iter.next().unwrap(),
+                    package_id: event.package_id,
+                    module: event.transaction_module,
+                    sender: event.sender,
+                    typ: event.typ,
+                    data: event.parsed_json,
+                })
+                .collect_vec();
+
+            if events.is_empty() {
+                trace!(
+                    "{reference}: no events of configured packages in {}",
+                    transaction.digest
+                );
+                return None;
+            }
+
+            Some(PgTransaction {
+                internal_chain_id,
+                height,
+                transaction_digest: transaction.digest,
+                transaction_index: transaction_index.try_into().unwrap(),
+                events,
+            })
+        })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::{json, Value};
+    use time::OffsetDateTime;
+
+    use crate::indexer::{
+        api::BlockReference,
+        sui::{block_handle::pg_transactions, postgres::PgTransaction, provider::TransactionBlock},
+    };
+
+    const ZKGM: &str = "0xzkgm";
+
+    fn event(seq: u64, package_id: &str) -> Value {
+        json!({
+            "id": { "txDigest": "ignored", "eventSeq": seq.to_string() },
+            "packageId": package_id,
+            "transactionModule": "zkgm",
+            "sender": "0xsender",
+            "type": format!("{package_id}::zkgm::PacketSend"),
+            "parsedJson": { "seq": seq }
+        })
+    }
+
+    fn transaction(digest: &str, events: Vec<Value>) -> TransactionBlock {
+        serde_json::from_value(json!({ "digest": digest, "events": events })).unwrap()
+    }
+
+    fn get_pg_transactions(transactions: Vec<TransactionBlock>) -> Vec<PgTransaction> {
+        pg_transactions(
+            &BlockReference::new(7, "hash".to_string(), OffsetDateTime::now_utc()),
+            1,
+            transactions,
+            &HashSet::from([ZKGM.to_string()]),
+        )
+    }
+
+    #[test]
+    fn maps_events_to_rows() {
+        let transactions = get_pg_transactions(vec![transaction(
+            "tx",
+            vec![event(0, ZKGM), event(1, ZKGM)],
+        )]);
+
+        let [transaction] = transactions.as_slice() else {
+            panic!("expected one transaction");
+        };
+        assert_eq!(transaction.internal_chain_id, 1);
+        assert_eq!(transaction.height, 7);
+        assert_eq!(transaction.transaction_digest, "tx");
+        assert_eq!(transaction.transaction_index, 0);
+
+        let [first, second] = transaction.events.as_slice() else {
+            panic!("expected two events");
+        };
+        assert_eq!(first.internal_chain_id, 1);
+        assert_eq!(first.height, 7);
+        assert_eq!(first.transaction_digest, "tx");
+        assert_eq!(first.event_sequence, 0);
+        assert_eq!(first.index, 0);
+        assert_eq!(first.package_id, ZKGM);
+        assert_eq!(first.module, "zkgm");
+        assert_eq!(first.sender, "0xsender");
+        assert_eq!(first.typ, "0xzkgm::zkgm::PacketSend");
+        assert_eq!(first.data, json!({ "seq": 0 }));
+        assert_eq!((second.event_sequence, second.index), (1, 1));
+    }
+
+    #[test]
+    fn ignores_events_of_other_packages() {
+        let transactions = get_pg_transactions(vec![transaction(
+            "tx",
+            vec![event(0, "0xother"), event(1, ZKGM)],
+        )]);
+
+        let [transaction] = transactions.as_slice() else {
+            panic!("expected one transaction");
+        };
+        let [event] = transaction.events.as_slice() else {
+            panic!("expected one event");
+        };
+        assert_eq!(event.package_id, ZKGM);
+        assert_eq!(event.event_sequence, 1);
+        // only matching events are indexed
+        assert_eq!(event.index, 0);
+    }
+
+    #[test]
+    fn ignores_transactions_without_matching_events() {
+        let transactions = get_pg_transactions(vec![
+            transaction("tx0", vec![event(0, ZKGM)]),
+            transaction("tx1", vec![event(0, "0xother")]),
+            transaction("tx2", vec![]),
+            transaction("tx3", vec![event(0, ZKGM), event(1, ZKGM)]),
+        ]);
+
+        let summary = transactions
+            .iter()
+            .map(|transaction| {
+                (
+                    transaction.transaction_digest.as_str(),
+                    transaction.transaction_index,
+                    transaction
+                        .events
+                        .iter()
+                        .map(|event| event.index)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // the transaction index is the position in the checkpoint, the event index counts across
+        // transactions
+        assert_eq!(summary, vec![("tx0", 0, vec![0]), ("tx3", 3, vec![1, 2])]);
+    }
+
+    #[test]
+    fn no_transactions() {
+        assert!(get_pg_transactions(vec![]).is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+use color_eyre::eyre::Report;
+use sqlx::PgPool;
+use url::Url;
+
+use crate::indexer::{
+    api::{BlockHeight, IndexerId},
+    sui::{context::SuiContext, fetcher_client::SuiFetcherClient},
+    BackfillConfig, FinalizerConfig, Indexer,
+};
+
+const DEFAULT_CHUNK_SIZE: usize = 20;
+const DEFAULT_TRANSACTIONS_MAX_PAGE_SIZE: u16 = 50;
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Config {
+    pub indexer_id: IndexerId,
+    // sui 'blocks' are checkpoints, hence the heights are checkpoint sequence numbers.
+    pub start_height: BlockHeight,
+    pub chunk_size: Option<usize>,
+    pub rpc_urls: Vec<Url>,
+    // maximum number of transactions fetched in a single multi-get request (sui allows at most 50).
+    pub tx_search_max_page_size: Option<u16>,
+    #[serde(default)]
+    pub finalizer: FinalizerConfig,
+    pub backfill: Option<BackfillConfig>,
+}
+
+impl Config {
+    pub async fn build(self, pg_pool: PgPool) -> Result<Indexer<SuiFetcherClient>, Report> {
+        Ok(Indexer::new(
+            pg_pool,
+            self.indexer_id,
+            self.start_height,
+            self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
+            self.finalizer,
+            self.backfill,
+            SuiContext {
+                rpc_urls: self.rpc_urls,
+                tx_search_max_page_size: self
+                    .tx_search_max_page_size
+                    .unwrap_or(DEFAULT_TRANSACTIONS_MAX_PAGE_SIZE),
+            },
+        ))
+    }
+}
@@ -0,0 +1,25 @@
+use std::fmt::Display;
+
+use url::Url;
+
+#[derive(Clone)]
+pub struct SuiContext {
+    pub rpc_urls: Vec<Url>,
+    pub tx_search_max_page_size: u16,
+}
+
+impl Display for SuiContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rpcs: {}, tx_search_max_page_size: {}",
+            self.rpc_urls
+                .iter()
+                .enumerate()
+                .map(|(index, url)| format!("{}: {}", index, url.as_str()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.tx_search_max_page_size,
+        )
+    }
+}
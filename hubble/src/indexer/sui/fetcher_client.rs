@@ -0,0 +1,199 @@
+use std::fmt::Display;
+
+use axum::async_trait;
+use color_eyre::eyre::Report;
+use time::OffsetDateTime;
+use tokio::task::JoinSet;
+use tracing::{debug, info, info_span, trace, Instrument};
+
+use crate::{
+    indexer::{
+        api::{
+            BlockHeight, BlockReference, BlockReferenceProvider, BlockSelection, FetchMode,
+            FetcherClient, IndexerError, IndexerId,
+        },
+        sui::{
+            block_handle::{BlockDetails, SuiBlockHandle},
+            context::SuiContext,
+            provider::{Checkpoint, Provider, RpcProviderId, TransactionBlock},
+        },
+    },
+    postgres::{fetch_or_insert_chain_id_tx, ChainId},
+};
+
+impl BlockReferenceProvider for Checkpoint {
+    fn block_reference(&self) -> Result<BlockReference, Report> {
+        let timestamp_ms: i128 = self.timestamp_ms.into();
+        Ok(BlockReference {
+            height: self.sequence_number,
+            hash: self.digest.clone(),
+            timestamp: OffsetDateTime::from_unix_timestamp_nanos(timestamp_ms * 1_000_000)
+                .map_err(Report::from)?,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct SuiFetcherClient {
+    pub chain_id: ChainId,
+    pub provider: Provider,
+    pub tx_search_max_page_size: u16,
+}
+
+impl Display for SuiFetcherClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "chain_id: {}", self.chain_id)
+    }
+}
+
+impl SuiFetcherClient {
+    pub async fn fetch_single_with_provider(
+        &self,
+        selection: BlockSelection,
+        mode: FetchMode,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<SuiBlockHandle, IndexerError> {
+        debug!("{}: fetching", selection);
+
+        match selection {
+            BlockSelection::LastFinalized => self.fetch_last_finalized(mode, provider_id).await,
+            BlockSelection::Height(height) => self.fetch_at_height(mode, provider_id, height).await,
+        }
+    }
+
+    // checkpoints are final once they are certified, so the latest checkpoint is the last finalized
+    // block. sui has no reorgs, but the finalizer still monitors the hashes.
+    async fn fetch_last_finalized(
+        &self,
+        mode: FetchMode,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<SuiBlockHandle, IndexerError> {
+        trace!("fetch latest checkpoint");
+
+        let result = self
+            .provider
+            .get_latest_checkpoint_sequence_number(provider_id)
+            .await?;
+
+        trace!(
+            "latest checkpoint: {} using {:?} to fetch checkpoint",
+            result.response,
+            result.provider_id
+        );
+
+        self.fetch_at_height(mode, Some(result.provider_id), result.response)
+            .await
+    }
+
+    async fn fetch_at_height(
+        &self,
+        mode: FetchMode,
+        provider_id: Option<RpcProviderId>,
+        height: BlockHeight,
+    ) -> Result<SuiBlockHandle, IndexerError> {
+        trace!("fetching checkpoint {height}");
+
+        let result = self.provider.get_checkpoint(height, provider_id).await?;
+
+        let Some(checkpoint) = result.response else {
+            info!("{}: does not exist", height);
+            return Err(IndexerError::NoBlock(BlockSelection::Height(height)));
+        };
+
+        trace!(
+            "fetched checkpoint {height} using {:?}: {} transactions",
+            result.provider_id,
+            checkpoint.transactions.len(),
+        );
+
+        Ok(SuiBlockHandle {
+            internal_chain_id: self.chain_id.db,
+            reference: checkpoint.block_reference()?,
+            details: match mode {
+                FetchMode::Lazy => BlockDetails::Lazy(checkpoint),
+                FetchMode::Eager => {
+                    let transactions = self
+                        .fetch_transactions(&checkpoint, result.provider_id)
+                        .await?;
+                    BlockDetails::Eager(checkpoint, transactions)
+                }
+            },
+            sui_client: self.clone(),
+            provider_id: result.provider_id,
+        })
+    }
+
+    pub async fn fetch_transactions(
+        &self,
+        checkpoint: &Checkpoint,
+        provider_id: RpcProviderId,
+    ) -> Result<Vec<TransactionBlock>, IndexerError> {
+        trace!(
+            "fetching transactions for checkpoint {}: {}",
+            checkpoint.sequence_number,
+            checkpoint.transactions.len(),
+        );
+
+        let mut result = Vec::with_capacity(checkpoint.transactions.len());
+
+        for digests in checkpoint
+            .transactions
+            .chunks(self.tx_search_max_page_size.into())
+        {
+            result.extend(
+                self.provider
+                    .get_transaction_blocks(digests, Some(provider_id))
+                    .await?
+                    .response,
+            );
+        }
+
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl FetcherClient for SuiFetcherClient {
+    type BlockHandle = SuiBlockHandle;
+    type Context = SuiContext;
+
+    async fn create(
+        pg_pool: sqlx::PgPool,
+        indexer_id: IndexerId,
+        _join_set: &mut JoinSet<Result<(), IndexerError>>,
+        context: SuiContext,
+    ) -> Result<Self, IndexerError> {
+        let provider = Provider::new(indexer_id, context.rpc_urls)?;
+
+        info!("fetching chain-id from node");
+        let chain_id = provider.get_chain_identifier(None).await?.response;
+        info!("fetched chain-id from node: {}", chain_id);
+
+        let indexing_span = info_span!("indexer", chain_id = chain_id).or_current();
+        async move {
+            let mut tx = pg_pool.begin().await?;
+
+            let chain_id = fetch_or_insert_chain_id_tx(&mut tx, chain_id.to_string())
+                .await?
+                .get_inner_logged();
+
+            tx.commit().await?;
+
+            Ok(SuiFetcherClient {
+                chain_id,
+                provider,
+                tx_search_max_page_size: context.tx_search_max_page_size,
+            })
+        }
+        .instrument(indexing_span)
+        .await
+    }
+
+    async fn fetch_single(
+        &self,
+        selection: BlockSelection,
+        mode: FetchMode,
+    ) -> Result<Self::BlockHandle, IndexerError> {
+        self.fetch_single_with_provider(selection, mode, None).await
+    }
+}
@@ -0,0 +1,16 @@
+use color_eyre::eyre::Report;
+
+use super::api::IndexerError;
+
+mod block_handle;
+pub mod config;
+mod context;
+mod fetcher_client;
+mod postgres;
+mod provider;
+
+impl From<jsonrpsee::core::client::Error> for IndexerError {
+    fn from(error: jsonrpsee::core::client::Error) -> Self {
+        Self::ProviderError(Report::from(error))
+    }
+}
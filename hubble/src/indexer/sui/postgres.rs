@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use tracing::trace;
+
+use crate::{indexer::api::BlockHeight, postgres::schedule_replication_reset};
+
+/// DTO corresponding to the v1_sui.checkpoints table.
+pub struct PgCheckpoint {
+    pub internal_chain_id: i32,
+    pub height: i64,
+    pub checkpoint_digest: String,
+    pub timestamp: OffsetDateTime,
+    pub transactions: Vec<PgTransaction>,
+}
+
+/// DTO corresponding to the v1_sui.transactions table.
+pub struct PgTransaction {
+    pub internal_chain_id: i32,
+    pub height: i64,
+    pub transaction_digest: String,
+    pub transaction_index: i64,
+    pub events: Vec<PgEvent>,
+}
+
+/// DTO corresponding to the v1_sui.events table.
+pub struct PgEvent {
+    pub internal_chain_id: i32,
+    pub height: i64,
+    pub transaction_digest: String,
+    pub event_sequence: i64,
+    pub index: i64,
+    pub package_id: String,
+    pub module: String,
+    pub sender: String,
+    pub typ: String,
+    pub data: Value,
+}
+
+pub async fn insert_sui_checkpoint(
+    tx: &mut Transaction<'_, Postgres>,
+    checkpoint: PgCheckpoint,
+) -> sqlx::Result<()> {
+    trace!("insert: {}", checkpoint.height);
+    sqlx::query!(
+        "
+        INSERT INTO v1_sui.checkpoints (
+            internal_chain_id,
+            height,
+            checkpoint_digest,
+            timestamp
+        ) VALUES ($1, $2, $3, $4);
+        ",
+        checkpoint.internal_chain_id,
+        checkpoint.height,
+        checkpoint.checkpoint_digest,
+        checkpoint.timestamp,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    for transaction in checkpoint.transactions {
+        trace!(
+            "insert: {}/{}",
+            checkpoint.height,
+            transaction.transaction_digest
+        );
+        sqlx::query!(
+            "
+            INSERT INTO v1_sui.transactions (
+                internal_chain_id,
+                height,
+                transaction_digest,
+                transaction_index
+            ) VALUES ($1, $2, $3, $4);
+            ",
+            transaction.internal_chain_id,
+            transaction.height,
+            transaction.transaction_digest,
+            transaction.transaction_index,
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        for event in transaction.events {
+            trace!(
+                "insert: {}/{}#{} ({}) {}",
+                checkpoint.height,
+                transaction.transaction_digest,
+                event.event_sequence,
+                event.package_id,
+                event.typ,
+            );
+            sqlx::query!(
+                "
+                INSERT INTO v1_sui.events (
+                    internal_chain_id,
+                    height,
+                    transaction_digest,
+                    event_sequence,
+                    index,
+                    package_id,
+                    module,
+                    sender,
+                    type,
+                    data
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);
+                ",
+                event.internal_chain_id,
+                event.height,
+                event.transaction_digest,
+                event.event_sequence,
+                event.index,
+                event.package_id,
+                event.module,
+                event.sender,
+                event.typ,
+                event.data
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn delete_sui_checkpoint_transactions_events(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+) -> sqlx::Result<()> {
+    let height: i64 = height.try_into().unwrap();
+    sqlx::query!(
+        "
+        DELETE FROM v1_sui.events WHERE internal_chain_id = $1 AND height = $2
+        ",
+        internal_chain_id,
+        height,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    sqlx::query!(
+        "
+        DELETE FROM v1_sui.transactions WHERE internal_chain_id = $1 AND height = $2
+        ",
+        internal_chain_id,
+        height,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    sqlx::query!(
+        "
+        DELETE FROM v1_sui.checkpoints WHERE internal_chain_id = $1 AND height = $2
+        ",
+        internal_chain_id,
+        height,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    schedule_replication_reset(tx, internal_chain_id, height, "block reorg (delete)").await?;
+
+    Ok(())
+}
+
+pub async fn active_contracts(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+) -> sqlx::Result<HashSet<String>> {
+    let height: i64 = height.try_into().unwrap();
+
+    let result = sqlx::query!(
+        r#"
+        SELECT    address
+        FROM      v1_sui.contracts
+        WHERE     internal_chain_id = $1
+        AND       $2 between start_height and end_height
+        "#,
+        internal_chain_id,
+        height,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| record.address)
+    .collect();
+
+    Ok(result)
+}
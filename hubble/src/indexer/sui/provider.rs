@@ -0,0 +1,173 @@
+use std::result::Result;
+
+use jsonrpsee::{
+    core::client::{ClientT, Error},
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+    indexer::api::{BlockHeight, IndexerError, IndexerId},
+    race_client::{RaceClient, RaceClientId, RaceClientResponse},
+};
+
+#[derive(Clone, Debug)]
+pub struct Provider {
+    pub rpc_client: RaceClient<HttpClient>,
+}
+
+#[derive(Clone, Debug, Copy)]
+pub struct RpcProviderId {
+    race_client_id: RaceClientId,
+}
+
+impl From<RpcProviderId> for RaceClientId {
+    fn from(value: RpcProviderId) -> Self {
+        value.race_client_id
+    }
+}
+
+#[derive(Debug)]
+pub struct RpcResult<T> {
+    pub provider_id: RpcProviderId,
+    pub response: T,
+}
+
+impl<T> RpcResult<T> {
+    fn new(race_client_id: RaceClientId, result: T) -> Self {
+        Self {
+            provider_id: RpcProviderId { race_client_id },
+            response: result,
+        }
+    }
+}
+
+impl<T> From<RaceClientResponse<T>> for RpcResult<T> {
+    fn from(value: RaceClientResponse<T>) -> Self {
+        RpcResult::new(value.race_client_id, value.response)
+    }
+}
+
+/// Subset of the `Checkpoint` returned by `sui_getCheckpoint`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    #[serde(with = "serde_utils::string")]
+    pub sequence_number: u64,
+    pub digest: String,
+    #[serde(with = "serde_utils::string")]
+    pub timestamp_ms: u64,
+    pub transactions: Vec<String>,
+}
+
+/// Subset of the `SuiTransactionBlockResponse` returned by `sui_multiGetTransactionBlocks`
+/// (requested with `showEvents`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionBlock {
+    pub digest: String,
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+/// Subset of the `SuiEvent` returned by `sui_multiGetTransactionBlocks`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub id: EventId,
+    pub package_id: String,
+    pub transaction_module: String,
+    pub sender: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+    pub parsed_json: Value,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventId {
+    pub tx_digest: String,
+    #[serde(with = "serde_utils::string")]
+    pub event_seq: u64,
+}
+
+impl Provider {
+    pub fn new(indexer_id: IndexerId, rpc_urls: Vec<Url>) -> Result<Self, IndexerError> {
+        Ok(Self {
+            rpc_client: RaceClient::new(
+                indexer_id,
+                rpc_urls
+                    .into_iter()
+                    .map(|rpc_url| {
+                        HttpClientBuilder::default()
+                            .build(rpc_url.as_str())
+                            .map(|client| (rpc_url, client))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+        })
+    }
+
+    // RPC
+    pub async fn get_chain_identifier(
+        &self,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<String>, Error> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.request("sui_getChainIdentifier", rpc_params![])
+            })
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn get_latest_checkpoint_sequence_number(
+        &self,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<BlockHeight>, Error> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| async move {
+                let sequence_number: String = c
+                    .request("sui_getLatestCheckpointSequenceNumber", rpc_params![])
+                    .await?;
+
+                sequence_number
+                    .parse()
+                    .map_err(|error| Error::Custom(format!("invalid sequence number: {error}")))
+            })
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn get_checkpoint(
+        &self,
+        height: BlockHeight,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<Option<Checkpoint>>, Error> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.request("sui_getCheckpoint", rpc_params![height.to_string()])
+            })
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn get_transaction_blocks(
+        &self,
+        digests: &[String],
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<Vec<TransactionBlock>>, Error> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.request(
+                    "sui_multiGetTransactionBlocks",
+                    rpc_params![digests, serde_json::json!({ "showEvents": true })],
+                )
+            })
+            .await
+            .map(Into::into)
+    }
+}
@@ -17,6 +17,9 @@ use crate::indexer::{
     api::{
         BlockHandle, BlockRange, BlockReference, BlockReferenceProvider, FetchMode, IndexerError,
     },
+    packet::{
+        delete_packet_events_of_block, event::from_tendermint_event, insert_packet_events_of_block,
+    },
     tendermint::{
         fetcher_client::TmFetcherClient,
         postgres::{
@@ -296,9 +299,23 @@ impl BlockHandle for TmBlockHandle {
                 filtered_events.len(),
             );
 
+            let packet_events = filtered_events
+                .iter()
+                .filter_map(|event| {
+                    from_tendermint_event(event.transaction_hash.clone()?, &event.data)
+                })
+                .collect_vec();
+
             insert_batch_blocks(tx, vec![block]).await?;
             insert_batch_transactions(tx, filtered_transactions).await?;
             insert_batch_events(tx, filtered_events).await?;
+            insert_packet_events_of_block(
+                tx,
+                self.tm_client.chain_id.db,
+                &reference,
+                packet_events,
+            )
+            .await?;
         } else {
             trace!("{}: ignore (no events for registered contracts)", reference);
         }
@@ -313,6 +330,8 @@ impl BlockHandle for TmBlockHandle {
 
         delete_tm_block_transactions_events(tx, self.tm_client.chain_id.db, self.reference.height)
             .await?;
+        delete_packet_events_of_block(tx, self.tm_client.chain_id.db, self.reference.height)
+            .await?;
         self.insert(tx).await?;
 
         debug!("{}: done", reference);
@@ -5,7 +5,7 @@ use url::Url;
 use crate::indexer::{
     api::{BlockHeight, IndexerId},
     tendermint::{context::TmContext, fetcher_client::TmFetcherClient},
-    FinalizerConfig, Indexer,
+    BackfillConfig, FinalizerConfig, Indexer,
 };
 
 const DEFAULT_CHUNK_SIZE: usize = 20;
@@ -20,6 +20,7 @@ pub struct Config {
     pub tx_search_max_page_size: Option<u8>,
     #[serde(default)]
     pub finalizer: FinalizerConfig,
+    pub backfill: Option<BackfillConfig>,
 }
 
 impl Config {
@@ -30,6 +31,7 @@ impl Config {
             self.start_height,
             self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
             self.finalizer,
+            self.backfill,
             TmContext {
                 rpc_urls: self.rpc_urls,
                 tx_search_max_page_size: self
@@ -24,7 +24,7 @@ use crate::{
     indexer::{
         api::{
             BlockHeight, BlockRange, BlockReferenceProvider, BlockSelection, FetchMode,
-            FetcherClient, IndexerError,
+            FetcherClient, IndexerError, IndexerId,
         },
         tendermint::{
             block_handle::{BlockDetails, BlockHeader, TmBlockHandle},
@@ -522,10 +522,17 @@ impl FetcherClient for TmFetcherClient {
 
     async fn create(
         pg_pool: sqlx::PgPool,
-        _join_set: &mut JoinSet<Result<(), IndexerError>>,
+        indexer_id: IndexerId,
+        join_set: &mut JoinSet<Result<(), IndexerError>>,
         context: TmContext,
     ) -> Result<Self, IndexerError> {
-        let provider = Provider::new(context.rpc_urls).await?;
+        let provider = Provider::new(indexer_id, context.rpc_urls).await?;
+
+        let monitor_provider = provider.clone();
+        join_set.spawn(
+            async move { monitor_provider.monitor_heads().await }
+                .instrument(info_span!("rpc-health")),
+        );
 
         info!("fetching chain-id from node");
         let chain_id = provider
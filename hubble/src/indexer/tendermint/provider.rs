@@ -16,7 +16,7 @@ use unionlabs::aptos::block_info::BlockHeight;
 use url::Url;
 
 use crate::{
-    indexer::api::IndexerError,
+    indexer::api::{IndexerError, IndexerId},
     race_client::{RaceClient, RaceClientId, RaceClientResponse},
 };
 
@@ -69,15 +69,16 @@ impl<T> From<RaceClientResponse<T>> for RpcResult<T> {
 }
 
 impl Provider {
-    pub async fn new(rpc_urls: Vec<Url>) -> Result<Self, IndexerError> {
+    pub async fn new(indexer_id: IndexerId, rpc_urls: Vec<Url>) -> Result<Self, IndexerError> {
         Ok(Self {
             rpc_client: {
                 RaceClient::new(
-                    future::join_all(
-                        rpc_urls
-                            .into_iter()
-                            .map(|rpc_url| Client::new(rpc_url.as_str().to_owned())),
-                    )
+                    indexer_id,
+                    future::join_all(rpc_urls.into_iter().map(|rpc_url| async move {
+                        Client::new(rpc_url.as_str().to_owned())
+                            .await
+                            .map(|client| (rpc_url, client))
+                    }))
                     .await
                     .into_iter()
                     .collect::<Result<Vec<_>, _>>()?,
@@ -86,6 +87,16 @@ impl Provider {
         })
     }
 
+    pub async fn monitor_heads(&self) -> Result<(), IndexerError> {
+        self.rpc_client
+            .monitor_heads(|c| async move {
+                c.status()
+                    .await
+                    .map(|status| status.sync_info.latest_block_height)
+            })
+            .await
+    }
+
     pub async fn status(
         &self,
         provider_id: Option<RpcProviderId>,
@@ -1,5 +1,7 @@
 use lazy_static::lazy_static;
-use prometheus::{IntCounterVec, Opts, Registry};
+use prometheus::{
+    GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry,
+};
 use reqwest::StatusCode;
 
 lazy_static! {
@@ -25,6 +27,64 @@ lazy_static! {
         &["chain_id"]
     )
     .expect("register TRANSACTION_COLLECTOR");
+    pub static ref REORG_DEPTH: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("reorg_depth", "Depth of detected reorgs (in blocks)")
+            .namespace("hubble")
+            .subsystem("finalizer")
+            .buckets(vec![1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 50.0, 100.0]),
+        &["indexer_id"]
+    )
+    .expect("register REORG_DEPTH");
+    pub static ref RPC_ENDPOINT_SCORE: GaugeVec = GaugeVec::new(
+        Opts::new(
+            "endpoint_score",
+            "Score of an rpc endpoint (0: quarantined, 1: best)"
+        )
+        .namespace("hubble")
+        .subsystem("rpc"),
+        &["indexer_id", "endpoint"]
+    )
+    .expect("register RPC_ENDPOINT_SCORE");
+    pub static ref RPC_ENDPOINT_LATENCY: GaugeVec = GaugeVec::new(
+        Opts::new(
+            "endpoint_latency_seconds",
+            "Moving average of the latency of an rpc endpoint"
+        )
+        .namespace("hubble")
+        .subsystem("rpc"),
+        &["indexer_id", "endpoint"]
+    )
+    .expect("register RPC_ENDPOINT_LATENCY");
+    pub static ref RPC_ENDPOINT_ERROR_RATE: GaugeVec = GaugeVec::new(
+        Opts::new(
+            "endpoint_error_rate",
+            "Moving average of failed requests to an rpc endpoint"
+        )
+        .namespace("hubble")
+        .subsystem("rpc"),
+        &["indexer_id", "endpoint"]
+    )
+    .expect("register RPC_ENDPOINT_ERROR_RATE");
+    pub static ref RPC_ENDPOINT_HEAD_LAG: IntGaugeVec = IntGaugeVec::new(
+        Opts::new(
+            "endpoint_head_lag",
+            "Blocks an rpc endpoint is behind the highest head"
+        )
+        .namespace("hubble")
+        .subsystem("rpc"),
+        &["indexer_id", "endpoint"]
+    )
+    .expect("register RPC_ENDPOINT_HEAD_LAG");
+    pub static ref RPC_ENDPOINT_QUARANTINED: IntGaugeVec = IntGaugeVec::new(
+        Opts::new(
+            "endpoint_quarantined",
+            "1 if an rpc endpoint is quarantined"
+        )
+        .namespace("hubble")
+        .subsystem("rpc"),
+        &["indexer_id", "endpoint"]
+    )
+    .expect("register RPC_ENDPOINT_QUARANTINED");
 }
 
 pub fn register_custom_metrics() {
@@ -37,6 +97,24 @@ pub fn register_custom_metrics() {
     REGISTRY
         .register(Box::new(TRANSACTION_COLLECTOR.clone()))
         .expect("TRANSACTION_COLLECTOR can be registered");
+    REGISTRY
+        .register(Box::new(REORG_DEPTH.clone()))
+        .expect("REORG_DEPTH can be registered");
+    REGISTRY
+        .register(Box::new(RPC_ENDPOINT_SCORE.clone()))
+        .expect("RPC_ENDPOINT_SCORE can be registered");
+    REGISTRY
+        .register(Box::new(RPC_ENDPOINT_LATENCY.clone()))
+        .expect("RPC_ENDPOINT_LATENCY can be registered");
+    REGISTRY
+        .register(Box::new(RPC_ENDPOINT_ERROR_RATE.clone()))
+        .expect("RPC_ENDPOINT_ERROR_RATE can be registered");
+    REGISTRY
+        .register(Box::new(RPC_ENDPOINT_HEAD_LAG.clone()))
+        .expect("RPC_ENDPOINT_HEAD_LAG can be registered");
+    REGISTRY
+        .register(Box::new(RPC_ENDPOINT_QUARANTINED.clone()))
+        .expect("RPC_ENDPOINT_QUARANTINED can be registered");
 }
 
 #[axum::debug_handler]
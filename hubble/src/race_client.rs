@@ -1,11 +1,38 @@
 use core::{fmt::Debug, future::Future};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use futures::{stream::FuturesUnordered, StreamExt};
-use tracing::debug;
+use futures::{future::join_all, stream::FuturesUnordered, StreamExt};
+use tokio::time::{sleep, Instant};
+use tracing::{debug, info, warn};
+use url::Url;
+
+use crate::{
+    indexer::api::{BlockHeight, IndexerError, IndexerId},
+    metrics::{
+        RPC_ENDPOINT_ERROR_RATE, RPC_ENDPOINT_HEAD_LAG, RPC_ENDPOINT_LATENCY,
+        RPC_ENDPOINT_QUARANTINED, RPC_ENDPOINT_SCORE,
+    },
+};
+
+// delay between starting requests to the next (lower scored) endpoint of a race.
+const HEDGE_DELAY: Duration = Duration::from_millis(100);
+// weight of the latest observation in the latency and error rate averages.
+const EWMA_WEIGHT: f64 = 0.2;
+// number of consecutive failures (while other endpoints succeed) before an endpoint is quarantined.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+// number of blocks an endpoint can be behind the highest head before it is quarantined.
+const MAX_HEAD_LAG: u64 = 10;
+const MIN_QUARANTINE: Duration = Duration::from_secs(5);
+const MAX_QUARANTINE: Duration = Duration::from_secs(300);
+const HEAD_PROBE_INTERVAL: Duration = Duration::from_secs(10);
 
 #[derive(Debug)]
 pub struct RaceClient<C> {
     pub clients: Vec<C>,
+    health: Arc<Vec<Mutex<EndpointHealth>>>,
 }
 
 #[derive(Clone, Debug, Copy)]
@@ -30,13 +57,219 @@ impl<T> RaceClientResponse<T> {
 impl<C: Clone> Clone for RaceClient<C> {
     fn clone(&self) -> Self {
         let clients = self.clients.clone();
-        Self { clients }
+        let health = self.health.clone();
+        Self { clients, health }
+    }
+}
+
+/// Observed health of an endpoint. Shared between clones of the race client, so all users of
+/// an endpoint contribute to (and benefit from) its score.
+#[derive(Debug)]
+struct EndpointHealth {
+    // used as metrics label, endpoints can be shared by indexers
+    indexer_id: IndexerId,
+    // used as metrics label (scheme, host and port of the url; the path can contain api keys)
+    endpoint: String,
+    // moving average of the latency (in seconds) of successful requests
+    latency: f64,
+    // moving average of failed requests (0: never fails, 1: always fails)
+    error_rate: f64,
+    head: Option<BlockHeight>,
+    head_lag: u64,
+    consecutive_failures: u32,
+    // number of successive quarantines, used for the backoff
+    quarantines: u32,
+    quarantined_until: Option<Instant>,
+}
+
+impl EndpointHealth {
+    fn new(indexer_id: IndexerId, url: &Url) -> Self {
+        Self {
+            indexer_id,
+            endpoint: url.origin().ascii_serialization(),
+            latency: 0.0,
+            error_rate: 0.0,
+            head: None,
+            head_lag: 0,
+            consecutive_failures: 0,
+            quarantines: 0,
+            quarantined_until: None,
+        }
+    }
+
+    fn is_quarantined(&self, now: Instant) -> bool {
+        self.quarantined_until.is_some_and(|until| until > now)
+    }
+
+    /// Higher is better: a fast, reliable endpoint that is at the head scores close to 1.
+    fn score(&self, now: Instant) -> f64 {
+        if self.is_quarantined(now) {
+            return 0.0;
+        }
+
+        (1.0 - self.error_rate) / (1.0 + self.latency) / (1.0 + self.head_lag as f64)
+    }
+
+    fn record_success(&mut self, latency: Duration) {
+        self.latency = ewma(self.latency, latency.as_secs_f64());
+        self.error_rate = ewma(self.error_rate, 0.0);
+        self.consecutive_failures = 0;
+
+        if self.head_lag <= MAX_HEAD_LAG && !self.is_quarantined(Instant::now()) {
+            self.quarantines = 0;
+        }
+    }
+
+    fn record_failure(&mut self) {
+        self.error_rate = ewma(self.error_rate, 1.0);
+        self.consecutive_failures += 1;
+
+        if self.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+            self.quarantine("consecutive failures");
+        }
+    }
+
+    fn record_head(&mut self, head: BlockHeight, highest: BlockHeight) {
+        self.head = Some(head);
+        self.head_lag = highest.saturating_sub(head);
+
+        if self.head_lag > MAX_HEAD_LAG {
+            self.quarantine("head lag");
+        }
+    }
+
+    fn quarantine(&mut self, reason: &str) {
+        let now = Instant::now();
+        if self.is_quarantined(now) {
+            return;
+        }
+
+        let backoff = MIN_QUARANTINE
+            .saturating_mul(2_u32.saturating_pow(self.quarantines))
+            .min(MAX_QUARANTINE);
+
+        warn!(
+            "{}: quarantined for {}s ({reason}, failures: {}, head lag: {})",
+            self.endpoint,
+            backoff.as_secs(),
+            self.consecutive_failures,
+            self.head_lag
+        );
+
+        self.quarantines += 1;
+        self.consecutive_failures = 0;
+        self.quarantined_until = Some(now + backoff);
     }
+
+    fn update_metrics(&self) {
+        let labels = [self.indexer_id.as_str(), self.endpoint.as_str()];
+
+        RPC_ENDPOINT_SCORE
+            .with_label_values(&labels)
+            .set(self.score(Instant::now()));
+        RPC_ENDPOINT_LATENCY
+            .with_label_values(&labels)
+            .set(self.latency);
+        RPC_ENDPOINT_ERROR_RATE
+            .with_label_values(&labels)
+            .set(self.error_rate);
+        RPC_ENDPOINT_HEAD_LAG
+            .with_label_values(&labels)
+            .set(self.head_lag.try_into().unwrap_or(i64::MAX));
+        RPC_ENDPOINT_QUARANTINED
+            .with_label_values(&labels)
+            .set(self.is_quarantined(Instant::now()).into());
+    }
+}
+
+fn ewma(average: f64, observation: f64) -> f64 {
+    average * (1.0 - EWMA_WEIGHT) + observation * EWMA_WEIGHT
 }
 
 impl<C> RaceClient<C> {
-    pub fn new(clients: Vec<C>) -> Self {
-        Self { clients }
+    pub fn new(indexer_id: IndexerId, endpoints: Vec<(Url, C)>) -> Self {
+        let (health, clients) = endpoints
+            .into_iter()
+            .map(|(url, client)| {
+                (
+                    Mutex::new(EndpointHealth::new(indexer_id.clone(), &url)),
+                    client,
+                )
+            })
+            .unzip();
+
+        Self {
+            clients,
+            health: Arc::new(health),
+        }
+    }
+
+    /// Indices of the clients to race, best scored first. Quarantined endpoints are skipped,
+    /// unless all endpoints are quarantined.
+    fn candidates(&self, race_client_id: Option<RaceClientId>) -> Vec<usize> {
+        if let Some(id) = race_client_id {
+            return vec![id.index];
+        }
+
+        let now = Instant::now();
+        let (available, quarantined): (Vec<_>, Vec<_>) = self
+            .health
+            .iter()
+            .enumerate()
+            .map(|(i, health)| {
+                let health = health.lock().unwrap();
+                (i, health.score(now), health.is_quarantined(now))
+            })
+            .partition(|(_, _, quarantined)| !quarantined);
+
+        let mut candidates = match available.is_empty() {
+            true => quarantined,
+            false => available,
+        };
+        candidates.sort_by(|(_, a, _), (_, b, _)| b.total_cmp(a));
+
+        candidates.into_iter().map(|(i, _, _)| i).collect()
+    }
+
+    fn with_health(&self, index: usize, f: impl FnOnce(&mut EndpointHealth)) {
+        let mut health = self.health[index].lock().unwrap();
+        f(&mut health);
+        health.update_metrics();
+    }
+
+    fn record_success(&self, index: usize, latency: Duration) {
+        self.with_health(index, |health| health.record_success(latency));
+    }
+
+    /// Only called for endpoints that failed while another endpoint succeeded. When all endpoints
+    /// fail, the request itself is most likely the problem (ie. a block that does not exist yet).
+    fn record_failures(&self, indices: Vec<usize>) {
+        for index in indices {
+            self.with_health(index, EndpointHealth::record_failure);
+        }
+    }
+
+    /// Races the closure over the candidates. The best scored endpoint starts immediately,
+    /// each next endpoint starts `HEDGE_DELAY` later.
+    fn hedged<'a, T, FUT: Future<Output = T> + 'a, F: Fn(&'a C) -> FUT>(
+        &'a self,
+        race_client_id: Option<RaceClientId>,
+        f: F,
+    ) -> FuturesUnordered<impl Future<Output = (usize, Duration, T)> + 'a> {
+        self.candidates(race_client_id)
+            .into_iter()
+            .enumerate()
+            .map(|(rank, i)| {
+                let f = f(&self.clients[i]);
+                let delay = HEDGE_DELAY * rank.try_into().unwrap();
+                async move {
+                    sleep(delay).await;
+                    let start = Instant::now();
+                    let res = f.await;
+                    (i, start.elapsed(), res)
+                }
+            })
+            .collect()
     }
 
     /// Run the provided closure over the clients, returning the first encountered Ok, or if all error, the first
@@ -52,28 +285,20 @@ impl<C> RaceClient<C> {
         race_client_id: Option<RaceClientId>,
         f: F,
     ) -> Result<RaceClientResponse<T>, E> {
-        let mut futures: FuturesUnordered<_> = self
-            .clients
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| race_client_id.as_ref().is_none_or(|id| &id.index == i))
-            .map(|(i, c)| {
-                let f = f(c);
-                async move {
-                    let res = f.await;
-                    (i, res)
-                }
-            })
-            .collect();
+        let mut futures = self.hedged(race_client_id, f);
         let mut error = None;
+        let mut failed = vec![];
 
         loop {
             match futures.next().await {
-                Some((i, Ok(res))) => {
+                Some((i, latency, Ok(res))) => {
+                    self.record_success(i, latency);
+                    self.record_failures(failed);
                     return Ok(RaceClientResponse::new(i, res));
                 }
-                Some((_, Err(err))) => {
+                Some((i, _, Err(err))) => {
                     debug!("error racing client requests: {:?}", err);
+                    failed.push(i);
                     if error.is_none() {
                         error = Some(err)
                     }
@@ -98,29 +323,24 @@ impl<C> RaceClient<C> {
         race_client_id: Option<RaceClientId>,
         f: F,
     ) -> Result<Option<RaceClientResponse<T>>, E> {
-        let mut futures: FuturesUnordered<_> = self
-            .clients
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| race_client_id.as_ref().is_none_or(|id| &id.index == i))
-            .map(|(i, c)| {
-                let f = f(c);
-                async move {
-                    let res = f.await;
-                    (i, res)
-                }
-            })
-            .collect();
+        let mut futures = self.hedged(race_client_id, f);
         let mut error = None;
+        let mut failed = vec![];
 
         loop {
             match futures.next().await {
-                Some((i, Ok(Some(res)))) => {
+                Some((i, latency, Ok(Some(res)))) => {
+                    self.record_success(i, latency);
+                    self.record_failures(failed);
                     return Ok(Some(RaceClientResponse::new(i, res)));
                 }
-                Some((_, Ok(None))) => continue,
-                Some((_, Err(err))) => {
+                Some((i, latency, Ok(None))) => {
+                    self.record_success(i, latency);
+                    continue;
+                }
+                Some((i, _, Err(err))) => {
                     debug!("error racing client requests: {:?}", err);
+                    failed.push(i);
                     if error.is_none() {
                         error = Some(err)
                     }
@@ -133,4 +353,140 @@ impl<C> RaceClient<C> {
         }
         Ok(None)
     }
+
+    /// Fetches the head from every endpoint (including quarantined ones, so they can recover)
+    /// and records the lag of each endpoint relative to the highest head.
+    pub async fn probe_heads<
+        'a,
+        E: Debug,
+        FUT: Future<Output = Result<BlockHeight, E>> + 'a,
+        F: Fn(&'a C) -> FUT,
+    >(
+        &'a self,
+        f: F,
+    ) {
+        let results = join_all(self.clients.iter().enumerate().map(|(i, c)| {
+            let f = f(c);
+            async move {
+                let start = Instant::now();
+                let res = f.await;
+                (i, start.elapsed(), res)
+            }
+        }))
+        .await;
+
+        let highest = results
+            .iter()
+            .filter_map(|(_, _, res)| res.as_ref().ok())
+            .max()
+            .copied();
+
+        for (i, latency, res) in results {
+            match (res, highest) {
+                (Ok(head), Some(highest)) => self.with_health(i, |health| {
+                    health.record_success(latency);
+                    health.record_head(head, highest);
+                }),
+                (Ok(_), None) => unreachable!("highest exists when a probe succeeded"),
+                (Err(err), _) => {
+                    debug!("error probing head: {:?}", err);
+                    self.with_health(i, EndpointHealth::record_failure);
+                }
+            }
+        }
+    }
+
+    /// Probes the heads of the endpoints forever. Intended to be spawned next to the indexer.
+    pub async fn monitor_heads<
+        'a,
+        E: Debug,
+        FUT: Future<Output = Result<BlockHeight, E>> + 'a,
+        F: Fn(&'a C) -> FUT,
+    >(
+        &'a self,
+        f: F,
+    ) -> Result<(), IndexerError> {
+        info!(
+            "monitoring heads of {} endpoint(s) (interval: {}s)",
+            self.clients.len(),
+            HEAD_PROBE_INTERVAL.as_secs()
+        );
+
+        loop {
+            self.probe_heads(&f).await;
+            sleep(HEAD_PROBE_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+    use url::Url;
+
+    use super::{EndpointHealth, MAX_CONSECUTIVE_FAILURES, MAX_HEAD_LAG};
+
+    fn health() -> EndpointHealth {
+        EndpointHealth::new(
+            "indexer".to_string(),
+            &Url::parse("https://rpc.example.com/secret-key").unwrap(),
+        )
+    }
+
+    #[test]
+    fn label_omits_path() {
+        assert_eq!(health().endpoint, "https://rpc.example.com");
+    }
+
+    #[test]
+    fn faster_endpoint_scores_higher() {
+        let mut fast = health();
+        let mut slow = health();
+
+        fast.record_success(Duration::from_millis(50));
+        slow.record_success(Duration::from_secs(2));
+
+        assert!(fast.score(Instant::now()) > slow.score(Instant::now()));
+    }
+
+    #[test]
+    fn quarantined_after_consecutive_failures() {
+        let mut health = health();
+
+        for _ in 0..MAX_CONSECUTIVE_FAILURES - 1 {
+            health.record_failure();
+        }
+        assert!(!health.is_quarantined(Instant::now()));
+
+        health.record_failure();
+        assert!(health.is_quarantined(Instant::now()));
+        assert_eq!(health.score(Instant::now()), 0.0);
+    }
+
+    #[test]
+    fn quarantined_when_lagging() {
+        let mut health = health();
+
+        health.record_head(100, 100 + MAX_HEAD_LAG);
+        assert!(!health.is_quarantined(Instant::now()));
+
+        health.record_head(100, 101 + MAX_HEAD_LAG);
+        assert!(health.is_quarantined(Instant::now()));
+    }
+
+    #[test]
+    fn quarantine_backs_off() {
+        let mut health = health();
+
+        health.quarantine("test");
+        let first = health.quarantined_until.unwrap();
+
+        health.quarantined_until = None;
+        health.quarantine("test");
+        let second = health.quarantined_until.unwrap();
+
+        assert!(second - Instant::now() > first - Instant::now());
+    }
 }
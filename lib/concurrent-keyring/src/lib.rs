@@ -7,6 +7,7 @@ use std::{
 };
 
 use crossbeam_queue::ArrayQueue;
+use dashmap::DashSet;
 use futures::{Future, FutureExt};
 use rand::prelude::SliceRandom;
 use serde::{Deserialize, Serialize};
@@ -30,7 +31,7 @@ pub struct SignerBalance<A> {
     pub denom: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct ConcurrentKeyring<A: Hash + Eq, S> {
     pub name: Arc<String>,
 
@@ -42,6 +43,25 @@ pub struct ConcurrentKeyring<A: Hash + Eq, S> {
     addresses_buffer: Arc<ArrayQueue<A>>,
 
     signers: Arc<HashMap<A, S>>,
+
+    /// Keys with a balance below this are skipped by [`Self::with`], as set by [`Self::record_balances`].
+    min_balance: Option<u128>,
+    low_balance: Arc<DashSet<A>>,
+}
+
+// manual impl to avoid the `S: Clone` bound, since the signers are behind an `Arc`
+impl<A: Hash + Eq, S> Clone for ConcurrentKeyring<A, S> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            address_to_key: self.address_to_key.clone(),
+            key_to_address: self.key_to_address.clone(),
+            addresses_buffer: self.addresses_buffer.clone(),
+            signers: self.signers.clone(),
+            min_balance: self.min_balance,
+            low_balance: self.low_balance.clone(),
+        }
+    }
 }
 
 pub struct KeyringEntry<A, S> {
@@ -82,6 +102,45 @@ impl<A: Hash + Eq + Clone + Display, S: 'static> ConcurrentKeyring<A, S> {
             key_to_address: Arc::new(key_to_address),
             addresses_buffer: Arc::new(addresses_buffer),
             signers: Arc::new(signers),
+            min_balance: None,
+            low_balance: Arc::new(DashSet::new()),
+        }
+    }
+
+    /// Skip keys with a balance below `min_balance` until they are topped up again.
+    #[must_use]
+    pub fn with_min_balance(mut self, min_balance: Option<u128>) -> Self {
+        self.min_balance = min_balance;
+        self
+    }
+
+    /// Updates the set of keys below the minimum balance of this keyring, if one is configured.
+    pub fn record_balances(&self, balances: &[SignerBalance<A>]) {
+        let Some(min_balance) = self.min_balance else {
+            return;
+        };
+
+        for balance in balances {
+            if balance.balance < min_balance {
+                warn!(
+                    keyring = %self.name,
+                    key_name = %balance.key_name,
+                    address = %balance.address,
+                    balance = balance.balance,
+                    denom = %balance.denom,
+                    min_balance,
+                    "signer balance is below the minimum, skipping key until it is topped up"
+                );
+
+                self.low_balance.insert(balance.address.clone());
+            } else if self.low_balance.remove(&balance.address).is_some() {
+                debug!(
+                    keyring = %self.name,
+                    key_name = %balance.key_name,
+                    address = %balance.address,
+                    "signer balance is above the minimum again"
+                );
+            }
         }
     }
 
@@ -94,10 +153,7 @@ impl<A: Hash + Eq + Clone + Display, S: 'static> ConcurrentKeyring<A, S> {
         F: FnOnce(&'a S) -> Fut + 'a,
         Fut: Future<Output: 'a> + Sized + UnwindSafe + 'a,
     {
-        let Some(address) = self.addresses_buffer.pop() else {
-            debug!(keyring = %self.name, "high traffic in keyring");
-            return None;
-        };
+        let address = self.pop_funded()?;
 
         let key_name = self
             .address_to_key
@@ -136,6 +192,29 @@ impl<A: Hash + Eq + Clone + Display, S: 'static> ConcurrentKeyring<A, S> {
             }
         }
     }
+
+    /// Pops the next address out of the buffer, skipping (and pushing back) any addresses with a low balance.
+    fn pop_funded(&self) -> Option<A> {
+        for _ in 0..self.signers.len() {
+            let Some(address) = self.addresses_buffer.pop() else {
+                debug!(keyring = %self.name, "high traffic in keyring");
+                return None;
+            };
+
+            if !self.low_balance.contains(&address) {
+                return Some(address);
+            }
+
+            self.addresses_buffer
+                .push(address)
+                .ok()
+                .expect("no additional items are added; qed;");
+        }
+
+        warn!(keyring = %self.name, "all keys in keyring have a balance below the minimum");
+
+        None
+    }
 }
 
 #[derive(Default)] // NOTE: Default impl is temporary until the EthereumSignersConfig stuff gets removed/ refactored
@@ -144,6 +223,13 @@ impl<A: Hash + Eq + Clone + Display, S: 'static> ConcurrentKeyring<A, S> {
 pub struct KeyringConfig {
     pub name: String,
     pub keys: Vec<KeyringConfigEntry>,
+    /// Keys with a balance below this are not used to sign transactions.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_utils::string_opt"
+    )]
+    pub min_balance: Option<u128>,
 }
 
 impl KeyringConfigEntry {
@@ -178,3 +264,91 @@ pub enum KeyringConfigEntry {
         key: Vec<u8>,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyring() -> ConcurrentKeyring<&'static str, ()> {
+        ConcurrentKeyring::new(
+            "test",
+            ["a", "b"].into_iter().map(|address| KeyringEntry {
+                name: address.to_owned(),
+                address,
+                signer: (),
+            }),
+        )
+        .with_min_balance(Some(10))
+    }
+
+    fn balance(address: &'static str, balance: u128) -> SignerBalance<&'static str> {
+        SignerBalance {
+            key_name: address.to_owned(),
+            address,
+            balance,
+            denom: "muno".to_owned(),
+        }
+    }
+
+    /// Pops `n` addresses, pushing each back as [`ConcurrentKeyring::with`] does.
+    fn pop_funded_n(keyring: &ConcurrentKeyring<&'static str, ()>, n: usize) -> Vec<Option<&str>> {
+        (0..n)
+            .map(|_| {
+                let address = keyring.pop_funded();
+
+                if let Some(address) = address {
+                    keyring.addresses_buffer.push(address).unwrap();
+                }
+
+                address
+            })
+            .collect()
+    }
+
+    #[test]
+    fn skips_low_balance() {
+        let keyring = keyring();
+
+        keyring.record_balances(&[balance("a", 9), balance("b", 10)]);
+
+        assert_eq!(pop_funded_n(&keyring, 3), [Some("b"); 3]);
+    }
+
+    #[test]
+    fn restores_topped_up() {
+        let keyring = keyring();
+
+        keyring.record_balances(&[balance("a", 9), balance("b", 10)]);
+        assert_eq!(pop_funded_n(&keyring, 2), [Some("b"); 2]);
+
+        keyring.record_balances(&[balance("a", 100), balance("b", 10)]);
+
+        let mut addresses = pop_funded_n(&keyring, 2);
+        addresses.sort();
+        assert_eq!(addresses, [Some("a"), Some("b")]);
+    }
+
+    #[test]
+    fn all_low_balance() {
+        let keyring = keyring();
+
+        keyring.record_balances(&[balance("a", 0), balance("b", 9)]);
+
+        assert_eq!(keyring.pop_funded(), None);
+        // the skipped keys are kept in the buffer
+        assert_eq!(keyring.addresses_buffer.len(), 2);
+
+        keyring.record_balances(&[balance("b", 10)]);
+
+        assert_eq!(keyring.pop_funded(), Some("b"));
+    }
+
+    #[test]
+    fn no_min_balance() {
+        let keyring = keyring().with_min_balance(None);
+
+        keyring.record_balances(&[balance("a", 0), balance("b", 0)]);
+
+        assert_eq!(pop_funded_n(&keyring, 2).iter().flatten().count(), 2);
+    }
+}
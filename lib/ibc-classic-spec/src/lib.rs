@@ -295,6 +295,7 @@ pub enum PathParseError {
 
 #[model]
 #[derive(Enumorph)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Datagram {
     CreateClient(MsgCreateClientData),
     UpdateClient(MsgUpdateClient),
@@ -590,6 +591,7 @@ impl FullEvent {
 }
 
 #[model]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MsgCreateClientData {
     pub msg: MsgCreateClient,
     pub client_type: ClientType,
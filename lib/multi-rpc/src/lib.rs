@@ -0,0 +1,424 @@
+//! A client layer over multiple rpc endpoints of the same chain.
+//!
+//! Requests are sent to the first endpoint, failing over to the next ones in order. Optionally, a quorum can be
+//! configured, in which case [`MultiRpc::read`] and [`MultiRpc::latest_height`] query all endpoints concurrently and
+//! require `quorum` of them to agree on the result.
+
+use std::{fmt::Display, future::Future, num::NonZeroUsize};
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// One or more rpc urls. This deserializes from either a single url or a list of urls, so that existing configs with a
+/// single `rpc_url` keep working.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RpcUrls {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl RpcUrls {
+    #[must_use]
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            RpcUrls::One(url) => vec![url],
+            RpcUrls::Many(urls) => urls,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MultiRpc<C> {
+    endpoints: Vec<Endpoint<C>>,
+    quorum: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, Clone)]
+struct Endpoint<C> {
+    url: String,
+    client: C,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NewError<E> {
+    #[error("no rpc urls configured")]
+    NoUrls,
+    #[error("invalid quorum of {quorum} for {urls} rpc url(s)")]
+    InvalidQuorum { quorum: usize, urls: usize },
+    #[error("connected to {connected} of {urls} rpc url(s), but at least {required} are required")]
+    Connect {
+        connected: usize,
+        urls: usize,
+        required: usize,
+        /// The error of the last endpoint that could not be connected to.
+        #[source]
+        source: E,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error<E> {
+    /// All queried endpoints failed, this is the error of the last one.
+    #[error(transparent)]
+    Rpc(E),
+    #[error("no quorum: {agreeing} of {responses} response(s) agree, but {quorum} are required")]
+    NoQuorum {
+        quorum: usize,
+        agreeing: usize,
+        responses: usize,
+    },
+}
+
+impl<C> MultiRpc<C> {
+    /// Connects to all of the `urls` with `connect`. A `quorum` of `None` disables quorum reads.
+    ///
+    /// Endpoints that can't be connected to are skipped, as long as at least `quorum` (or one, without a quorum) of them
+    /// are connected.
+    pub async fn new<E: Display, Fut>(
+        urls: RpcUrls,
+        quorum: Option<usize>,
+        connect: impl Fn(String) -> Fut,
+    ) -> Result<Self, NewError<E>>
+    where
+        Fut: Future<Output = Result<C, E>>,
+    {
+        let urls = urls.into_vec();
+
+        if urls.is_empty() {
+            return Err(NewError::NoUrls);
+        }
+
+        let quorum = match quorum {
+            Some(quorum) if quorum == 0 || quorum > urls.len() => {
+                return Err(NewError::InvalidQuorum {
+                    quorum,
+                    urls: urls.len(),
+                })
+            }
+            quorum => quorum.and_then(NonZeroUsize::new),
+        };
+
+        let urls_len = urls.len();
+
+        let results = join_all(urls.into_iter().map(|url| {
+            let client = connect(url.clone());
+            async move { (url, client.await) }
+        }))
+        .await;
+
+        let mut endpoints = vec![];
+        let mut last_error = None;
+
+        for (url, result) in results {
+            match result {
+                Ok(client) => endpoints.push(Endpoint { url, client }),
+                Err(err) => {
+                    warn!(%url, %err, "unable to connect to rpc endpoint, skipping it");
+                    last_error = Some(err);
+                }
+            }
+        }
+
+        let required = quorum.map_or(1, NonZeroUsize::get);
+
+        if endpoints.len() < required {
+            return Err(NewError::Connect {
+                connected: endpoints.len(),
+                urls: urls_len,
+                required,
+                source: last_error.expect("at least one endpoint failed to connect; qed;"),
+            });
+        }
+
+        Ok(Self { endpoints, quorum })
+    }
+
+    /// The client of the first endpoint.
+    #[must_use]
+    pub fn primary(&self) -> &C {
+        &self.endpoints[0].client
+    }
+
+    #[must_use]
+    pub fn quorum(&self) -> Option<usize> {
+        self.quorum.map(NonZeroUsize::get)
+    }
+
+    /// Sends the request to each endpoint in order, until one of them succeeds.
+    pub async fn failover<'a, T, E: Display, Fut>(
+        &'a self,
+        f: impl Fn(&'a C) -> Fut,
+    ) -> Result<T, E>
+    where
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut endpoints = self.endpoints.iter().peekable();
+
+        loop {
+            let endpoint = endpoints
+                .next()
+                .expect("there is at least one endpoint; qed;");
+
+            match f(&endpoint.client).await {
+                Ok(value) => return Ok(value),
+                Err(err) if endpoints.peek().is_some() => {
+                    warn!(url = %endpoint.url, %err, "rpc request failed, trying the next endpoint");
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Reads a value from the endpoints. In quorum mode all endpoints are queried concurrently, and at least `quorum` of
+    /// them must return the same value. Otherwise, this is the same as [`Self::failover`].
+    pub async fn read<'a, T: PartialEq, E: Display, Fut>(
+        &'a self,
+        f: impl Fn(&'a C) -> Fut,
+    ) -> Result<T, Error<E>>
+    where
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let Some(quorum) = self.quorum else {
+            return self.failover(f).await.map_err(Error::Rpc);
+        };
+
+        let (values, last_error) = self.query_all(f).await;
+
+        let responses = values.len();
+
+        let mut counts: Vec<(T, usize)> = vec![];
+        for value in values {
+            match counts.iter_mut().find(|(v, _)| *v == value) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((value, 1)),
+            }
+        }
+
+        match counts.into_iter().max_by_key(|(_, count)| *count) {
+            Some((value, count)) if count >= quorum.get() => Ok(value),
+            Some((_, agreeing)) => Err(Error::NoQuorum {
+                quorum: quorum.get(),
+                agreeing,
+                responses,
+            }),
+            None => Err(Error::Rpc(
+                last_error.expect("there is at least one endpoint; qed;"),
+            )),
+        }
+    }
+
+    /// Reads the latest height from the endpoints. In quorum mode all endpoints are queried concurrently, and the highest
+    /// height that at least `quorum` of them have reached is returned, so a single lagging or faulty node can neither
+    /// hold back nor advance the height. Otherwise, this is the same as [`Self::failover`].
+    pub async fn latest_height<'a, T: Ord, E: Display, Fut>(
+        &'a self,
+        f: impl Fn(&'a C) -> Fut,
+    ) -> Result<T, Error<E>>
+    where
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let Some(quorum) = self.quorum else {
+            return self.failover(f).await.map_err(Error::Rpc);
+        };
+
+        let (mut heights, last_error) = self.query_all(f).await;
+
+        if heights.is_empty() {
+            return Err(Error::Rpc(
+                last_error.expect("there is at least one endpoint; qed;"),
+            ));
+        }
+
+        let responses = heights.len();
+
+        if responses < quorum.get() {
+            return Err(Error::NoQuorum {
+                quorum: quorum.get(),
+                agreeing: responses,
+                responses,
+            });
+        }
+
+        heights.sort_unstable_by(|a, b| b.cmp(a));
+
+        Ok(heights.swap_remove(quorum.get() - 1))
+    }
+
+    /// Queries all endpoints concurrently, returning the successful responses and the last error.
+    async fn query_all<'a, T, E: Display, Fut>(
+        &'a self,
+        f: impl Fn(&'a C) -> Fut,
+    ) -> (Vec<T>, Option<E>)
+    where
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let results = join_all(self.endpoints.iter().map(|endpoint| f(&endpoint.client))).await;
+
+        let mut values = vec![];
+        let mut last_error = None;
+
+        for (endpoint, result) in self.endpoints.iter().zip(results) {
+            match result {
+                Ok(value) => values.push(value),
+                Err(err) => {
+                    warn!(url = %endpoint.url, %err, "rpc request failed");
+                    last_error = Some(err);
+                }
+            }
+        }
+
+        debug!(
+            responses = values.len(),
+            endpoints = self.endpoints.len(),
+            "queried all endpoints"
+        );
+
+        (values, last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Endpoints are named after the height they return, or `down`.
+    async fn multi_rpc(urls: &[&str], quorum: Option<usize>) -> MultiRpc<Option<u64>> {
+        MultiRpc::new(
+            RpcUrls::Many(urls.iter().map(|url| (*url).to_owned()).collect()),
+            quorum,
+            |url| async move { Ok::<_, String>(url.parse().ok()) },
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn height(client: &Option<u64>) -> Result<u64, String> {
+        client.ok_or_else(|| "down".to_owned())
+    }
+
+    #[test]
+    fn rpc_urls_deserialize() {
+        assert_eq!(
+            serde_json::from_str::<RpcUrls>(r#""http://a""#).unwrap(),
+            RpcUrls::One("http://a".to_owned())
+        );
+        assert_eq!(
+            serde_json::from_str::<RpcUrls>(r#"["http://a", "http://b"]"#)
+                .unwrap()
+                .into_vec(),
+            vec!["http://a", "http://b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_quorum() {
+        let err = MultiRpc::new(RpcUrls::One("a".to_owned()), Some(2), |url| async move {
+            Ok::<_, String>(url)
+        })
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            NewError::InvalidQuorum { quorum: 2, urls: 1 }
+        ));
+    }
+
+    #[tokio::test]
+    async fn skips_unreachable_endpoints() {
+        let connect = |url: String| async move {
+            if url == "unreachable" {
+                Err("unreachable".to_owned())
+            } else {
+                Ok(url.parse::<u64>().ok())
+            }
+        };
+
+        let urls =
+            |urls: &[&str]| RpcUrls::Many(urls.iter().map(|url| (*url).to_owned()).collect());
+
+        let rpc = MultiRpc::new(urls(&["unreachable", "10"]), None, connect)
+            .await
+            .unwrap();
+        assert_eq!(rpc.failover(height).await, Ok(10));
+
+        let rpc = MultiRpc::new(urls(&["10", "unreachable", "10"]), Some(2), connect)
+            .await
+            .unwrap();
+        assert_eq!(rpc.read(height).await.unwrap(), 10);
+
+        let err = MultiRpc::new(
+            urls(&["unreachable", "unreachable", "10"]),
+            Some(2),
+            connect,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            NewError::Connect {
+                connected: 1,
+                urls: 3,
+                required: 2,
+                ..
+            }
+        ));
+
+        let err = MultiRpc::new(urls(&["unreachable"]), None, connect)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NewError::Connect {
+                connected: 0,
+                required: 1,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn failover() {
+        let rpc = multi_rpc(&["down", "10", "11"], None).await;
+        assert_eq!(rpc.failover(height).await, Ok(10));
+
+        let rpc = multi_rpc(&["down", "down"], None).await;
+        assert_eq!(rpc.failover(height).await, Err("down".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn read_quorum() {
+        let rpc = multi_rpc(&["10", "down", "10", "11"], Some(2)).await;
+        assert_eq!(rpc.read(height).await.unwrap(), 10);
+
+        let rpc = multi_rpc(&["10", "down", "11"], Some(2)).await;
+        assert!(matches!(
+            rpc.read(height).await.unwrap_err(),
+            Error::NoQuorum {
+                quorum: 2,
+                agreeing: 1,
+                responses: 2
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn latest_height_quorum() {
+        let rpc = multi_rpc(&["12", "10", "down", "11"], Some(2)).await;
+        assert_eq!(rpc.latest_height(height).await.unwrap(), 11);
+
+        let rpc = multi_rpc(&["12", "down", "down"], Some(2)).await;
+        assert!(matches!(
+            rpc.latest_height(height).await.unwrap_err(),
+            Error::NoQuorum { .. }
+        ));
+
+        let rpc = multi_rpc(&["down", "down"], Some(2)).await;
+        assert!(matches!(
+            rpc.latest_height(height).await.unwrap_err(),
+            Error::Rpc(_)
+        ));
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
 };
 
 #[model(proto(raw(protos::ibc::core::channel::v1::Channel), into, from))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct Channel {
     pub state: State,
     pub ordering: Order,
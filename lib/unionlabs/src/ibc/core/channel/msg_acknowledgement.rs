@@ -6,6 +6,7 @@ use crate::{
 };
 
 #[model(proto(raw(protos::ibc::core::channel::v1::MsgAcknowledgement)))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct MsgAcknowledgement {
     pub packet: Packet,
     pub acknowledgement: Bytes,
@@ -7,6 +7,7 @@ use crate::{
 };
 
 #[model(proto(raw(protos::ibc::core::channel::v1::MsgChannelOpenAck)))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct MsgChannelOpenAck {
     pub port_id: PortId,
     pub channel_id: ChannelId,
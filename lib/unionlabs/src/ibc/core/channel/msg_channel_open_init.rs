@@ -3,6 +3,7 @@ use macros::model;
 use crate::{ibc::core::channel::channel::Channel, id::PortId};
 
 #[model(proto(raw(protos::ibc::core::channel::v1::MsgChannelOpenInit)))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct MsgChannelOpenInit {
     pub port_id: PortId,
     pub channel: Channel,
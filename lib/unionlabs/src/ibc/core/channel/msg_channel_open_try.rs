@@ -7,6 +7,7 @@ use crate::{
 };
 
 #[model(proto(raw(protos::ibc::core::channel::v1::MsgChannelOpenTry)))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct MsgChannelOpenTry {
     pub port_id: PortId,
     pub channel: Channel,
@@ -5,9 +5,11 @@ use macros::model;
 use crate::ibc::core::{channel::packet::Packet, client::height::Height};
 
 #[model(proto(raw(protos::ibc::core::channel::v1::MsgRecvPacket)))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct MsgTimeout {
     pub packet: Packet,
     #[serde(with = "::serde_utils::hex_string")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     #[debug(wrap = ::serde_utils::fmt::DebugAsHex)]
     pub proof_unreceived: Vec<u8>,
     pub proof_height: Height,
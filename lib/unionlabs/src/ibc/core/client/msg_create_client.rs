@@ -3,6 +3,7 @@ use macros::model;
 use crate::primitives::Bytes;
 
 #[model(proto(raw(protos::ibc::core::client::v1::MsgCreateClient)))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct MsgCreateClient {
     pub client_state: Bytes,
     pub consensus_state: Bytes,
@@ -3,6 +3,7 @@ use macros::model;
 use crate::{id::ClientId, primitives::Bytes};
 
 #[model(proto(raw(protos::ibc::core::client::v1::MsgUpdateClient)))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct MsgUpdateClient {
     pub client_id: ClientId,
     pub client_message: Bytes,
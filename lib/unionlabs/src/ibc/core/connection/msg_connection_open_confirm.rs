@@ -3,6 +3,7 @@ use macros::model;
 use crate::{ibc::core::client::height::Height, id::ConnectionId, primitives::Bytes};
 
 #[model(proto(raw(protos::ibc::core::connection::v1::MsgConnectionOpenConfirm)))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct MsgConnectionOpenConfirm {
     pub connection_id: ConnectionId,
     pub proof_ack: Bytes,
@@ -10,6 +10,7 @@ use crate::{
 };
 
 #[model(proto(raw(protos::ibc::core::connection::v1::MsgConnectionOpenTry)))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct MsgConnectionOpenTry {
     pub client_id: ClientId,
     pub counterparty: Counterparty,
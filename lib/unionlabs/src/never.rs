@@ -13,3 +13,15 @@ impl Display for Never {
 }
 
 impl core::error::Error for Never {}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Never {
+    fn schema_name() -> String {
+        "Never".to_owned()
+    }
+
+    // no value is valid for the empty type
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::Schema::Bool(false)
+    }
+}
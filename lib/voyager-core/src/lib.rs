@@ -323,6 +323,15 @@ pub struct ClientStateMeta {
 
     /// The chain id of the counterparty chain this client tracks.
     pub counterparty_chain_id: ChainId,
+
+    /// The trusting period of this client in nanoseconds, for clients that
+    /// expire if they are not updated within this period of the timestamp of
+    /// their latest consensus state.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub trusting_period: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
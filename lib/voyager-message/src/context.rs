@@ -79,6 +79,9 @@ pub struct Modules {
 
     equivalent_chain_ids: EquivalentChainIds,
 
+    /// The loaded plugins, allowing plugins to query other plugins through voyager.
+    plugins: HashMap<String, ModuleRpcClient>,
+
     // ibc version id => handler
     #[debug(skip)]
     pub ibc_spec_handlers: IbcSpecHandlers,
@@ -273,6 +276,7 @@ impl Context {
         register_ibc_spec_handlers: fn(&mut IbcSpecHandlers),
         ipc_client_request_timeout: Duration,
         cache_config: crate::rpc::server::cache::Config,
+        metrics_endpoint: Option<String>,
     ) -> anyhow::Result<Self> {
         let cancellation_token = CancellationToken::new();
 
@@ -291,6 +295,7 @@ impl Context {
             chain_consensus_types: Default::default(),
             client_consensus_types: Default::default(),
             equivalent_chain_ids,
+            plugins: Default::default(),
             ibc_spec_handlers,
         };
 
@@ -298,7 +303,8 @@ impl Context {
 
         let mut interest_filters = HashMap::default();
 
-        let main_rpc_server = Server::new(cache_config);
+        let main_rpc_server =
+            Server::new(cache_config).context("error creating the persistent cache")?;
 
         info!("spawning {} plugins", plugin_configs.len());
 
@@ -346,6 +352,7 @@ impl Context {
                     tokio::spawn(plugin_child_process(
                         name.clone(),
                         plugin_config.clone(),
+                        metrics_endpoint.clone(),
                         cancellation_token.clone(),
                     ));
 
@@ -610,6 +617,8 @@ impl Context {
             None => return Err(anyhow!("startup error")),
         }
 
+        modules.plugins = plugins.clone();
+
         main_rpc_server.start(Arc::new(modules));
 
         info!("started");
@@ -791,6 +800,19 @@ impl Modules {
             .client())
     }
 
+    pub fn plugin(
+        &self,
+        name: &str,
+    ) -> Result<&reconnecting_jsonrpc_ws_client::Client, PluginNotFound> {
+        Ok(self
+            .plugins
+            .get(name)
+            .ok_or_else(|| PluginNotFound {
+                name: name.to_owned(),
+            })?
+            .client())
+    }
+
     pub fn client_module<'a, 'b, 'c: 'a>(
         &'a self,
         client_type: &ClientType,
@@ -843,6 +865,7 @@ pub struct LoadedModulesInfo {
 async fn plugin_child_process(
     plugin_name: String,
     module_config: PluginConfig,
+    metrics_endpoint: Option<String>,
     cancellation_token: CancellationToken,
 ) {
     let client_socket = ModuleRpcClient::make_socket_path(&plugin_name);
@@ -864,6 +887,9 @@ async fn plugin_child_process(
             &server_socket,
             &module_config.config.to_string(),
         ],
+        metrics_endpoint
+            .as_deref()
+            .map(|endpoint| (crate::metrics::METRICS_ENDPOINT_ENV_VAR, endpoint)),
         cancellation_token,
     )
     .await
@@ -889,17 +915,24 @@ async fn module_child_process<Info: Serialize>(
             &module_config.config.to_string(),
             &serde_json::to_string(&module_config.info).unwrap(),
         ],
+        None,
         cancellation_token,
     )
     .await
 }
 
-async fn lazarus_pit(cmd: &Path, args: &[&str], cancellation_token: CancellationToken) {
+async fn lazarus_pit(
+    cmd: &Path,
+    args: &[&str],
+    env: Option<(&str, &str)>,
+    cancellation_token: CancellationToken,
+) {
     let mut attempt = 0;
 
     loop {
         let mut cmd = tokio::process::Command::new(cmd);
         cmd.args(args);
+        cmd.envs(env);
 
         debug!(%attempt, "spawning plugin child process");
 
@@ -5,10 +5,12 @@ use std::{
     env::VarError,
     fmt::{self, Debug},
     future::Future,
+    marker::PhantomData,
     time::Duration,
 };
 
 use clap::builder::{StringValueParser, TypedValueParser, ValueParserFactory};
+use concurrent_keyring::{ChainKeyring, SignerBalance};
 use futures::FutureExt;
 use jsonrpsee::{
     core::{
@@ -23,7 +25,7 @@ use jsonrpsee::{
         error::{INVALID_PARAMS_CODE, METHOD_NOT_FOUND_CODE, PARSE_ERROR_CODE},
         ErrorObject, Response, ResponsePayload,
     },
-    Extensions, MethodResponse, RpcModule,
+    Extensions, MethodResponse, Methods, RpcModule,
 };
 use macros::model;
 use reth_ipc::{
@@ -31,6 +33,7 @@ use reth_ipc::{
     server::{RpcService, RpcServiceBuilder},
 };
 use rpc::{SelfClientState, SelfConsensusState};
+use schemars::JsonSchema;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, value::RawValue, Value};
 use tracing::{
@@ -53,8 +56,9 @@ use crate::{
     filter::JaqInterestFilter,
     module::{
         ClientBootstrapModuleInfo, ClientBootstrapModuleServer, ClientModuleInfo,
-        ClientModuleServer, ConsensusModuleInfo, ConsensusModuleServer, PluginInfo, PluginServer,
-        ProofModuleInfo, ProofModuleServer, StateModuleInfo, StateModuleServer,
+        ClientModuleServer, ConsensusModuleInfo, ConsensusModuleServer, KeyringServer, PluginInfo,
+        PluginSchemaServer, PluginServer, ProofModuleInfo, ProofModuleServer, StateModuleInfo,
+        StateModuleServer,
     },
     rpc::{json_rpc_error_to_error_object, IbcProof, IbcState, VoyagerRpcClient},
 };
@@ -70,6 +74,8 @@ pub mod pass;
 
 pub mod hook;
 
+pub mod metrics;
+
 pub mod rpc;
 
 pub use reconnecting_jsonrpc_ws_client;
@@ -90,7 +96,7 @@ impl QueueMessage for VoyagerMessage {
 }
 
 /// Simple wrapper around a [`Value`] for raw client ids.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct RawClientId(Value);
 
@@ -286,6 +292,12 @@ pub trait Plugin: PluginServer<Self::Call, Self::Callback> + Sized {
 
     async fn cmd(config: Self::Config, cmd: Self::Cmd);
 
+    /// Additional rpc methods to serve alongside the [`PluginServer`] methods, such as [`KeyringServer`] for plugins
+    /// that sign transactions, or the [`plugin_schema_methods`] for plugins that advertise their message schemas.
+    fn extra_rpc_methods(&self) -> Methods {
+        Methods::new()
+    }
+
     async fn run() {
         init_log();
 
@@ -303,13 +315,22 @@ pub trait Plugin: PluginServer<Self::Call, Self::Callback> + Sized {
 
                 let name = info.name;
 
+                metrics::init_from_env(&name);
+
                 run_server(
                     name.clone(),
                     voyager_socket,
                     config,
                     socket,
                     Self::new,
-                    Self::into_rpc,
+                    |plugin| {
+                        let extra_rpc_methods = plugin.extra_rpc_methods();
+
+                        let mut rpc = plugin.into_rpc();
+                        rpc.merge(extra_rpc_methods)
+                            .expect("extra rpc methods must not conflict with the plugin methods");
+                        rpc
+                    },
                 )
                 .instrument(debug_span!("run_plugin_server", %name))
                 .await
@@ -324,6 +345,49 @@ pub trait Plugin: PluginServer<Self::Call, Self::Callback> + Sized {
     }
 }
 
+#[async_trait]
+impl<T> KeyringServer for T
+where
+    T: ChainKeyring<Signer: 'static> + Send + Sync + 'static,
+{
+    async fn signer_balances(&self, _: &Extensions) -> RpcResult<Vec<SignerBalance<String>>> {
+        let balances = self.balances().await;
+
+        self.keyring().record_balances(&balances);
+
+        Ok(balances
+            .into_iter()
+            .map(|balance| SignerBalance {
+                key_name: balance.key_name,
+                address: balance.address.to_string(),
+                balance: balance.balance,
+                denom: balance.denom,
+            })
+            .collect())
+    }
+}
+
+/// The [`PluginSchemaServer`] methods for a plugin with the `Call` and `Callback` types `C` and `Cb`, to be returned from
+/// [`Plugin::extra_rpc_methods`].
+#[must_use]
+pub fn plugin_schema_methods<C: JsonSchema + 'static, Cb: JsonSchema + 'static>() -> Methods {
+    PluginSchemas::<C, Cb>(PhantomData).into_rpc().into()
+}
+
+struct PluginSchemas<C, Cb>(PhantomData<fn() -> (C, Cb)>);
+
+impl<C: JsonSchema + 'static, Cb: JsonSchema + 'static> PluginSchemaServer
+    for PluginSchemas<C, Cb>
+{
+    fn call_schema(&self) -> RpcResult<Value> {
+        Ok(into_value(schemars::schema_for!(C)))
+    }
+
+    fn callback_schema(&self) -> RpcResult<Value> {
+        Ok(into_value(schemars::schema_for!(Cb)))
+    }
+}
+
 #[allow(async_fn_in_trait)]
 pub trait StateModule<V: IbcSpec>: StateModuleServer<V> + Sized {
     type Config: DeserializeOwned + Clone;
@@ -807,6 +871,17 @@ impl VoyagerClient {
             .map_err(json_rpc_error_to_error_object)
     }
 
+    /// The balances of the signers in the keyring of the plugin `plugin_name`.
+    pub async fn signer_balances(
+        &self,
+        plugin_name: impl Into<String>,
+    ) -> RpcResult<Vec<SignerBalance<String>>> {
+        self.0
+            .signer_balances(plugin_name.into())
+            .await
+            .map_err(json_rpc_error_to_error_object)
+    }
+
     pub async fn client_info<V: IbcSpec>(
         &self,
         chain_id: ChainId,
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use tracing::debug;
+
+/// The environment variable voyager passes its metrics endpoint to plugins in, set on each plugin process when it is
+/// spawned.
+pub const METRICS_ENDPOINT_ENV_VAR: &str = "VOYAGER_METRICS_ENDPOINT";
+
+/// Exports the metrics of this process to the OTLP `endpoint`.
+pub fn init(endpoint: &str, process_name: impl Into<String>) {
+    let exporter = opentelemetry_otlp::MetricExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+        .with_timeout(Duration::from_secs(3))
+        .build()
+        .expect("unable to build metrics exporter");
+
+    let provider = opentelemetry_sdk::metrics::SdkMeterProvider::builder()
+        .with_periodic_exporter(exporter)
+        .with_resource(
+            opentelemetry_sdk::Resource::builder_empty()
+                .with_attributes([KeyValue::new("process.name", process_name.into())])
+                .build(),
+        )
+        .build();
+
+    opentelemetry::global::set_meter_provider(provider);
+}
+
+/// Exports the metrics of a plugin to the endpoint in [`METRICS_ENDPOINT_ENV_VAR`], if it is set.
+pub(crate) fn init_from_env(process_name: &str) {
+    match std::env::var(METRICS_ENDPOINT_ENV_VAR) {
+        Ok(endpoint) => init(&endpoint, process_name),
+        Err(_) => debug!("{METRICS_ENDPOINT_ENV_VAR} is not set, not exporting metrics"),
+    }
+}
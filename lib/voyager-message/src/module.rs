@@ -1,5 +1,6 @@
 use std::collections::VecDeque;
 
+use concurrent_keyring::SignerBalance;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use macros::model;
 use schemars::JsonSchema;
@@ -336,6 +337,28 @@ pub trait Plugin<C: Member, Cb: Member> {
     async fn callback(&self, aggregate: Cb, data: VecDeque<Data>) -> RpcResult<Op<VoyagerMessage>>;
 }
 
+/// Exposed by plugins that advertise JSON schemas for their `Call` and `Callback` types, allowing voyager to validate
+/// the messages for the plugin when they are enqueued. See [`plugin_schema_methods`](crate::plugin_schema_methods).
+#[rpc(client, server, namespace = "schema")]
+pub trait PluginSchema {
+    /// The JSON schema of the `Call` type of this plugin.
+    #[method(name = "call")]
+    fn call_schema(&self) -> RpcResult<Value>;
+
+    /// The JSON schema of the `Callback` type of this plugin.
+    #[method(name = "callback")]
+    fn callback_schema(&self) -> RpcResult<Value>;
+}
+
+/// Exposed by plugins that sign transactions with a [`ConcurrentKeyring`](concurrent_keyring::ConcurrentKeyring),
+/// allowing voyager to monitor the balances of the signers.
+#[rpc(client, server, namespace = "keyring")]
+pub trait Keyring {
+    /// The balances of all of the keys in the keyring of this plugin, with the addresses rendered as strings.
+    #[method(name = "signerBalances", with_extensions)]
+    async fn signer_balances(&self) -> RpcResult<Vec<SignerBalance<String>>>;
+}
+
 #[rpc(
     client,
     server,
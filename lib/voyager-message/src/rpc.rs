@@ -1,3 +1,4 @@
+use concurrent_keyring::SignerBalance;
 use jsonrpsee::{
     self,
     core::RpcResult,
@@ -166,6 +167,15 @@ pub trait VoyagerRpc {
         ibc_spec_id: IbcSpecId,
         consensus_state: Bytes,
     ) -> RpcResult<Value>;
+
+    // =======
+    // plugins
+    // =======
+
+    /// The balances of the signers of a plugin exposing the
+    /// [`Keyring`](crate::module::KeyringServer) rpc methods, queried from the plugin.
+    #[method(name = "signerBalances", with_extensions)]
+    async fn signer_balances(&self, plugin_name: String) -> RpcResult<Vec<SignerBalance<String>>>;
 }
 
 #[model]
@@ -6,6 +6,7 @@ use std::{
 };
 
 use anyhow::{anyhow, Context};
+use concurrent_keyring::SignerBalance;
 use futures::TryFutureExt;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
@@ -25,7 +26,7 @@ use crate::{
     core::{ChainId, ClientInfo, ClientStateMeta, ClientType, IbcInterface, QueryHeight},
     into_value,
     module::{
-        ClientBootstrapModuleClient, ClientModuleClient, ConsensusModuleClient,
+        ClientBootstrapModuleClient, ClientModuleClient, ConsensusModuleClient, KeyringClient,
         RawProofModuleClient, RawStateModuleClient,
     },
     rpc::{
@@ -36,7 +37,7 @@ use crate::{
 };
 
 pub mod cache {
-    use std::{future::Future, time::Duration};
+    use std::{future::Future, sync::Arc, time::Duration};
 
     use futures::TryFutureExt;
     use jsonrpsee::core::RpcResult;
@@ -45,86 +46,327 @@ pub mod cache {
     use schemars::JsonSchema;
     use serde::{de::DeserializeOwned, Deserialize, Serialize};
     use serde_json::Value;
-    use tracing::trace;
-    use unionlabs::ibc::core::client::height::Height;
+    use sqlx::{postgres::PgPoolOptions, types::Json, Executor, PgPool};
+    use tokio::sync::OnceCell;
+    use tracing::{debug, trace, warn};
+    use unionlabs::{ibc::core::client::height::Height, ErrorReporter};
     use voyager_core::{ChainId, IbcSpec, IbcSpecId, IbcStorePathKey};
 
+    use crate::rpc::{IbcProof, ProofType};
+
     #[derive(Debug, Clone)]
     pub struct Cache {
-        state_cache: moka::future::Cache<StateRequest, Value>,
-        state_cache_size_metric: opentelemetry::metrics::Gauge<u64>,
-        state_cache_hit_counter_metric: opentelemetry::metrics::Counter<u64>,
-        state_cache_miss_counter_metric: opentelemetry::metrics::Counter<u64>,
-        // proof_cache: moka::future::Cache,
+        state_cache: Layer,
+        proof_cache: Layer,
+        persistent: Option<PersistentCache>,
     }
 
-    impl Cache {
-        #[allow(clippy::new_without_default)]
-        pub fn new(config: Config) -> Self {
+    /// An in-memory cache of one kind of request, along with its metrics.
+    #[derive(Debug, Clone)]
+    struct Layer {
+        kind: &'static str,
+        cache: moka::future::Cache<StateRequest, Value>,
+        size_metric: opentelemetry::metrics::Gauge<u64>,
+        hit_counter_metric: opentelemetry::metrics::Counter<u64>,
+        miss_counter_metric: opentelemetry::metrics::Counter<u64>,
+    }
+
+    impl Layer {
+        fn new(kind: &'static str, meter: &'static str, config: &CacheConfig) -> Self {
+            let meter = opentelemetry::global::meter(meter);
+
             Self {
-                state_cache: moka::future::CacheBuilder::new(config.state.capacity)
+                kind,
+                cache: moka::future::CacheBuilder::new(config.capacity)
                     // .expire_after()
-                    .time_to_live(Duration::from_secs(config.state.time_to_live))
-                    .time_to_idle(Duration::from_secs(config.state.time_to_idle))
+                    .time_to_live(Duration::from_secs(config.time_to_live))
+                    .time_to_idle(Duration::from_secs(config.time_to_idle))
                     .eviction_policy(EvictionPolicy::lru())
                     .build(),
-                state_cache_size_metric: opentelemetry::global::meter("voyager.cache.state")
-                    .u64_gauge("size")
-                    .build(),
-                state_cache_hit_counter_metric: opentelemetry::global::meter("voyager.cache.state")
-                    .u64_counter("hit")
-                    .build(),
-                state_cache_miss_counter_metric: opentelemetry::global::meter(
-                    "voyager.cache.state",
-                )
-                .u64_counter("miss")
-                .build(),
+                size_metric: meter.u64_gauge("size").build(),
+                hit_counter_metric: meter.u64_counter("hit").build(),
+                miss_counter_metric: meter.u64_counter("miss").build(),
             }
         }
+    }
+
+    impl Cache {
+        /// Creates the cache, failing if the persistent cache is configured with an invalid database url.
+        pub fn new(config: Config) -> sqlx::Result<Self> {
+            Ok(Self {
+                state_cache: Layer::new("state", "voyager.cache.state", &config.state),
+                proof_cache: Layer::new("proof", "voyager.cache.proof", &config.proof),
+                persistent: config
+                    .persistent
+                    .as_ref()
+                    .map(PersistentCache::new)
+                    .transpose()?,
+            })
+        }
 
         pub async fn state<T: Serialize + DeserializeOwned>(
             &self,
             state_request: StateRequest,
             fut: impl Future<Output = RpcResult<Option<T>>>,
         ) -> RpcResult<Option<T>> {
-            let attributes = &[KeyValue::new(
-                "chain_id",
-                state_request.chain_id.to_string(),
-            )];
+            let value = self
+                .get_or_fetch(
+                    &self.state_cache,
+                    state_request,
+                    fut.map_ok(|state| {
+                        Some(
+                            serde_json::to_value(state).expect("serialization is infallible; qed;"),
+                        )
+                        .filter(|state| !state.is_null())
+                    }),
+                )
+                .await?;
+
+            Ok(value.map(|value| {
+                serde_json::from_value(value)
+                    .expect("infallible; only valid values are inserted into the cache; qed;")
+            }))
+        }
+
+        /// Proofs at a fixed height never change, so they are cached the same way as states. `fut` is only polled
+        /// on a cache miss.
+        pub async fn proof(
+            &self,
+            proof_request: StateRequest,
+            fut: impl Future<Output = RpcResult<(Value, ProofType)>>,
+        ) -> RpcResult<IbcProof> {
+            let height = proof_request.height;
+
+            let value = self
+                .get_or_fetch(
+                    &self.proof_cache,
+                    proof_request,
+                    fut.map_ok(|proof| {
+                        Some(
+                            serde_json::to_value(proof).expect("serialization is infallible; qed;"),
+                        )
+                    }),
+                )
+                .await?
+                .expect("proofs are always inserted into the cache; qed;");
+
+            let (proof, proof_type) = serde_json::from_value(value)
+                .expect("infallible; only valid values are inserted into the cache; qed;");
+
+            Ok(IbcProof {
+                proof_type,
+                height,
+                proof,
+            })
+        }
+
+        async fn get_or_fetch(
+            &self,
+            layer: &Layer,
+            request: StateRequest,
+            fut: impl Future<Output = RpcResult<Option<Value>>>,
+        ) -> RpcResult<Option<Value>> {
+            let attributes = &[KeyValue::new("chain_id", request.chain_id.to_string())];
+
+            layer
+                .size_metric
+                .record(layer.cache.entry_count(), attributes);
+
+            if let Some(value) = layer.cache.get(&request).await {
+                layer.hit_counter_metric.add(1, attributes);
 
-            self.state_cache_size_metric
-                .record(self.state_cache.entry_count(), attributes);
+                trace!(%value, "cached value");
+
+                return Ok(Some(value));
+            }
+
+            if let Some(persistent) = &self.persistent {
+                match persistent.get(layer.kind, &request).await {
+                    Ok(Some(value)) => {
+                        layer.hit_counter_metric.add(1, attributes);
+
+                        trace!(%value, "persisted value");
 
-            let init = fut
-                .map_ok(|state| {
-                    serde_json::to_value(state).expect("serialization is infallible; qed;")
+                        layer.cache.insert(request, value.clone()).await;
+
+                        return Ok(Some(value));
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        warn!(err = %ErrorReporter(err), "error reading from the persistent cache");
+                    }
+                }
+            }
+
+            layer.miss_counter_metric.add(1, attributes);
+
+            let value = fut.await?;
+
+            if let Some(value) = &value {
+                if let Some(persistent) = &self.persistent {
+                    if let Err(err) = persistent.insert(layer.kind, &request, value).await {
+                        warn!(err = %ErrorReporter(err), "error writing to the persistent cache");
+                    }
+                }
+
+                layer.cache.insert(request, value.clone()).await;
+            }
+
+            Ok(value)
+        }
+    }
+
+    /// How often the persistent cache is pruned, if a `time_to_live` or `max_entries` is configured.
+    const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+    /// States and proofs persisted in postgres. Entries are keyed by height and are therefore never invalidated, the
+    /// table can be truncated at any time to reclaim space.
+    #[derive(Debug, Clone)]
+    struct PersistentCache {
+        pool: PgPool,
+        migrated: Arc<OnceCell<()>>,
+        time_to_live: Option<u64>,
+        max_entries: Option<u64>,
+    }
+
+    impl PersistentCache {
+        fn new(config: &PersistentCacheConfig) -> sqlx::Result<Self> {
+            let cache = Self {
+                pool: PgPoolOptions::new()
+                    .max_connections(config.max_connections.unwrap_or(10))
+                    .connect_lazy(&config.database_url)?,
+                migrated: Arc::new(OnceCell::new()),
+                time_to_live: config.time_to_live,
+                max_entries: config.max_entries,
+            };
+
+            if cache.time_to_live.is_some() || cache.max_entries.is_some() {
+                tokio::spawn(cache.clone().prune_periodically());
+            }
+
+            Ok(cache)
+        }
+
+        async fn migrate(&self) -> sqlx::Result<()> {
+            self.migrated
+                .get_or_try_init(|| async {
+                    self.pool
+                        .execute(
+                            r#"
+                            CREATE TABLE IF NOT EXISTS voyager_cache(
+                                kind TEXT NOT NULL,
+                                chain_id TEXT NOT NULL,
+                                ibc_spec_id TEXT NOT NULL,
+                                height TEXT NOT NULL,
+                                path TEXT NOT NULL,
+                                value JSONB NOT NULL,
+                                created_at timestamptz NOT NULL DEFAULT now(),
+                                PRIMARY KEY (kind, chain_id, ibc_spec_id, height, path)
+                            );
+
+                            CREATE INDEX IF NOT EXISTS voyager_cache_created_at ON voyager_cache(created_at);
+                            "#,
+                        )
+                        .await
+                        .map(|_| ())
                 })
-                .await?;
+                .await
+                .copied()
+        }
+
+        async fn prune_periodically(self) {
+            let mut interval = tokio::time::interval(PRUNE_INTERVAL);
 
-            if init.is_null() {
-                Ok(None)
-            } else {
-                let entry = self.state_cache.entry(state_request).or_insert(init).await;
+            loop {
+                interval.tick().await;
 
-                if entry.is_fresh() {
-                    self.state_cache_miss_counter_metric.add(1, attributes);
-                } else {
-                    self.state_cache_hit_counter_metric.add(1, attributes);
+                match self.prune().await {
+                    Ok(pruned) => debug!(pruned, "pruned the persistent cache"),
+                    Err(err) => {
+                        warn!(err = %ErrorReporter(err), "error pruning the persistent cache");
+                    }
                 }
+            }
+        }
 
-                let value = entry.into_value();
+        /// Removes the entries older than `time_to_live` and all but the newest `max_entries` entries, returning the
+        /// number of entries removed.
+        async fn prune(&self) -> sqlx::Result<u64> {
+            self.migrate().await?;
 
-                trace!(%value, "cached value");
+            let mut pruned = 0;
 
-                Ok(serde_json::from_value(value)
-                    .expect("infallible; only valid values are inserted into the cache; qed;"))
+            if let Some(time_to_live) = self.time_to_live {
+                pruned += sqlx::query(
+                    "DELETE FROM voyager_cache WHERE created_at < now() - make_interval(secs => $1)",
+                )
+                .bind(time_to_live as f64)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+            }
+
+            if let Some(max_entries) = self.max_entries {
+                pruned += sqlx::query(
+                    "DELETE FROM voyager_cache WHERE ctid IN (SELECT ctid FROM voyager_cache ORDER BY created_at DESC OFFSET $1)",
+                )
+                .bind(i64::try_from(max_entries).unwrap_or(i64::MAX))
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
             }
+
+            Ok(pruned)
+        }
+
+        async fn get(&self, kind: &str, request: &StateRequest) -> sqlx::Result<Option<Value>> {
+            self.migrate().await?;
+
+            sqlx::query_scalar::<_, Json<Value>>(
+                "SELECT value FROM voyager_cache WHERE kind = $1 AND chain_id = $2 AND ibc_spec_id = $3 AND height = $4 AND path = $5",
+            )
+            .bind(kind)
+            .bind(request.chain_id.to_string())
+            .bind(request.ibc_spec_id.to_string())
+            .bind(request.height.to_string())
+            .bind(request.path.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map(|value| value.map(|Json(value)| value))
+        }
+
+        async fn insert(
+            &self,
+            kind: &str,
+            request: &StateRequest,
+            value: &Value,
+        ) -> sqlx::Result<()> {
+            self.migrate().await?;
+
+            sqlx::query(
+                "INSERT INTO voyager_cache (kind, chain_id, ibc_spec_id, height, path, value) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING",
+            )
+            .bind(kind)
+            .bind(request.chain_id.to_string())
+            .bind(request.ibc_spec_id.to_string())
+            .bind(request.height.to_string())
+            .bind(request.path.to_string())
+            .bind(Json(value))
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
         }
     }
 
     #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
     pub struct Config {
         pub state: CacheConfig,
+        /// Disabled (0 capacity) by default.
+        #[serde(default)]
+        pub proof: CacheConfig,
+        /// Persist cached states and proofs in postgres, so that they survive restarts.
+        #[serde(default)]
+        pub persistent: Option<PersistentCacheConfig>,
     }
 
     #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
@@ -134,6 +376,20 @@ pub mod cache {
         pub time_to_idle: u64,
     }
 
+    #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+    pub struct PersistentCacheConfig {
+        pub database_url: String,
+        #[serde(default)]
+        pub max_connections: Option<u32>,
+        /// Entries older than this many seconds are pruned. Entries are kept forever if not set.
+        #[serde(default)]
+        pub time_to_live: Option<u64>,
+        /// Only the newest this many entries are kept. Unlimited if not set.
+        #[serde(default)]
+        pub max_entries: Option<u64>,
+    }
+
+    /// A request for a state or proof at a fixed height.
     #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     pub struct StateRequest {
         chain_id: ChainId,
@@ -185,15 +441,14 @@ pub struct ServerInner {
 }
 
 impl Server {
-    #[allow(clippy::new_without_default)]
-    pub fn new(cache_config: cache::Config) -> Self {
-        Server {
+    pub fn new(cache_config: cache::Config) -> sqlx::Result<Self> {
+        Ok(Server {
             inner: Arc::new(ServerInner {
                 modules: OnceLock::new(),
-                cache: cache::Cache::new(cache_config),
+                cache: cache::Cache::new(cache_config)?,
             }),
             item_id: None,
-        }
+        })
     }
 
     pub fn start(&self, modules: Arc<Modules>) {
@@ -266,6 +521,28 @@ impl ServerInner {
 }
 
 impl Server {
+    #[instrument(skip_all, fields(%plugin_name))]
+    pub async fn signer_balances(
+        &self,
+        plugin_name: &str,
+    ) -> RpcResult<Vec<SignerBalance<String>>> {
+        self.span()
+            .in_scope(|| async {
+                trace!("querying signer balances");
+
+                KeyringClient::signer_balances(
+                    &self
+                        .inner
+                        .modules()?
+                        .plugin(plugin_name)?
+                        .with_id(self.item_id),
+                )
+                .await
+                .map_err(json_rpc_error_to_error_object)
+            })
+            .await
+    }
+
     #[instrument(skip_all, fields(%chain_id, finalized))]
     pub async fn query_latest_height(
         &self,
@@ -595,19 +872,26 @@ impl Server {
                     .proof_module(&chain_id, &ibc_spec_id)?
                     .with_id(self.item_id);
 
-                let (proof, proof_type) = proof_module
-                    .query_ibc_proof_raw(height, path)
-                    .await
-                    .map_err(json_rpc_error_to_error_object)?;
-
-                // TODO: Use valuable here
-                debug!(%proof, ?proof_type, "fetched ibc proof");
+                self.inner
+                    .cache
+                    .proof(
+                        StateRequest::new_raw(
+                            chain_id.clone(),
+                            ibc_spec_id.clone(),
+                            height,
+                            path.clone(),
+                        ),
+                        proof_module
+                            .query_ibc_proof_raw(height, path)
+                            .map_ok(|(proof, proof_type)| {
+                                // TODO: Use valuable here
+                                debug!(%proof, ?proof_type, "fetched ibc proof");
 
-                Ok(IbcProof {
-                    height,
-                    proof,
-                    proof_type,
-                })
+                                (proof, proof_type)
+                            })
+                            .map_err(json_rpc_error_to_error_object),
+                    )
+                    .await
             })
             .await
     }
@@ -676,19 +960,21 @@ impl Server {
                     .proof_module(chain_id, &P::Spec::ID)?
                     .with_id(self.item_id);
 
-                let (proof, proof_type) = proof_module
-                    .query_ibc_proof_raw(height, into_value(path.clone()))
-                    .await
-                    .map_err(json_rpc_error_to_error_object)?;
-
-                // TODO: Use valuable here
-                debug!(%proof, ?proof_type, "fetched ibc proof");
+                self.inner
+                    .cache
+                    .proof(
+                        StateRequest::new::<P>(chain_id.clone(), height, path.clone()),
+                        proof_module
+                            .query_ibc_proof_raw(height, into_value(path.clone()))
+                            .map_ok(|(proof, proof_type)| {
+                                // TODO: Use valuable here
+                                debug!(%proof, ?proof_type, "fetched ibc proof");
 
-                Ok(IbcProof {
-                    height,
-                    proof,
-                    proof_type,
-                })
+                                (proof, proof_type)
+                            })
+                            .map_err(json_rpc_error_to_error_object),
+                    )
+                    .await
             })
             .await
     }
@@ -1123,6 +1409,20 @@ impl VoyagerRpcServer for Server {
             .decode_consensus_state(&client_type, &ibc_interface, &ibc_spec_id, consensus_state)
             .await
     }
+
+    // =======
+    // PLUGINS
+    // =======
+
+    async fn signer_balances(
+        &self,
+        e: &Extensions,
+        plugin_name: String,
+    ) -> RpcResult<Vec<SignerBalance<String>>> {
+        self.with_id(e.try_get().ok().cloned())
+            .signer_balances(&plugin_name)
+            .await
+    }
 }
 
 pub(crate) fn fatal_error(t: impl core::error::Error) -> ErrorObjectOwned {
@@ -1132,3 +1432,108 @@ pub(crate) fn fatal_error(t: impl core::error::Error) -> ErrorObjectOwned {
         None::<()>,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use serde_json::json;
+
+    use super::{cache::*, *};
+    use crate::rpc::ProofType;
+
+    fn proof_cache_config(persistent: Option<PersistentCacheConfig>) -> Config {
+        Config {
+            state: CacheConfig::default(),
+            proof: CacheConfig {
+                capacity: 10,
+                time_to_live: 60,
+                time_to_idle: 60,
+            },
+            persistent,
+        }
+    }
+
+    fn request(chain_id: &str) -> StateRequest {
+        StateRequest::new_raw(
+            ChainId::new(chain_id.to_owned()),
+            IbcSpecId::new("ibc-union".to_owned()),
+            Height::new(1),
+            json!({ "commitment": 1 }),
+        )
+    }
+
+    async fn fetch_proof(cache: &Cache, request: StateRequest, fetched: &AtomicUsize) -> IbcProof {
+        cache
+            .proof(request, async {
+                fetched.fetch_add(1, Ordering::SeqCst);
+                Ok((json!("proof"), ProofType::Membership))
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn proof_is_fetched_once() {
+        let cache = Cache::new(proof_cache_config(None)).unwrap();
+        let fetched = AtomicUsize::new(0);
+
+        for _ in 0..3 {
+            let proof = fetch_proof(&cache, request("chain"), &fetched).await;
+
+            assert_eq!(proof.proof, json!("proof"));
+            assert_eq!(proof.proof_type, ProofType::Membership);
+            assert_eq!(proof.height, Height::new(1));
+        }
+
+        assert_eq!(fetched.load(Ordering::SeqCst), 1);
+
+        // a different request is not served from the cache
+        fetch_proof(&cache, request("other-chain"), &fetched).await;
+
+        assert_eq!(fetched.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn invalid_database_url() {
+        let config = proof_cache_config(Some(PersistentCacheConfig {
+            database_url: "not a url".to_owned(),
+            ..Default::default()
+        }));
+
+        assert!(Cache::new(config).is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs postgres at DATABASE_URL"]
+    async fn proof_is_persisted() {
+        let config = proof_cache_config(Some(PersistentCacheConfig {
+            database_url: std::env::var("DATABASE_URL").unwrap(),
+            ..Default::default()
+        }));
+
+        // unique per run, as the entries outlive the test
+        let chain_id = format!(
+            "chain-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        );
+
+        let fetched = AtomicUsize::new(0);
+
+        fetch_proof(
+            &Cache::new(config.clone()).unwrap(),
+            request(&chain_id),
+            &fetched,
+        )
+        .await;
+
+        // a new cache starts with an empty in-memory layer, so the proof is read from postgres
+        let proof = fetch_proof(&Cache::new(config).unwrap(), request(&chain_id), &fetched).await;
+
+        assert_eq!(proof.proof, json!("proof"));
+        assert_eq!(fetched.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,233 @@
+//! A mock of galoisd, serving the `Prove` and `Poll` endpoints of the galois api without generating real proofs.
+//!
+//! Each request is reported as pending for `--pending-polls` polls, and then done with a zeroed proof. The proof is
+//! only accepted by a cometbls light client built with the `mock-zkp-verifier` feature, so this is only useful for
+//! end-to-end tests of the relayer.
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use clap::Parser;
+use cometbls_groth16_verifier::EXPECTED_PROOF_SIZE;
+use galois_rpc::{
+    poll_response::{PollResponse, ProveRequestDone},
+    prove_response::ProveResponse,
+    zero_knowledge_proof::ZeroKnowledgeProof,
+};
+use prost::Message;
+use protos::union::galois::api::v3 as proto;
+use sha2::Digest;
+use tonic::{
+    body::BoxBody,
+    codec::ProstCodec,
+    codegen::{empty_body, http, Body, BoxFuture, Context, Poll, Service, StdError},
+    server::{Grpc, NamedService, UnaryService},
+    Request, Response, Status,
+};
+use tracing::info;
+use unionlabs::primitives::H256;
+
+#[derive(Parser)]
+struct App {
+    /// The address to serve the galois api on.
+    #[arg(default_value = "0.0.0.0:9999")]
+    listen: SocketAddr,
+    /// The number of polls a request is pending for before it is done.
+    #[arg(long, default_value_t = 1)]
+    pending_polls: u32,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), tonic::transport::Error> {
+    tracing_subscriber::fmt::init();
+
+    let app = App::parse();
+
+    info!(listen = %app.listen, pending_polls = app.pending_polls, "serving mock prover");
+
+    tonic::transport::Server::builder()
+        .add_service(MockProverServer(Arc::new(MockProver::new(
+            app.pending_polls,
+        ))))
+        .serve(app.listen)
+        .await
+}
+
+#[derive(Debug)]
+struct MockProver {
+    pending_polls: u32,
+    /// The number of times each request has been polled, keyed by the hash of the encoded request.
+    polls: Mutex<HashMap<H256, u32>>,
+}
+
+impl MockProver {
+    fn new(pending_polls: u32) -> Self {
+        Self {
+            pending_polls,
+            polls: Mutex::default(),
+        }
+    }
+
+    fn poll(&self, request: proto::PollRequest) -> Result<proto::PollResponse, Status> {
+        let request = request
+            .request
+            .ok_or_else(|| Status::invalid_argument("missing request"))?;
+
+        let key = H256::new(sha2::Sha256::digest(request.encode_to_vec()).into());
+
+        let mut polls = self.polls.lock().expect("lock is not poisoned");
+        let count = polls.entry(key).or_default();
+
+        let height = request.untrusted_header.map(|header| header.height);
+
+        if *count < self.pending_polls {
+            *count += 1;
+
+            info!(%key, ?height, polls = *count, "proof pending");
+
+            return Ok(PollResponse::Pending.into());
+        }
+
+        info!(%key, ?height, "proof done");
+
+        Ok(PollResponse::Done(ProveRequestDone {
+            response: mock_prove_response(),
+        })
+        .into())
+    }
+}
+
+/// A zeroed proof of the size expected by the cometbls light client.
+fn mock_prove_response() -> ProveResponse {
+    ProveResponse {
+        proof: ZeroKnowledgeProof {
+            content: vec![0; EXPECTED_PROOF_SIZE],
+            compressed_content: vec![0; EXPECTED_PROOF_SIZE],
+            evm_proof: vec![0; EXPECTED_PROOF_SIZE],
+            public_inputs: vec![],
+        },
+        trusted_validator_set_root: H256::default(),
+    }
+}
+
+/// The protos are generated without servers, so the service is implemented by hand here, following the layout of the
+/// servers generated by `tonic-build`.
+#[derive(Debug, Clone)]
+struct MockProverServer(Arc<MockProver>);
+
+impl NamedService for MockProverServer {
+    const NAME: &'static str = "union.galois.api.v3.UnionProverAPI";
+}
+
+impl<B> Service<http::Request<B>> for MockProverServer
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let prover = self.0.clone();
+
+        match req.uri().path() {
+            "/union.galois.api.v3.UnionProverAPI/Poll" => Box::pin(async move {
+                Ok(Grpc::new(ProstCodec::default())
+                    .unary(PollSvc(prover), req)
+                    .await)
+            }),
+            "/union.galois.api.v3.UnionProverAPI/Prove" => Box::pin(async move {
+                Ok(Grpc::new(ProstCodec::default())
+                    .unary(ProveSvc(prover), req)
+                    .await)
+            }),
+            _ => Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .header("grpc-status", "12")
+                    .header("content-type", "application/grpc")
+                    .body(empty_body())
+                    .expect("response is valid; qed;"))
+            }),
+        }
+    }
+}
+
+struct PollSvc(Arc<MockProver>);
+
+impl UnaryService<proto::PollRequest> for PollSvc {
+    type Response = proto::PollResponse;
+    type Future = BoxFuture<Response<Self::Response>, Status>;
+
+    fn call(&mut self, request: Request<proto::PollRequest>) -> Self::Future {
+        let response = self.0.poll(request.into_inner()).map(Response::new);
+
+        Box::pin(async move { response })
+    }
+}
+
+/// `Prove` blocks until the proof is done, so the pending polls are skipped.
+struct ProveSvc(Arc<MockProver>);
+
+impl UnaryService<proto::ProveRequest> for ProveSvc {
+    type Response = proto::ProveResponse;
+    type Future = BoxFuture<Response<Self::Response>, Status>;
+
+    fn call(&mut self, request: Request<proto::ProveRequest>) -> Self::Future {
+        info!(
+            height = ?request.get_ref().untrusted_header.as_ref().map(|header| header.height),
+            "proof done"
+        );
+
+        Box::pin(async move { Ok(Response::new(mock_prove_response().into())) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poll(prover: &MockProver, height: i64) -> PollResponse {
+        prover
+            .poll(proto::PollRequest {
+                request: Some(proto::ProveRequest {
+                    untrusted_header: Some(protos::cometbft::types::v1::Header {
+                        height,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            })
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn pending_then_done() {
+        let prover = MockProver::new(2);
+
+        assert_eq!(poll(&prover, 1), PollResponse::Pending);
+        assert_eq!(poll(&prover, 2), PollResponse::Pending);
+        assert_eq!(poll(&prover, 1), PollResponse::Pending);
+        assert!(matches!(poll(&prover, 1), PollResponse::Done(_)));
+        assert!(matches!(poll(&prover, 1), PollResponse::Done(_)));
+        assert_eq!(poll(&prover, 2), PollResponse::Pending);
+    }
+
+    #[test]
+    fn proof_has_expected_size() {
+        let response = mock_prove_response();
+
+        assert_eq!(response.proof.evm_proof.len(), EXPECTED_PROOF_SIZE);
+    }
+}
@@ -6,5 +6,9 @@ _: {
         (crane.buildWorkspaceMember {
           crateDirFromRoot = "tools/devnet-utils";
         }).packages.devnet-utils;
+      packages.mock-galoisd =
+        (crane.buildWorkspaceMember {
+          crateDirFromRoot = "tools/mock-galoisd";
+        }).packages.mock-galoisd;
     };
 }
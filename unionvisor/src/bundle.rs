@@ -1,7 +1,9 @@
 use std::{
-    ffi::OsString,
+    collections::BTreeMap,
+    ffi::{OsStr, OsString},
     fs, io,
-    path::PathBuf,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
 
@@ -115,6 +117,77 @@ pub struct BundleMeta {
     fallback_version: String,
     /// The directory containing a directory for each version
     versions_directory: PathBuf,
+    /// Per version configuration, keyed by version
+    #[serde(default)]
+    versions: BTreeMap<String, VersionConfig>,
+}
+
+/// Configuration of a single version in `bundle/meta.json`, for versions which need to be started differently
+/// than the previous ones:
+///
+/// ```json
+/// {
+///   "versions": {
+///     "v0.26.0": {
+///       "args": ["--x-crisis-skip-assert-invariants"],
+///       "env": { "UNIOND_PRUNING": "nothing" },
+///       "pre_start": [["sh", "-c", "sed -i 's/foo/bar/' $UNIONVISOR_HOME/config/app.toml"]]
+///     }
+///   }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VersionConfig {
+    /// Extra arguments passed to `uniond start`, after the arguments given to unionvisor.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment variables set when calling uniond and the pre-start hooks.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Commands (program followed by its arguments) run in order before uniond is started, such as config
+    /// migrations. They run on every start, and must therefore be idempotent. `UNIONVISOR_HOME` is set to the
+    /// uniond home directory.
+    #[serde(default)]
+    pub pre_start: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Error)]
+pub enum PreStartError {
+    #[error("pre-start hook {0} is empty")]
+    EmptyHook(usize),
+    #[error("cannot run pre-start hook {0:?}")]
+    Spawn(Vec<String>, #[source] io::Error),
+    #[error("pre-start hook {hook:?} exited with code: {code}")]
+    Failed {
+        hook: Vec<String>,
+        code: std::process::ExitStatus,
+    },
+}
+
+impl VersionConfig {
+    /// Runs the pre-start hooks in order, failing on the first hook that does not exit successfully.
+    pub fn run_pre_start(&self, home: &Path) -> Result<(), PreStartError> {
+        for (i, hook) in self.pre_start.iter().enumerate() {
+            let (program, args) = hook.split_first().ok_or(PreStartError::EmptyHook(i))?;
+            info!(target: "unionvisor", "running pre-start hook {:?}", hook);
+            let code = Command::new(program)
+                .args(args)
+                .envs(&self.env)
+                .env("UNIONVISOR_HOME", home)
+                .stderr(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .status()
+                .map_err(|source| PreStartError::Spawn(hook.clone(), source))?;
+            if !code.success() {
+                return Err(PreStartError::Failed {
+                    hook: hook.clone(),
+                    code,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Bundle {
@@ -149,6 +222,37 @@ impl Bundle {
         )
     }
 
+    /// Installs `binary` as version `version` in the bundle. The binary is written next to its destination and
+    /// then moved, so that a partially written binary is never picked up.
+    pub fn install_version(
+        &self,
+        version: impl Into<OsString>,
+        binary: &[u8],
+    ) -> Result<ValidVersionPath, InstallVersionError> {
+        let version = version.into();
+        let dir = self.versions_path().join(&version);
+        fs::create_dir_all(&dir).map_err(|source| InstallVersionError::Io(dir.clone(), source))?;
+
+        let path = dir.join(&self.meta.binary_name);
+        let tmp = path.with_extension("tmp");
+        info!(target: "unionvisor", "installing binary at {}", as_display(path.display()));
+        fs::write(&tmp, binary).map_err(|source| InstallVersionError::Io(tmp.clone(), source))?;
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o755))
+            .map_err(|source| InstallVersionError::Io(tmp.clone(), source))?;
+        fs::rename(&tmp, &path).map_err(|source| InstallVersionError::Io(path.clone(), source))?;
+
+        Ok(self.path_to(version).validate()?)
+    }
+
+    /// The configuration of version `version`, which is empty if the version is not configured in the meta.
+    pub fn version_config(&self, version: &OsStr) -> VersionConfig {
+        version
+            .to_str()
+            .and_then(|version| self.meta.versions.get(version))
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// Provides the full path the the versions directory
     pub fn versions_path(&self) -> PathBuf {
         self.path.join(&self.meta.versions_directory)
@@ -179,6 +283,14 @@ pub fn log_bundle(bundle: &Bundle) {
     info!(target: "unionvisor", ?bundle, genesis=?bundle.genesis_json().into_os_string(), ?versions, "running with bundle" );
 }
 
+#[derive(Debug, Error)]
+pub enum InstallVersionError {
+    #[error("cannot write {0}")]
+    Io(PathBuf, #[source] io::Error),
+    #[error("installed binary is not valid")]
+    ValidateVersionPath(#[from] ValidateVersionPathError),
+}
+
 #[derive(Debug, Error)]
 pub enum NewBundleError {
     #[error("cannot read bundle/meta.json")]
@@ -3,6 +3,7 @@ use std::{
     ffi::OsString,
     fs,
     io::{self},
+    net::SocketAddr,
     path::PathBuf,
     process::Stdio,
 };
@@ -14,10 +15,12 @@ use tracing_subscriber::filter::LevelFilter;
 
 use crate::{
     bundle::{log_bundle, Bundle, NewBundleError, ValidateVersionPathError},
+    fetcher::{FetchBinaryError, Fetcher},
     init::{self, SetSeedsError},
     logging::LogFormat,
-    supervisor::{self, RuntimeError},
-    symlinker::{MakeFallbackLinkError, Symlinker, SymlinkerError},
+    status::StatusServer,
+    supervisor::{self, FetchConfig, HealthCheck, RollbackPolicy, RuntimeError},
+    symlinker::{CurrentVersionError, MakeFallbackLinkError, Symlinker, SymlinkerError},
 };
 
 #[derive(Parser, Clone)]
@@ -124,6 +127,34 @@ pub struct RunCmd {
     /// Milliseconds in between each poll for an upgrade.
     #[arg(short, long, env = "UNIONVISOR_POLL_INTERVAL")]
     poll_interval: Option<u64>,
+
+    /// Seconds a new binary must stay up after an upgrade. If it exits (or fails the liveness check) within
+    /// this window, the home directory is restored from the backup and the previous version is put back.
+    /// 0 disables the health check.
+    #[arg(long, env = "UNIONVISOR_HEALTH_WINDOW", default_value = "0")]
+    health_window: u64,
+
+    /// Url which must respond successfully within the health window, such as `http://localhost:26657/health`.
+    #[arg(long, env = "UNIONVISOR_LIVENESS_URL")]
+    liveness_url: Option<String>,
+
+    /// Address to serve `/status` (JSON) and `/metrics` (Prometheus) on, such as `127.0.0.1:9101`.
+    #[arg(long, env = "UNIONVISOR_STATUS_ADDR")]
+    status_addr: Option<SocketAddr>,
+
+    /// What to do after rolling back a failed upgrade.
+    #[arg(long, env = "UNIONVISOR_ON_FAILED_UPGRADE", value_enum, default_value_t = RollbackPolicy::Halt)]
+    on_failed_upgrade: RollbackPolicy,
+
+    /// Seconds after which fetching an upgrade binary or plan over http times out.
+    #[arg(long, env = "UNIONVISOR_FETCH_TIMEOUT", default_value = "600")]
+    fetch_timeout: u64,
+
+    /// Url of the upgrade plan scheduled on chain, such as
+    /// `http://localhost:1317/cosmos/upgrade/v1beta1/current_plan`. If set, the binary of a scheduled upgrade
+    /// is fetched ahead of the upgrade height.
+    #[arg(long, env = "UNIONVISOR_PLAN_URL")]
+    plan_url: Option<String>,
 }
 
 impl Cli {
@@ -229,12 +260,27 @@ impl RunCmd {
         let bundle = Bundle::new(self.bundle.clone())?;
         log_bundle(&bundle);
         let symlinker = Symlinker::new(root.clone(), bundle);
+        if let Some(addr) = self.status_addr {
+            StatusServer::new(symlinker.clone(), root.join("home/data/upgrade-info.json"))
+                .spawn(addr)
+                .map_err(RunError::Status)?;
+        }
+        let fetcher = Fetcher::new(Duration::from_secs(self.fetch_timeout))?;
         supervisor::run_and_upgrade(
             root,
             logformat,
             &symlinker,
             &self.args,
             Duration::from_millis(self.poll_interval.unwrap_or(6000)),
+            &HealthCheck {
+                window: Duration::from_secs(self.health_window),
+                liveness_url: self.liveness_url.clone(),
+                on_failure: self.on_failed_upgrade,
+            },
+            &FetchConfig {
+                fetcher,
+                plan_url: self.plan_url.clone(),
+            },
         )?;
         Ok(())
     }
@@ -246,6 +292,10 @@ pub enum RunError {
     NewBundle(#[from] NewBundleError),
     #[error("runtime error")]
     Runtime(#[from] RuntimeError),
+    #[error("cannot serve status")]
+    Status(#[source] io::Error),
+    #[error("cannot create fetcher")]
+    Fetcher(#[from] FetchBinaryError),
 }
 
 #[derive(Debug, Error)]
@@ -305,6 +355,9 @@ impl CallCmd {
         let bundle = Bundle::new(self.bundle.clone())?;
         let symlinker = Symlinker::new(root.clone(), bundle);
         let current = symlinker.current_validated()?;
+        let version = symlinker.current_version()?;
+        // Only the environment of the version applies to calls, args and pre-start hooks are for `uniond start`.
+        let config = symlinker.bundle.version_config(&version);
         info!(target: "unionvisor",
             binary = as_display(current.0.display()),
             root = as_display(root.display()),
@@ -322,6 +375,7 @@ impl CallCmd {
 
         let mut child = std::process::Command::new(&current.0)
             .args(&args)
+            .envs(&config.env)
             .stdin(stdin.into())
             .stderr(stderr.into())
             .stdout(stdout.into())
@@ -338,6 +392,8 @@ pub enum CallError {
     NewBundle(#[from] NewBundleError),
     #[error("cannot validating version path")]
     ValidateVersionPath(#[from] ValidateVersionPathError),
+    #[error("cannot get current version")]
+    CurrentVersion(#[from] CurrentVersionError),
     #[error("cannot spawn child process")]
     SpawnChildProcess(#[source] io::Error),
     #[error("child process exited with error")]
@@ -0,0 +1,311 @@
+use std::{collections::BTreeMap, fs, io, path::PathBuf, time::Duration};
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256, Sha512};
+use thiserror::Error;
+use tracing::{debug, info};
+
+use crate::watcher::UpgradeInfo;
+
+/// The binaries of an upgrade, as set in the `info` field of the upgrade plan. This follows the cosmovisor
+/// convention, mapping a platform to a url carrying the checksum of the binary:
+///
+/// ```json
+/// {
+///   "binaries": {
+///     "linux/amd64": "https://example.com/uniond?checksum=sha256:d2627445...",
+///     "linux/arm64": "file:///nix/store/...-uniond/bin/uniond?checksum=sha256:..."
+///   }
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct UpgradeBinaries {
+    /// Urls of the binary per platform (`{os}/{arch}` or `any`).
+    pub binaries: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Error)]
+pub enum FetchBinaryError {
+    #[error("cannot deserialize the upgrade info, please ensure that it adheres to the cosmovisor format")]
+    InvalidInfo(#[from] serde_json::Error),
+    #[error("no binary for platform {0} in the upgrade info")]
+    NoBinaryForPlatform(String),
+    #[error("url {0} does not carry a checksum")]
+    MissingChecksum(String),
+    #[error("unsupported checksum {0}, expected sha256:<hex> or sha512:<hex>")]
+    UnsupportedChecksum(String),
+    #[error("unsupported source {0}, expected a file://, http:// or https:// url")]
+    UnsupportedSource(String),
+    #[error("cannot read {0}")]
+    ReadFile(PathBuf, #[source] io::Error),
+    #[error("cannot build the http client")]
+    Client(#[source] reqwest::Error),
+    #[error("cannot download {0}")]
+    Download(String, #[source] reqwest::Error),
+    #[error("invalid height {0} in the upgrade plan")]
+    InvalidPlanHeight(String),
+    #[error("checksum mismatch for {url}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// A checksum of a binary, parsed from the `checksum` query parameter of its url.
+#[derive(Clone, Debug, PartialEq)]
+enum Checksum {
+    Sha256(String),
+    Sha512(String),
+}
+
+impl Checksum {
+    fn parse(checksum: &str) -> Result<Self, FetchBinaryError> {
+        match checksum.split_once(':') {
+            Some(("sha256", hex)) => Ok(Checksum::Sha256(hex.to_lowercase())),
+            Some(("sha512", hex)) => Ok(Checksum::Sha512(hex.to_lowercase())),
+            _ => Err(FetchBinaryError::UnsupportedChecksum(checksum.to_owned())),
+        }
+    }
+
+    fn verify(&self, url: &str, bytes: &[u8]) -> Result<(), FetchBinaryError> {
+        let (expected, actual) = match self {
+            Checksum::Sha256(expected) => (expected, hex::encode(Sha256::digest(bytes))),
+            Checksum::Sha512(expected) => (expected, hex::encode(Sha512::digest(bytes))),
+        };
+        if *expected != actual {
+            return Err(FetchBinaryError::ChecksumMismatch {
+                url: url.to_owned(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The platform of the running unionvisor, in the cosmovisor format (`linux/amd64`).
+pub fn platform() -> String {
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        arch => arch,
+    };
+    format!("{}/{}", std::env::consts::OS, arch)
+}
+
+/// Splits the `checksum` query parameter from the url, returning the url to fetch and the checksum.
+fn split_checksum(url: &str) -> Result<(String, Checksum), FetchBinaryError> {
+    let (base, query) = url
+        .split_once('?')
+        .ok_or_else(|| FetchBinaryError::MissingChecksum(url.to_owned()))?;
+
+    let mut checksum = None;
+    let mut params = vec![];
+    for param in query.split('&') {
+        match param.strip_prefix("checksum=") {
+            Some(value) => checksum = Some(Checksum::parse(value)?),
+            None => params.push(param),
+        }
+    }
+    let checksum = checksum.ok_or_else(|| FetchBinaryError::MissingChecksum(url.to_owned()))?;
+
+    let url = if params.is_empty() {
+        base.to_owned()
+    } else {
+        format!("{base}?{}", params.join("&"))
+    };
+    Ok((url, checksum))
+}
+
+/// The response of the cosmos-sdk `/cosmos/upgrade/v1beta1/current_plan` REST endpoint.
+#[derive(Clone, Debug, Deserialize)]
+struct CurrentPlanResponse {
+    plan: Option<Plan>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Plan {
+    name: String,
+    height: String,
+    #[serde(default)]
+    info: String,
+}
+
+impl CurrentPlanResponse {
+    fn into_upgrade_info(self) -> Result<Option<UpgradeInfo>, FetchBinaryError> {
+        self.plan
+            .map(|plan| {
+                Ok(UpgradeInfo {
+                    height: plan
+                        .height
+                        .parse()
+                        .map_err(|_| FetchBinaryError::InvalidPlanHeight(plan.height.clone()))?,
+                    name: plan.name,
+                    info: (!plan.info.is_empty()).then_some(plan.info),
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Fetches upgrade binaries and plans. Http requests are bounded by the timeout of the client, so a stalled
+/// download fails instead of blocking the upgrade.
+#[derive(Clone, Debug)]
+pub struct Fetcher {
+    client: reqwest::blocking::Client,
+}
+
+impl Fetcher {
+    pub fn new(timeout: Duration) -> Result<Self, FetchBinaryError> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(FetchBinaryError::Client)?;
+        Ok(Self { client })
+    }
+
+    fn read(&self, url: &str) -> Result<Vec<u8>, FetchBinaryError> {
+        if let Some(path) = url.strip_prefix("file://") {
+            let path = PathBuf::from(path);
+            fs::read(&path).map_err(|source| FetchBinaryError::ReadFile(path, source))
+        } else if url.starts_with("http://") || url.starts_with("https://") {
+            self.client
+                .get(url)
+                .send()
+                .and_then(reqwest::blocking::Response::error_for_status)
+                .and_then(|response| response.bytes())
+                .map(|bytes| bytes.to_vec())
+                .map_err(|source| FetchBinaryError::Download(url.to_owned(), source))
+        } else {
+            Err(FetchBinaryError::UnsupportedSource(url.to_owned()))
+        }
+    }
+
+    /// Fetches the binary for the current platform described by the upgrade `info`, and verifies its checksum.
+    ///
+    /// # Errors
+    /// Errors if the info does not describe a binary for this platform, if the binary cannot be fetched or
+    /// if its checksum does not match.
+    pub fn fetch_binary(&self, info: &str) -> Result<Vec<u8>, FetchBinaryError> {
+        let upgrade: UpgradeBinaries = serde_json::from_str(info)?;
+        let platform = platform();
+        let url = upgrade
+            .binaries
+            .get(&platform)
+            .or_else(|| upgrade.binaries.get("any"))
+            .ok_or(FetchBinaryError::NoBinaryForPlatform(platform))?;
+
+        let (source, checksum) = split_checksum(url)?;
+        info!(target: "unionvisor", "fetching binary from {}", source);
+        let bytes = self.read(&source)?;
+        debug!(target: "unionvisor", "fetched {} bytes, verifying checksum", bytes.len());
+        checksum.verify(&source, &bytes)?;
+        Ok(bytes)
+    }
+
+    /// Queries the upgrade plan scheduled on chain, from the cosmos-sdk REST endpoint at `url`
+    /// (`/cosmos/upgrade/v1beta1/current_plan`).
+    pub fn current_plan(&self, url: &str) -> Result<Option<UpgradeInfo>, FetchBinaryError> {
+        self.client
+            .get(url)
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)
+            .and_then(reqwest::blocking::Response::json::<CurrentPlanResponse>)
+            .map_err(|source| FetchBinaryError::Download(url.to_owned(), source))?
+            .into_upgrade_info()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BINARY: &str = "#!/usr/bin/env sh\necho uniond\n";
+    const BINARY_SHA256: &str = "d2627445eee175265c64f567ee80c7b3d4a0c6e028caf0dbdd41a803fa45cb5a";
+
+    fn info_for(url: &str) -> String {
+        serde_json::json!({ "binaries": { platform(): url } }).to_string()
+    }
+
+    fn fetcher() -> Fetcher {
+        Fetcher::new(Duration::from_secs(1)).unwrap()
+    }
+
+    #[test]
+    fn test_split_checksum() {
+        let (url, checksum) =
+            split_checksum("https://example.com/uniond?foo=bar&checksum=sha256:ABCD").unwrap();
+        assert_eq!(url, "https://example.com/uniond?foo=bar");
+        assert_eq!(checksum, Checksum::Sha256("abcd".to_owned()));
+
+        split_checksum("https://example.com/uniond").unwrap_err();
+        split_checksum("https://example.com/uniond?checksum=md5:abcd").unwrap_err();
+    }
+
+    #[test]
+    fn test_fetch_binary_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("uniond");
+        fs::write(&path, BINARY).unwrap();
+
+        let info = info_for(&format!(
+            "file://{}?checksum=sha256:{BINARY_SHA256}",
+            path.display()
+        ));
+        assert_eq!(fetcher().fetch_binary(&info).unwrap(), BINARY.as_bytes());
+    }
+
+    #[test]
+    fn test_fetch_binary_checksum_mismatch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("uniond");
+        fs::write(&path, "#!/usr/bin/env sh\necho tampered\n").unwrap();
+
+        let info = info_for(&format!(
+            "file://{}?checksum=sha256:{BINARY_SHA256}",
+            path.display()
+        ));
+        assert!(matches!(
+            fetcher().fetch_binary(&info).unwrap_err(),
+            FetchBinaryError::ChecksumMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_fetch_binary_no_platform() {
+        let info = r#"{ "binaries": { "plan9/mips": "file:///uniond?checksum=sha256:00" } }"#;
+        assert!(matches!(
+            fetcher().fetch_binary(info).unwrap_err(),
+            FetchBinaryError::NoBinaryForPlatform(_)
+        ));
+    }
+
+    #[test]
+    fn test_current_plan() {
+        let response: CurrentPlanResponse = serde_json::from_str(
+            r#"{ "plan": { "name": "v1.1.0", "time": "0001-01-01T00:00:00Z", "height": "123", "info": "{}", "upgraded_client_state": null } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response.into_upgrade_info().unwrap(),
+            Some(UpgradeInfo {
+                name: "v1.1.0".to_owned(),
+                height: 123,
+                info: Some("{}".to_owned()),
+            })
+        );
+
+        let response: CurrentPlanResponse = serde_json::from_str(r#"{ "plan": null }"#).unwrap();
+        assert_eq!(response.into_upgrade_info().unwrap(), None);
+
+        let response: CurrentPlanResponse = serde_json::from_str(
+            r#"{ "plan": { "name": "v1.1.0", "height": "soon", "info": "" } }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            response.into_upgrade_info().unwrap_err(),
+            FetchBinaryError::InvalidPlanHeight(_)
+        ));
+    }
+}
@@ -6,8 +6,11 @@ use color_eyre::eyre;
 
 mod bundle;
 mod cli;
+mod fetcher;
 mod init;
 mod logging;
+mod metrics;
+mod status;
 mod supervisor;
 mod symlinker;
 mod watcher;
@@ -0,0 +1,79 @@
+use lazy_static::lazy_static;
+use prometheus::{IntCounter, IntGauge, IntGaugeVec, Opts, Registry};
+
+lazy_static! {
+    pub static ref REGISTRY: Registry = Registry::new();
+    pub static ref STARTS: IntCounter = IntCounter::with_opts(
+        Opts::new("starts_total", "Number of times uniond has been started")
+            .namespace("unionvisor")
+    )
+    .expect("register STARTS");
+    pub static ref UPGRADES: IntCounter = IntCounter::with_opts(
+        Opts::new("upgrades_total", "Number of upgrades performed").namespace("unionvisor")
+    )
+    .expect("register UPGRADES");
+    pub static ref ROLLBACKS: IntCounter = IntCounter::with_opts(
+        Opts::new("rollbacks_total", "Number of failed upgrades rolled back")
+            .namespace("unionvisor")
+    )
+    .expect("register ROLLBACKS");
+    pub static ref LAST_BACKUP: IntGauge = IntGauge::with_opts(
+        Opts::new(
+            "last_backup_timestamp_seconds",
+            "Unix timestamp of the last completed backup of the home directory"
+        )
+        .namespace("unionvisor")
+    )
+    .expect("register LAST_BACKUP");
+    pub static ref CHILD_PID: IntGauge = IntGauge::with_opts(
+        Opts::new("uniond_pid", "Pid of the running uniond (0 if not running)")
+            .namespace("unionvisor")
+    )
+    .expect("register CHILD_PID");
+    pub static ref CURRENT_VERSION: IntGaugeVec = IntGaugeVec::new(
+        Opts::new(
+            "current_version",
+            "Version the current symlink points to (1 for the current version)"
+        )
+        .namespace("unionvisor"),
+        &["version"]
+    )
+    .expect("register CURRENT_VERSION");
+    pub static ref UPGRADE_PENDING: IntGauge = IntGauge::with_opts(
+        Opts::new(
+            "upgrade_pending",
+            "Whether an upgrade has been signaled but not yet performed"
+        )
+        .namespace("unionvisor")
+    )
+    .expect("register UPGRADE_PENDING");
+}
+
+pub fn register_custom_metrics() {
+    REGISTRY
+        .register(Box::new(STARTS.clone()))
+        .expect("STARTS can be registered");
+    REGISTRY
+        .register(Box::new(UPGRADES.clone()))
+        .expect("UPGRADES can be registered");
+    REGISTRY
+        .register(Box::new(ROLLBACKS.clone()))
+        .expect("ROLLBACKS can be registered");
+    REGISTRY
+        .register(Box::new(LAST_BACKUP.clone()))
+        .expect("LAST_BACKUP can be registered");
+    REGISTRY
+        .register(Box::new(CHILD_PID.clone()))
+        .expect("CHILD_PID can be registered");
+    REGISTRY
+        .register(Box::new(CURRENT_VERSION.clone()))
+        .expect("CURRENT_VERSION can be registered");
+    REGISTRY
+        .register(Box::new(UPGRADE_PENDING.clone()))
+        .expect("UPGRADE_PENDING can be registered");
+}
+
+/// Encodes the registered metrics in the Prometheus text format.
+pub fn encode() -> Result<String, prometheus::Error> {
+    prometheus::TextEncoder::new().encode_to_string(&REGISTRY.gather())
+}
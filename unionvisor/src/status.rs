@@ -0,0 +1,218 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::PathBuf,
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use serde::Serialize;
+use tracing::{debug, info, warn};
+
+use crate::{
+    metrics,
+    symlinker::Symlinker,
+    watcher::{FileReader, FileReaderError, UpgradeInfo},
+};
+
+/// The response of `GET /status`.
+#[derive(Clone, Debug, Serialize)]
+pub struct Status {
+    /// The version the current symlink points to.
+    pub current_version: Option<String>,
+    /// The versions available in the bundle.
+    pub bundle_versions: Vec<String>,
+    /// The signaled upgrade, if it has not been performed yet.
+    pub pending_upgrade: Option<UpgradeInfo>,
+    /// The pid of the running uniond.
+    pub child_pid: Option<u32>,
+    /// Number of times uniond has been restarted, because of upgrades or rollbacks.
+    pub restarts: u64,
+    /// Unix timestamp of the last completed backup of the home directory.
+    pub last_backup: Option<i64>,
+}
+
+/// Serves `/status` (JSON) and `/metrics` (Prometheus) on a background thread. State is read from the
+/// [`Symlinker`], its [`crate::bundle::Bundle`], the upgrade info and the [`metrics`] on each request.
+pub struct StatusServer {
+    symlinker: Symlinker,
+    upgrade_info: FileReader,
+}
+
+impl StatusServer {
+    pub fn new(symlinker: Symlinker, upgrade_info: impl Into<PathBuf>) -> Self {
+        Self {
+            symlinker,
+            upgrade_info: FileReader::new(upgrade_info),
+        }
+    }
+
+    /// Binds `addr` and serves requests until the process exits.
+    pub fn spawn(self, addr: SocketAddr) -> io::Result<JoinHandle<()>> {
+        metrics::register_custom_metrics();
+        let listener = TcpListener::bind(addr)?;
+        info!(target: "unionvisor", "serving status on {}", addr);
+        Ok(std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let result = stream.and_then(|stream| self.handle(stream));
+                if let Err(err) = result {
+                    warn!(target: "unionvisor", "error serving status request: {}", err);
+                }
+            }
+        }))
+    }
+
+    fn handle(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let mut reader = BufReader::new(&stream);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Drain the headers, we don't need them.
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (method, path) = (parts.next(), parts.next());
+        debug!(target: "unionvisor", "status request {:?} {:?}", method, path);
+
+        let (status, content_type, body) = match (method, path) {
+            (Some("GET"), Some("/status")) => match serde_json::to_string(&self.status()) {
+                Ok(body) => ("200 OK", "application/json", body),
+                Err(err) => ("500 Internal Server Error", "text/plain", err.to_string()),
+            },
+            (Some("GET"), Some("/metrics")) => {
+                // Refresh the metrics derived from the status.
+                self.status();
+                match metrics::encode() {
+                    Ok(body) => ("200 OK", "text/plain; version=0.0.4", body),
+                    Err(err) => ("500 Internal Server Error", "text/plain", err.to_string()),
+                }
+            }
+            _ => ("404 Not Found", "text/plain", "not found".to_owned()),
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+
+    /// Reads the current status, updating the metrics that are derived from it.
+    fn status(&self) -> Status {
+        let current_version = self
+            .symlinker
+            .current_version()
+            .ok()
+            .map(|version| version.to_string_lossy().into_owned());
+
+        let mut bundle_versions = fs::read_dir(self.symlinker.bundle.versions_path())
+            .map(|dir| {
+                dir.filter_map(Result::ok)
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        bundle_versions.sort();
+
+        let pending_upgrade = match self.upgrade_info.read_upgrade_info() {
+            Ok(info) if Some(&info.name) != current_version.as_ref() => Some(info),
+            Ok(_) | Err(FileReaderError::FileNotFound) => None,
+            Err(err) => {
+                warn!(target: "unionvisor", "cannot read upgrade info: {}", err);
+                None
+            }
+        };
+        metrics::UPGRADE_PENDING.set(pending_upgrade.is_some().into());
+
+        let child_pid = u32::try_from(metrics::CHILD_PID.get())
+            .ok()
+            .filter(|pid| *pid != 0);
+        let last_backup = Some(metrics::LAST_BACKUP.get()).filter(|timestamp| *timestamp != 0);
+
+        Status {
+            current_version,
+            bundle_versions,
+            pending_upgrade,
+            child_pid,
+            restarts: metrics::STARTS.get().saturating_sub(1),
+            last_backup,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::{bundle::Bundle, testdata};
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_status() {
+        let tmp = testdata::temp_dir_with(&["test_run"]);
+        let root = tmp.into_path().join("test_run");
+        let bundle = Bundle::new(root.join("bundle")).unwrap();
+        let symlinker = Symlinker::new(root.clone(), bundle);
+        symlinker
+            .make_fallback_link()
+            .expect("fallback link should be made");
+        fs::write(
+            root.join("home/data/upgrade-info.json"),
+            r#"{"name": "upgrade1", "height": 123}"#,
+        )
+        .unwrap();
+
+        let status =
+            StatusServer::new(symlinker, root.join("home/data/upgrade-info.json")).status();
+        assert_eq!(status.current_version.as_deref(), Some("genesis"));
+        assert_eq!(
+            status.bundle_versions,
+            vec!["genesis", "upgrade1", "upgrade2"]
+        );
+        assert_eq!(status.pending_upgrade.unwrap().name, "upgrade1");
+    }
+
+    #[test]
+    fn test_serve() {
+        let tmp = testdata::temp_dir_with(&["test_run"]);
+        let root = tmp.into_path().join("test_run");
+        let bundle = Bundle::new(root.join("bundle")).unwrap();
+        let symlinker = Symlinker::new(root.clone(), bundle);
+        symlinker
+            .make_fallback_link()
+            .expect("fallback link should be made");
+
+        // Bind an ephemeral port to find a free one.
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        StatusServer::new(symlinker, root.join("home/data/upgrade-info.json"))
+            .spawn(addr)
+            .unwrap();
+
+        let status = get(addr, "/status");
+        assert!(status.starts_with("HTTP/1.1 200 OK"));
+        assert!(status.contains(r#""current_version":"genesis""#));
+
+        let metrics = get(addr, "/metrics");
+        assert!(metrics.starts_with("HTTP/1.1 200 OK"));
+        assert!(metrics.contains("unionvisor_upgrade_pending"));
+
+        assert!(get(addr, "/foo").starts_with("HTTP/1.1 404 Not Found"));
+    }
+}
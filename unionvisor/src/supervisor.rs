@@ -4,15 +4,18 @@ use std::{
     io,
     path::{Path, PathBuf},
     process::{Child, ExitStatus},
-    time::Duration,
+    thread::JoinHandle,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use thiserror::Error;
 use tracing::{error, field::display as as_display, info, warn};
 
 use crate::{
-    bundle::ValidateVersionPathError,
+    bundle::{InstallVersionError, PreStartError, ValidateVersionPathError},
+    fetcher::{FetchBinaryError, Fetcher},
     logging::LogFormat,
+    metrics,
     symlinker::{CurrentVersionError, Symlinker, SymlinkerError},
     watcher::{FileReader, FileReaderError},
 };
@@ -28,6 +31,42 @@ pub struct Supervisor {
     child: Option<Child>,
 }
 
+/// Checks performed on a new binary after an upgrade. If the binary fails them, the upgrade is rolled back.
+#[derive(Clone, Debug, Default)]
+pub struct HealthCheck {
+    /// Duration after an upgrade during which the new binary must stay up. Zero disables the check.
+    pub window: Duration,
+    /// Url which must respond successfully at least once within the window, such as `http://localhost:26657/health`.
+    pub liveness_url: Option<String>,
+    /// What to do once a failed upgrade has been rolled back.
+    pub on_failure: RollbackPolicy,
+}
+
+/// How binaries that are not part of the bundle are fetched.
+#[derive(Clone, Debug)]
+pub struct FetchConfig {
+    pub fetcher: Fetcher,
+    /// Url of the upgrade plan scheduled on chain, such as
+    /// `http://localhost:1317/cosmos/upgrade/v1beta1/current_plan`. If set, the binary of a scheduled upgrade is
+    /// fetched in the background, so that it is installed before the upgrade height is reached.
+    pub plan_url: Option<String>,
+}
+
+/// A binary being fetched in the background for a scheduled upgrade.
+struct Prefetch {
+    name: String,
+    handle: JoinHandle<Result<(), RuntimeError>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+pub enum RollbackPolicy {
+    /// Exit with an error, leaving the node on the previous version.
+    #[default]
+    Halt,
+    /// Keep running the previous version. Only useful for non-consensus upgrades.
+    Continue,
+}
+
 impl Drop for Supervisor {
     fn drop(&mut self) {
         if self.child.is_some() {
@@ -53,6 +92,9 @@ impl Supervisor {
         args: I,
     ) -> Result<(), SpawnError> {
         let program = self.symlinker.current_validated()?;
+        let version = self.symlinker.current_version()?;
+        let config = self.symlinker.bundle.version_config(&version);
+        config.run_pre_start(&self.home_dir())?;
         info!(
             "running {:?} pointing to {:?}",
             program.0.clone().into_os_string(),
@@ -65,10 +107,12 @@ impl Supervisor {
             .args(vec!["--log_format", logformat.as_str()])
             .arg("start")
             .args(args)
+            .args(&config.args)
             .args(vec![
                 OsString::from("--home"),
                 self.home_dir().into_os_string(),
             ])
+            .envs(&config.env)
             .stderr(std::process::Stdio::inherit())
             .stdout(std::process::Stdio::inherit());
 
@@ -78,6 +122,12 @@ impl Supervisor {
                 source,
                 command: format!("{command:?}"),
             })?;
+        metrics::STARTS.inc();
+        metrics::CHILD_PID.set(child.id().into());
+        metrics::CURRENT_VERSION.reset();
+        metrics::CURRENT_VERSION
+            .with_label_values(&[&version.to_string_lossy()])
+            .set(1);
         self.child = Some(child);
         Ok(())
     }
@@ -102,18 +152,102 @@ impl Supervisor {
             source,
         })?;
         info!(target: "unionvisor", "completed backup");
+        metrics::LAST_BACKUP.set(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| {
+                    elapsed.as_secs().try_into().unwrap_or(i64::MAX)
+                }),
+        );
+        Ok(())
+    }
+
+    /// Restore the uniond home directory from a backup made by [`Supervisor::backup`] in `backup_dir`.
+    pub fn restore(&self, backup_dir: impl AsRef<Path>) -> Result<(), RestoreError> {
+        use fs_extra::dir::{copy, CopyOptions};
+        let backup_dir = backup_dir.as_ref();
+        let home_dir = self.home_dir();
+        info!(target: "unionvisor", "restoring {} from {}. This might take a while",  as_display(home_dir.display()),  as_display(backup_dir.display()));
+        if home_dir.exists() {
+            fs::remove_dir_all(&home_dir)
+                .map_err(|source| RestoreError::RemoveHome(home_dir.clone(), source))?;
+        }
+        let options = CopyOptions::new().overwrite(true);
+        copy(backup_dir.join("home"), &self.root, &options).map_err(|source| {
+            RestoreError::CopyDir {
+                home: home_dir.clone(),
+                backup: backup_dir.to_owned(),
+                source,
+            }
+        })?;
+        info!(target: "unionvisor", "completed restore");
+        Ok(())
+    }
+
+    /// Waits for the health window to pass, checking that uniond stays up and, if configured, that the liveness
+    /// url responds successfully at least once.
+    pub fn wait_healthy(&mut self, health: &HealthCheck) -> Result<(), HealthError> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(1))
+            .build()
+            .map_err(HealthError::Client)?;
+        let start = Instant::now();
+        let mut live = health.liveness_url.is_none();
+        while start.elapsed() < health.window {
+            if let Some(code) = self.try_wait()? {
+                return Err(HealthError::Exited { code });
+            }
+            if let (false, Some(url)) = (live, &health.liveness_url) {
+                live = client
+                    .get(url)
+                    .send()
+                    .and_then(reqwest::blocking::Response::error_for_status)
+                    .is_ok();
+            }
+            std::thread::sleep(Duration::from_secs(1));
+        }
+        if !live {
+            return Err(HealthError::NotLive {
+                url: health.liveness_url.clone().unwrap_or_default(),
+                window: health.window,
+            });
+        }
+        Ok(())
+    }
+
+    /// Stops uniond, restores the home directory from `backup_dir` and swaps the symlink back to `version`.
+    pub fn rollback(
+        &mut self,
+        symlinker: &Symlinker,
+        backup_dir: impl AsRef<Path>,
+        version: &OsStr,
+    ) -> Result<(), RuntimeError> {
+        metrics::ROLLBACKS.inc();
+        // The child may have exited already, or never been spawned, in which case there is nothing to kill.
+        if self.child.is_some() {
+            let _ = self.kill();
+        }
+        self.restore(backup_dir)?;
+        symlinker.swap(version)?;
         Ok(())
     }
 
     pub fn try_wait(&mut self) -> Result<Option<ExitStatus>, TryWaitError> {
         match &mut self.child {
-            Some(child) => Ok(child.try_wait()?),
+            Some(child) => {
+                let status = child.try_wait()?;
+                if status.is_some() {
+                    metrics::CHILD_PID.set(0);
+                }
+                Ok(status)
+            }
             _ => unreachable!("try_waiting for a child should only happen after spawn"),
         }
     }
 
     pub fn kill(&mut self) -> Result<(), KillError> {
         if let Some(ref mut child) = self.child.take() {
+            metrics::CHILD_PID.set(0);
             child.kill()?;
         } else {
             debug_assert!(false, "killing a child should only happen after spawn");
@@ -134,6 +268,10 @@ pub struct KillError(#[from] io::Error);
 pub enum SpawnError {
     #[error("error validating version path")]
     ValidateVersionPath(#[from] ValidateVersionPathError),
+    #[error("cannot get current version")]
+    CurrentVersion(#[from] CurrentVersionError),
+    #[error("error running pre-start hooks")]
+    PreStart(#[from] PreStartError),
     #[error("error spawning child with command {command}")]
     SpawnChildError { source: io::Error, command: String },
 }
@@ -150,6 +288,32 @@ pub enum BackupError {
     },
 }
 
+#[derive(Debug, Error)]
+pub enum RestoreError {
+    #[error("Cannot remove home dir {0}")]
+    RemoveHome(PathBuf, #[source] io::Error),
+    #[error("Cannot copy backup dir to home dir")]
+    CopyDir {
+        home: PathBuf,
+        backup: PathBuf,
+        source: fs_extra::error::Error,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum HealthError {
+    #[error("cannot start uniond")]
+    Spawn(#[source] SpawnError),
+    #[error("uniond exited with code: {code}")]
+    Exited { code: ExitStatus },
+    #[error("{url} did not respond successfully within {window:?}")]
+    NotLive { url: String, window: Duration },
+    #[error("cannot build the liveness client")]
+    Client(#[source] reqwest::Error),
+    #[error("error try waiting")]
+    TryWait(#[from] TryWaitError),
+}
+
 #[derive(Debug, Error)]
 pub enum RuntimeError {
     #[error("error spawning uniond")]
@@ -162,6 +326,14 @@ pub enum RuntimeError {
     SupervisorKill(#[from] KillError),
     #[error("supervisor cannot make backup")]
     SupervisorBackup(#[from] BackupError),
+    #[error("supervisor cannot restore backup")]
+    SupervisorRestore(#[from] RestoreError),
+    #[error("upgrade {name} failed and has been rolled back to {previous}")]
+    UpgradeFailed {
+        name: String,
+        previous: String,
+        source: HealthError,
+    },
     #[error("cannot swap symlink")]
     Symlinker(#[from] SymlinkerError),
     #[error("cannot validate new version's path")]
@@ -171,6 +343,16 @@ pub enum RuntimeError {
         name: String,
         source: ValidateVersionPathError,
     },
+    #[error("cannot fetch binary {}", name)]
+    FetchBinary {
+        name: String,
+        source: FetchBinaryError,
+    },
+    #[error("cannot install binary {}", name)]
+    InstallBinary {
+        name: String,
+        source: InstallVersionError,
+    },
     #[error("uniond exited with code: {code}")]
     UniondExit { code: ExitStatus },
     #[error("unknown FileReaderError while polling for upgrades")]
@@ -185,6 +367,8 @@ pub fn run_and_upgrade<S: AsRef<OsStr>, I: IntoIterator<Item = S> + Clone>(
     symlinker: &Symlinker,
     args: &I,
     pol_interval: Duration,
+    health: &HealthCheck,
+    fetch: &FetchConfig,
 ) -> Result<(), RuntimeError> {
     let root = root.into();
     symlinker.fix_legacy_paths()?;
@@ -200,11 +384,24 @@ pub fn run_and_upgrade<S: AsRef<OsStr>, I: IntoIterator<Item = S> + Clone>(
         })?;
     info!(target: "unionvisor", "spawned uniond, starting poll for upgrade signals");
     std::thread::sleep(Duration::from_millis(300));
+    let mut prefetch: Option<Prefetch> = None;
+    let mut last_plan_check: Option<Instant> = None;
     loop {
         if let Some(code) = supervisor.try_wait()? {
             return Err(RuntimeError::UniondExit { code });
         }
 
+        if let Some(plan_url) = &fetch.plan_url {
+            if last_plan_check.map_or(true, |checked| checked.elapsed() >= pol_interval) {
+                last_plan_check = Some(Instant::now());
+                if let Some(started) =
+                    prefetch_scheduled_binary(symlinker, fetch, plan_url, prefetch.as_ref())
+                {
+                    prefetch = Some(started);
+                }
+            }
+        }
+
         match watcher.poll() {
             Err(FileReaderError::FileNotFound) | Ok(None) => continue,
             Err(err) => {
@@ -236,14 +433,25 @@ pub fn run_and_upgrade<S: AsRef<OsStr>, I: IntoIterator<Item = S> + Clone>(
                 );
                 info!(target: "unionvisor", "checking binary availability");
 
-                symlinker
-                    .bundle
-                    .path_to(&upgrade_name)
-                    .validate()
-                    .map_err(|source| RuntimeError::BinaryUnavailable {
-                        name: upgrade.name.clone(),
-                        source,
-                    })?;
+                // Wait for the binary if it is still being fetched in the background.
+                if let Some(Prefetch { handle, .. }) =
+                    prefetch.take_if(|prefetch| prefetch.name == upgrade.name)
+                {
+                    if let Err(err) = handle.join().expect("prefetch thread does not panic") {
+                        warn!(target: "unionvisor", err = err.to_string().as_str(), "fetching binary for {} in the background failed, retrying", &upgrade.name);
+                    }
+                }
+
+                if let Err(source) = symlinker.bundle.path_to(&upgrade_name).validate() {
+                    // The binary is not part of the bundle, fetch it if the upgrade plan tells us where from.
+                    let Some(info) = upgrade.info.as_deref() else {
+                        return Err(RuntimeError::BinaryUnavailable {
+                            name: upgrade.name.clone(),
+                            source,
+                        });
+                    };
+                    install_binary(symlinker, &fetch.fetcher, &upgrade.name, info)?;
+                }
 
                 info!(target: "unionvisor", "killing supervisor process");
                 supervisor.kill()?;
@@ -262,11 +470,38 @@ pub fn run_and_upgrade<S: AsRef<OsStr>, I: IntoIterator<Item = S> + Clone>(
                 // If this upgrade fails, we'll revert the local DB and exit the node, ensuring we keep the filesystem in
                 // the last correct state.
                 info!(target: "unionvisor", "spawning new supervisor process for {}", &upgrade.name);
-                supervisor.spawn(logformat, args.clone()).inspect_err(|err| {
-                    error!(target: "unionvisor", err = err.to_string().as_str(), "spawning new supervisor process for {} failed", &upgrade.name);
-                    // This error is most likely caused by incorrect args because of an upgrade. We can reduce the chance of that happening
-                    // by introducing a configuration file with name -> args mappings.
-                })?;
+                let upgraded = match supervisor.spawn(logformat, args.clone()) {
+                    Ok(()) => {
+                        metrics::UPGRADES.inc();
+                        if health.window.is_zero() {
+                            Ok(())
+                        } else {
+                            info!(target: "unionvisor", "checking health of {} for {} seconds", &upgrade.name, health.window.as_secs());
+                            supervisor.wait_healthy(health).inspect(|()| {
+                                info!(target: "unionvisor", "{} is healthy", &upgrade.name);
+                            })
+                        }
+                    }
+                    // This error is most likely caused by incorrect args or a failing pre-start hook of the new
+                    // version, which can be configured per version in the bundle's meta.json.
+                    Err(err) => Err(HealthError::Spawn(err)),
+                };
+
+                if let Err(source) = upgraded {
+                    let previous = current_version.to_string_lossy().into_owned();
+                    error!(target: "unionvisor", err = source.to_string().as_str(), "{} is unhealthy, rolling back to {}", &upgrade.name, &previous);
+                    supervisor.rollback(symlinker, &backup_dir, &current_version)?;
+                    if let RollbackPolicy::Halt = health.on_failure {
+                        return Err(RuntimeError::UpgradeFailed {
+                            name: upgrade.name.clone(),
+                            previous,
+                            source,
+                        });
+                    }
+                    warn!(target: "unionvisor", "continuing with {}", &previous);
+                    supervisor = Supervisor::new(root.clone(), symlinker.clone());
+                    supervisor.spawn(logformat, args.clone())?;
+                }
             }
         }
         info!(target: "unionvisor", "no upgrade detected, sleeping for {} milliseconds.", &pol_interval.as_millis());
@@ -274,6 +509,62 @@ pub fn run_and_upgrade<S: AsRef<OsStr>, I: IntoIterator<Item = S> + Clone>(
     }
 }
 
+/// Starts fetching the binary of the upgrade scheduled on chain, if it is not part of the bundle and not being
+/// fetched already.
+fn prefetch_scheduled_binary(
+    symlinker: &Symlinker,
+    fetch: &FetchConfig,
+    plan_url: &str,
+    current: Option<&Prefetch>,
+) -> Option<Prefetch> {
+    let plan = match fetch.fetcher.current_plan(plan_url) {
+        Ok(plan) => plan?,
+        Err(err) => {
+            warn!(target: "unionvisor", err = err.to_string().as_str(), "cannot query the upgrade plan");
+            return None;
+        }
+    };
+    let info = plan.info?;
+    let fetching = current
+        .is_some_and(|prefetch| prefetch.name == plan.name && !prefetch.handle.is_finished());
+    if fetching || symlinker.bundle.path_to(&plan.name).validate().is_ok() {
+        return None;
+    }
+
+    info!(target: "unionvisor", name = plan.name.as_str(), height = plan.height, "upgrade scheduled, fetching binary in the background");
+    let (symlinker, fetcher, name) = (symlinker.clone(), fetch.fetcher.clone(), plan.name.clone());
+    Some(Prefetch {
+        name: plan.name,
+        handle: std::thread::spawn(move || install_binary(&symlinker, &fetcher, &name, &info)),
+    })
+}
+
+/// Fetches the binary described in the upgrade info, verifies its checksum and installs it in the bundle.
+fn install_binary(
+    symlinker: &Symlinker,
+    fetcher: &Fetcher,
+    name: &str,
+    info: &str,
+) -> Result<(), RuntimeError> {
+    info!(target: "unionvisor", "fetching binary for {} from the upgrade info", name);
+    let binary = fetcher.fetch_binary(info).map_err(|source| {
+        error!(target: "unionvisor", err = source.to_string().as_str(), "cannot fetch binary for {}, refusing to upgrade", name);
+        RuntimeError::FetchBinary {
+            name: name.to_owned(),
+            source,
+        }
+    })?;
+    symlinker
+        .bundle
+        .install_version(name, &binary)
+        .map_err(|source| RuntimeError::InstallBinary {
+            name: name.to_owned(),
+            source,
+        })?;
+    info!(target: "unionvisor", "installed binary for {}", name);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use tracing_test::traced_test;
@@ -302,6 +593,8 @@ mod tests {
             &symlinker,
             &vec![root.join("home/data").as_os_str()],
             Duration::from_secs(1),
+            &HealthCheck::default(),
+            &fetch_config(),
         )
         .unwrap_err();
 
@@ -331,6 +624,8 @@ mod tests {
             &symlinker,
             &vec![root.join("home/data").as_os_str()],
             Duration::from_secs(1),
+            &HealthCheck::default(),
+            &fetch_config(),
         )
         .unwrap_err();
 
@@ -341,6 +636,115 @@ mod tests {
         }
     }
 
+    #[test]
+    #[traced_test]
+    /// Upgrades to a binary which crashes after touching the db, which must be rolled back.
+    fn test_run_and_upgrade_rollback() {
+        let tmp = testdata::temp_dir_with(&["test_rollback"]);
+        let root = tmp.into_path().join("test_rollback");
+        let bundle = Bundle::new(root.join("bundle")).unwrap();
+        let symlinker = Symlinker::new(root.clone(), bundle);
+
+        // Usually this is made as part of the init process, but we're not test that here.
+        symlinker
+            .make_fallback_link()
+            .expect("fallback link should be made");
+
+        let err = run_and_upgrade(
+            root.clone(),
+            LogFormat::Plain,
+            &symlinker,
+            &vec![root.join("home/data").as_os_str()],
+            Duration::from_secs(1),
+            &HealthCheck {
+                window: Duration::from_secs(5),
+                liveness_url: None,
+                on_failure: RollbackPolicy::Halt,
+            },
+            &fetch_config(),
+        )
+        .unwrap_err();
+
+        if let RuntimeError::UpgradeFailed { name, previous, .. } = err {
+            assert_eq!(name, "upgrade1");
+            assert_eq!(previous, "genesis");
+        } else {
+            panic!("didn't receive expected error: {err:?}")
+        }
+        assert_eq!(symlinker.current_version().unwrap(), "genesis");
+        assert_file_contains(root.join("home/data/foo.db"), "foo");
+    }
+
+    #[test]
+    #[traced_test]
+    /// Upgrades to a version whose pre-start hook touches the db and then fails, which must be rolled back.
+    fn test_run_and_upgrade_rollback_pre_start() {
+        let tmp = testdata::temp_dir_with(&["test_rollback_pre_start"]);
+        let root = tmp.into_path().join("test_rollback_pre_start");
+        let bundle = Bundle::new(root.join("bundle")).unwrap();
+        let symlinker = Symlinker::new(root.clone(), bundle);
+
+        // Usually this is made as part of the init process, but we're not test that here.
+        symlinker
+            .make_fallback_link()
+            .expect("fallback link should be made");
+
+        let err = run_and_upgrade(
+            root.clone(),
+            LogFormat::Plain,
+            &symlinker,
+            &vec![root.join("home/data").as_os_str()],
+            Duration::from_secs(1),
+            // The health check is disabled, the failure to start alone must trigger the rollback.
+            &HealthCheck::default(),
+            &fetch_config(),
+        )
+        .unwrap_err();
+
+        if let RuntimeError::UpgradeFailed {
+            name,
+            previous,
+            source,
+        } = err
+        {
+            assert_eq!(name, "upgrade1");
+            assert_eq!(previous, "genesis");
+            assert!(matches!(
+                source,
+                HealthError::Spawn(SpawnError::PreStart(PreStartError::Failed { .. }))
+            ));
+        } else {
+            panic!("didn't receive expected error: {err:?}")
+        }
+        assert_eq!(symlinker.current_version().unwrap(), "genesis");
+        assert_file_contains(root.join("home/data/foo.db"), "foo");
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_spawn_version_config() {
+        let tmp = testdata::temp_dir_with(&["test_version_config"]);
+        let root = tmp.into_path().join("test_version_config");
+        let bundle = Bundle::new(root.join("bundle")).unwrap();
+        let symlinker = Symlinker::new(root.clone(), bundle);
+
+        // Usually this is made as part of the init process, but we're not test that here.
+        symlinker
+            .make_fallback_link()
+            .expect("fallback link should be made");
+
+        let mut supervisor = Supervisor::new(root.clone(), symlinker);
+        supervisor
+            .spawn(LogFormat::Plain, vec![root.join("home/data").as_os_str()])
+            .unwrap();
+        while supervisor.try_wait().unwrap().is_none() {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        assert_file_contains(root.join("home/data/hook"), "hooked");
+        assert_file_contains(root.join("home/data/out"), "extra bar");
+    }
+
     #[test]
     #[traced_test]
     fn test_backup() {
@@ -357,6 +761,13 @@ mod tests {
         assert_file_contains(root.join("home/data/bar.db"), "bar");
     }
 
+    fn fetch_config() -> FetchConfig {
+        FetchConfig {
+            fetcher: Fetcher::new(Duration::from_secs(1)).unwrap(),
+            plan_url: None,
+        }
+    }
+
     fn assert_file_contains(file: impl AsRef<Path>, want: &str) {
         let contents = fs::read_to_string(file.as_ref()).unwrap();
         assert_eq!(contents, want);
@@ -381,6 +792,8 @@ mod tests {
             &symlinker,
             &vec![root.join("data").as_os_str()],
             Duration::from_secs(1),
+            &HealthCheck::default(),
+            &fetch_config(),
         )
         .unwrap_err();
 
@@ -5,11 +5,11 @@ use std::{
     time::Duration,
 };
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::error;
 
 /// `UpgradeInfo` is set by the node periodically when a chain upgrade is required.
-#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct UpgradeInfo {
     /// The name of the upgrade; which operators must match to a binary.
     pub name: String,
@@ -43,7 +43,7 @@ impl FileReader {
         }
     }
 
-    fn read_upgrade_info(&self) -> Result<UpgradeInfo, FileReaderError> {
+    pub fn read_upgrade_info(&self) -> Result<UpgradeInfo, FileReaderError> {
         let mut file = File::open(&self.path).map_err(|err| match err.kind() {
             io::ErrorKind::NotFound => FileReaderError::FileNotFound,
             _ => FileReaderError::Io(err),
@@ -183,6 +183,7 @@ impl ClientModuleServer for Module {
         Ok(ClientStateMeta {
             counterparty_chain_id: ChainId::new(cs.chain_id.as_str().to_owned()),
             counterparty_height: cs.latest_height,
+            trusting_period: Some(cs.trusting_period),
         })
     }
 
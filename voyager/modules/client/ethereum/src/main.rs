@@ -94,6 +94,7 @@ impl ClientModuleServer for Module {
         Ok(ClientStateMeta {
             counterparty_chain_id: ChainId::new(cs.chain_id.to_string()),
             counterparty_height: Module::make_height(cs.latest_height),
+            trusting_period: None,
         })
     }
 
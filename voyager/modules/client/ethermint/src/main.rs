@@ -84,6 +84,13 @@ impl ClientModuleServer for Module {
         Ok(ClientStateMeta {
             counterparty_chain_id: ChainId::new(cs.tendermint_client_state.chain_id),
             counterparty_height: cs.tendermint_client_state.latest_height,
+            trusting_period: u64::try_from(
+                cs.tendermint_client_state
+                    .trusting_period
+                    .as_nanos()
+                    .inner(),
+            )
+            .ok(),
         })
     }
 
@@ -142,6 +142,7 @@ impl ClientModuleServer for Module {
         Ok(ClientStateMeta {
             counterparty_chain_id: ChainId::new(cs.l2_chain_id.to_string()),
             counterparty_height: Module::make_height(cs.l2_latest_height),
+            trusting_period: None,
         })
     }
 
@@ -153,6 +153,7 @@ impl ClientModuleServer for Module {
         Ok(ClientStateMeta {
             counterparty_chain_id: ChainId::new(cs.chain_id),
             counterparty_height: cs.latest_height,
+            trusting_period: u64::try_from(cs.trusting_period.as_nanos().inner()).ok(),
         })
     }
 
@@ -2,8 +2,10 @@ use std::num::ParseIntError;
 
 use jsonrpsee::{
     core::{async_trait, RpcResult},
+    types::{ErrorObject, ErrorObjectOwned},
     Extensions,
 };
+use multi_rpc::{MultiRpc, RpcUrls};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, instrument, trace};
 use unionlabs::{
@@ -26,7 +28,7 @@ async fn main() {
 pub struct Module {
     pub chain_id: ChainId,
 
-    pub cometbft_client: cometbft_rpc::Client,
+    pub cometbft_client: MultiRpc<cometbft_rpc::Client>,
     pub chain_revision: u64,
 
     pub ibc_host_contract_address: H256,
@@ -35,7 +37,11 @@ pub struct Module {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
-    pub rpc_url: String,
+    /// The rpc endpoint(s) of the chain. Requests fail over to the next endpoint in order.
+    pub rpc_url: RpcUrls,
+    /// Require this many of the endpoints to have reached the latest height.
+    #[serde(default)]
+    pub quorum: Option<usize>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ibc_host_contract_address: Option<Bech32<H256>>,
 }
@@ -44,9 +50,17 @@ impl ConsensusModule for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: ConsensusModuleInfo) -> Result<Self, BoxDynError> {
-        let tm_client = cometbft_rpc::Client::new(config.rpc_url).await?;
-
-        let chain_id = tm_client.status().await?.node_info.network.to_string();
+        let tm_client =
+            MultiRpc::new(config.rpc_url, config.quorum, cometbft_rpc::Client::new).await?;
+
+        let chain_id = tm_client
+            .read(|client| async move {
+                client
+                    .status()
+                    .await
+                    .map(|status| status.node_info.network.to_string())
+            })
+            .await?;
 
         info.ensure_chain_id(&chain_id)?;
         info.ensure_consensus_type(ConsensusType::COMETBLS)?;
@@ -90,24 +104,34 @@ impl Module {
         Height::new_with_revision(self.chain_revision, height)
     }
 
-    async fn latest_height(&self, finalized: bool) -> Result<Height, cometbft_rpc::JsonRpcError> {
-        let commit_response = self.cometbft_client.commit(None).await?;
-
-        let mut height = commit_response
-            .signed_header
-            .header
-            .height
-            .inner()
-            .try_into()
-            .expect("value is >= 0; qed;");
-
-        if finalized && !commit_response.canonical {
-            trace!(
-                "commit is not canonical and finalized height was requested, \
-                latest finalized height is the previous block"
-            );
-            height -= 1;
-        }
+    async fn latest_height(
+        &self,
+        finalized: bool,
+    ) -> Result<Height, multi_rpc::Error<cometbft_rpc::JsonRpcError>> {
+        let height = self
+            .cometbft_client
+            .latest_height(|client| async move {
+                let commit_response = client.commit(None).await?;
+
+                let mut height: u64 = commit_response
+                    .signed_header
+                    .header
+                    .height
+                    .inner()
+                    .try_into()
+                    .expect("value is >= 0; qed;");
+
+                if finalized && !commit_response.canonical {
+                    trace!(
+                        "commit is not canonical and finalized height was requested, \
+                        latest finalized height is the previous block"
+                    );
+                    height -= 1;
+                }
+
+                Ok(height)
+            })
+            .await?;
 
         debug!(height, "latest height");
 
@@ -123,7 +147,7 @@ impl ConsensusModuleServer for Module {
         self.latest_height(finalized)
             .await
             // TODO: Add more context here
-            .map_err(json_rpc_error_to_error_object)
+            .map_err(multi_rpc_error)
     }
 
     /// Query the latest finalized timestamp of this chain.
@@ -133,39 +157,47 @@ impl ConsensusModuleServer for Module {
         _: &Extensions,
         finalized: bool,
     ) -> RpcResult<Timestamp> {
-        let mut commit_response = self
-            .cometbft_client
-            .commit(None)
-            .await
-            .map_err(json_rpc_error_to_error_object)?;
-
-        if finalized && commit_response.canonical {
-            trace!(
-                "commit is not canonical and finalized timestamp was \
-                requested, fetching commit at previous block"
-            );
-            commit_response = self
-                .cometbft_client
-                .commit(Some(
-                    (u64::try_from(commit_response.signed_header.header.height.inner() - 1)
-                        .expect("should be fine"))
-                    .try_into()
-                    .expect("should be fine"),
+        self.cometbft_client
+            .failover(|client| async move {
+                let mut commit_response = client.commit(None).await?;
+
+                if finalized && commit_response.canonical {
+                    trace!(
+                        "commit is not canonical and finalized timestamp was \
+                        requested, fetching commit at previous block"
+                    );
+                    commit_response = client
+                        .commit(Some(
+                            (u64::try_from(
+                                commit_response.signed_header.header.height.inner() - 1,
+                            )
+                            .expect("should be fine"))
+                            .try_into()
+                            .expect("should be fine"),
+                        ))
+                        .await?;
+
+                    if !commit_response.canonical {
+                        error!(
+                            ?commit_response,
+                            "commit for previous height is not canonical? continuing \
+                            anyways, but this may cause issues downstream"
+                        );
+                    }
+                }
+
+                Ok(Timestamp::from_nanos(
+                    commit_response.signed_header.header.time.as_unix_nanos(),
                 ))
-                .await
-                .map_err(json_rpc_error_to_error_object)?;
-
-            if !commit_response.canonical {
-                error!(
-                    ?commit_response,
-                    "commit for previous height is not canonical? continuing \
-                    anyways, but this may cause issues downstream"
-                );
-            }
-        }
-
-        Ok(Timestamp::from_nanos(
-            commit_response.signed_header.header.time.as_unix_nanos(),
-        ))
+            })
+            .await
+            .map_err(json_rpc_error_to_error_object)
+    }
+}
+
+fn multi_rpc_error(err: multi_rpc::Error<cometbft_rpc::JsonRpcError>) -> ErrorObjectOwned {
+    match err {
+        multi_rpc::Error::Rpc(err) => json_rpc_error_to_error_object(err),
+        err => ErrorObject::owned(-1, err.to_string(), None::<()>),
     }
 }
@@ -5,9 +5,10 @@ use ethereum_light_client_types::StorageProof;
 use ibc_union_spec::{path::StorePath, IbcUnion};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
+    types::{ErrorObject, ErrorObjectOwned},
     Extensions,
 };
+use multi_rpc::{MultiRpc, RpcUrls};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug, instrument};
@@ -37,7 +38,7 @@ pub struct Module {
 
     pub ibc_handler_address: H160,
 
-    pub provider: DynProvider,
+    pub provider: MultiRpc<DynProvider>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,8 +47,12 @@ pub struct Config {
     /// The address of the `IBCHandler` smart contract.
     pub ibc_handler_address: H160,
 
-    /// The RPC endpoint for the execution chain.
-    pub rpc_url: String,
+    /// The RPC endpoint(s) for the execution chain. Requests fail over to the next endpoint in order.
+    pub rpc_url: RpcUrls,
+
+    /// Require this many of the endpoints to return the same proof.
+    #[serde(default)]
+    pub quorum: Option<usize>,
 
     #[serde(default)]
     pub max_cache_size: u32,
@@ -57,14 +62,20 @@ impl ProofModule<IbcUnion> for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: ProofModuleInfo) -> Result<Self, BoxDynError> {
-        let provider = DynProvider::new(
+        let max_cache_size = config.max_cache_size;
+
+        let provider = MultiRpc::new(config.rpc_url, config.quorum, |rpc_url| async move {
             ProviderBuilder::new()
-                .layer(CacheLayer::new(config.max_cache_size))
-                .on_builtin(&config.rpc_url)
-                .await?,
-        );
+                .layer(CacheLayer::new(max_cache_size))
+                .on_builtin(&rpc_url)
+                .await
+                .map(DynProvider::new)
+        })
+        .await?;
 
-        let chain_id = provider.get_chain_id().await?;
+        let chain_id = provider
+            .read(|provider| async move { provider.get_chain_id().await })
+            .await?;
 
         info.ensure_chain_id(chain_id.to_string())?;
 
@@ -103,32 +114,37 @@ impl ProofModuleServer<IbcUnion> for Module {
 
         let proof = self
             .provider
-            .get_proof(
-                self.ibc_handler_address.get().into(),
-                vec![location.to_be_bytes().into()],
-            )
-            .block_id(execution_height.into())
+            .read(|provider| async move {
+                let proof = provider
+                    .get_proof(
+                        self.ibc_handler_address.get().into(),
+                        vec![location.to_be_bytes().into()],
+                    )
+                    .block_id(execution_height.into())
+                    .await
+                    .map_err(|e| {
+                        ErrorObject::owned(
+                            -1,
+                            format!("error fetching proof: {}", ErrorReporter(e)),
+                            None::<()>,
+                        )
+                    })?;
+
+                let proof = match <[_; 1]>::try_from(proof.storage_proof) {
+                    Ok([proof]) => proof,
+                    Err(invalid) => {
+                        panic!("received invalid response from eth_getProof, expected length of 1 but got `{invalid:#?}`");
+                    }
+                };
+
+                Ok(StorageProof {
+                    key: U256::from_be_bytes(proof.key.as_b256().0),
+                    value: U256::from_be_bytes(proof.value.to_be_bytes()),
+                    proof: proof.proof.into_iter().map(|bytes| bytes.into()).collect(),
+                })
+            })
             .await
-            .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
-                    format!("error fetching proof: {}", ErrorReporter(e)),
-                    None::<()>,
-                )
-            })?;
-
-        let proof = match <[_; 1]>::try_from(proof.storage_proof) {
-            Ok([proof]) => proof,
-            Err(invalid) => {
-                panic!("received invalid response from eth_getProof, expected length of 1 but got `{invalid:#?}`");
-            }
-        };
-
-        let proof = StorageProof {
-            key: U256::from_be_bytes(proof.key.as_b256().0),
-            value: U256::from_be_bytes(proof.value.to_be_bytes()),
-            proof: proof.proof.into_iter().map(|bytes| bytes.into()).collect(),
-        };
+            .map_err(multi_rpc_error)?;
 
         let proof_type = if proof.value == U256::ZERO {
             ProofType::NonMembership
@@ -139,3 +155,10 @@ impl ProofModuleServer<IbcUnion> for Module {
         Ok((into_value(proof), proof_type))
     }
 }
+
+fn multi_rpc_error(err: multi_rpc::Error<ErrorObjectOwned>) -> ErrorObjectOwned {
+    match err {
+        multi_rpc::Error::Rpc(err) => err,
+        err => ErrorObject::owned(-1, err.to_string(), None::<()>),
+    }
+}
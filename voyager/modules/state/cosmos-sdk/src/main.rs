@@ -19,6 +19,7 @@ use jsonrpsee::{
     types::{ErrorObject, ErrorObjectOwned},
     Extensions,
 };
+use multi_rpc::{MultiRpc, RpcUrls};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{error, instrument};
@@ -58,7 +59,7 @@ pub struct Module {
     pub chain_id: ChainId,
     pub chain_revision: u64,
 
-    pub tm_client: cometbft_rpc::Client,
+    pub tm_client: MultiRpc<cometbft_rpc::Client>,
 
     pub checksum_cache: Arc<DashMap<H256<HexUnprefixed>, WasmClientType>>,
 }
@@ -66,7 +67,11 @@ pub struct Module {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
-    pub rpc_url: String,
+    /// The rpc endpoint(s) of the chain. Requests fail over to the next endpoint in order.
+    pub rpc_url: RpcUrls,
+    /// Require this many of the endpoints to agree on the result of state queries.
+    #[serde(default)]
+    pub quorum: Option<usize>,
     #[serde(default = "default_max_drift")]
     pub max_drift: u64,
 }
@@ -79,9 +84,15 @@ impl StateModule<IbcClassic> for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: StateModuleInfo) -> Result<Self, BoxDynError> {
-        let tm_client = cometbft_rpc::Client::new(config.rpc_url).await?;
+        let tm_client =
+            MultiRpc::new(config.rpc_url, config.quorum, cometbft_rpc::Client::new).await?;
 
-        let chain_id = tm_client.status().await?.node_info.network;
+        let chain_id =
+            tm_client
+                .read(|client| async move {
+                    client.status().await.map(|status| status.node_info.network)
+                })
+                .await?;
 
         info.ensure_chain_id(&chain_id)?;
 
@@ -233,24 +244,23 @@ impl Module {
     // }
 
     async fn abci_query(&self, path_string: &str, height: Height) -> RpcResult<QueryResponse> {
+        let query_height = i64::try_from(height.height())
+            .expect("should be fine")
+            .try_into()
+            .expect("invalid height");
+
         self.tm_client
-            .abci_query(
-                IBC_STORE_PATH,
-                &path_string,
-                Some(
-                    i64::try_from(height.height())
-                        .expect("should be fine")
-                        .try_into()
-                        .expect("invalid height"),
-                ),
-                false,
-            )
+            .read(|client| async move {
+                client
+                    .abci_query(IBC_STORE_PATH, path_string, Some(query_height), false)
+                    .await
+                    .map(|response| response.response)
+            })
             .await
             .map_err(rpc_error(
                 format_args!("error fetching abci query"),
                 Some(json!({ "height": height, "path": path_string })),
             ))
-            .map(|response| response.response)
     }
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id, %height, %client_id))]
@@ -16,9 +16,10 @@ use ibc_union_spec::{
 };
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
+    types::{ErrorObject, ErrorObjectOwned},
     Extensions,
 };
+use multi_rpc::{MultiRpc, RpcUrls};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{info, instrument};
@@ -45,7 +46,7 @@ pub struct Module {
 
     pub ibc_handler_address: H160,
 
-    pub provider: DynProvider,
+    pub provider: MultiRpc<DynProvider>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,8 +55,12 @@ pub struct Config {
     /// The address of the `IBCHandler` smart contract.
     pub ibc_handler_address: H160,
 
-    /// The RPC endpoint for the execution chain.
-    pub rpc_url: String,
+    /// The RPC endpoint(s) for the execution chain. Requests fail over to the next endpoint in order.
+    pub rpc_url: RpcUrls,
+
+    /// Require this many of the endpoints to agree on the result of state queries.
+    #[serde(default)]
+    pub quorum: Option<usize>,
 
     #[serde(default)]
     pub max_cache_size: u32,
@@ -65,14 +70,20 @@ impl StateModule<IbcUnion> for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: StateModuleInfo) -> Result<Self, BoxDynError> {
-        let provider = DynProvider::new(
+        let max_cache_size = config.max_cache_size;
+
+        let provider = MultiRpc::new(config.rpc_url, config.quorum, |rpc_url| async move {
             ProviderBuilder::new()
-                .layer(CacheLayer::new(config.max_cache_size))
-                .on_builtin(&config.rpc_url)
-                .await?,
-        );
+                .layer(CacheLayer::new(max_cache_size))
+                .on_builtin(&rpc_url)
+                .await
+                .map(DynProvider::new)
+        })
+        .await?;
 
-        let chain_id = provider.get_chain_id().await?;
+        let chain_id = provider
+            .read(|provider| async move { provider.get_chain_id().await })
+            .await?;
 
         info.ensure_chain_id(chain_id.to_string())?;
 
@@ -90,18 +101,19 @@ impl Module {
         Height::new(height)
     }
 
-    fn ibc_handler(&self) -> IbcInstance<(), DynProvider> {
-        Ibc::new(self.ibc_handler_address.get().into(), self.provider.clone())
+    fn ibc_handler(&self, provider: &DynProvider) -> IbcInstance<(), DynProvider> {
+        Ibc::new(self.ibc_handler_address.get().into(), provider.clone())
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip(self, provider))]
     pub async fn client_address(
         &self,
+        provider: &DynProvider,
         client_id: u32,
         height: u64,
     ) -> RpcResult<alloy::primitives::Address> {
         let client_address = self
-            .ibc_handler()
+            .ibc_handler(provider)
             .clientImpls(client_id)
             .block(height.into())
             .call()
@@ -121,12 +133,19 @@ impl Module {
     }
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id, %height, %client_id))]
-    async fn query_client_state(&self, height: Height, client_id: u32) -> RpcResult<Bytes> {
+    async fn query_client_state(
+        &self,
+        provider: &DynProvider,
+        height: Height,
+        client_id: u32,
+    ) -> RpcResult<Bytes> {
         let execution_height = height.height();
 
-        let client_address = self.client_address(client_id, execution_height).await?;
+        let client_address = self
+            .client_address(provider, client_id, execution_height)
+            .await?;
 
-        let light_client = ILightClient::new(client_address, self.provider.clone());
+        let light_client = ILightClient::new(client_address, provider.clone());
         let client_state = light_client
             .getClientState(client_id)
             .block(execution_height.into())
@@ -151,15 +170,18 @@ impl Module {
     #[instrument(skip_all, fields(chain_id = %self.chain_id, %height, %client_id, %trusted_height))]
     async fn query_consensus_state(
         &self,
+        provider: &DynProvider,
         height: Height,
         client_id: u32,
         trusted_height: u64,
     ) -> RpcResult<Bytes> {
         let execution_height = height.height();
 
-        let client_address = self.client_address(client_id, execution_height).await?;
+        let client_address = self
+            .client_address(provider, client_id, execution_height)
+            .await?;
 
-        let light_client = ILightClient::new(client_address, self.provider.clone());
+        let light_client = ILightClient::new(client_address, provider.clone());
 
         let consensus_state = light_client
             .getConsensusState(client_id, trusted_height)
@@ -182,12 +204,13 @@ impl Module {
     #[instrument(skip_all, fields(chain_id = %self.chain_id, %height, %connection_id))]
     async fn query_connection(
         &self,
+        provider: &DynProvider,
         height: Height,
         connection_id: u32,
     ) -> RpcResult<Option<Connection>> {
         let execution_height = height.height();
 
-        let ibc_handler = self.ibc_handler();
+        let ibc_handler = self.ibc_handler(provider);
 
         let raw = ibc_handler
             .provider()
@@ -223,10 +246,15 @@ impl Module {
     }
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id, %height, %channel_id))]
-    async fn query_channel(&self, height: Height, channel_id: u32) -> RpcResult<Option<Channel>> {
+    async fn query_channel(
+        &self,
+        provider: &DynProvider,
+        height: Height,
+        channel_id: u32,
+    ) -> RpcResult<Option<Channel>> {
         let execution_height = height.height();
 
-        let ibc_handler = self.ibc_handler();
+        let ibc_handler = self.ibc_handler(provider);
 
         // https://github.com/alloy-rs/core/issues/811
         // let raw = ibc_handler
@@ -277,13 +305,14 @@ impl Module {
     #[instrument(skip_all, fields(chain_id = %self.chain_id, %height, %channel_id))]
     async fn query_batch_packets(
         &self,
+        provider: &DynProvider,
         height: Height,
         channel_id: u32,
         batch_hash: H256,
     ) -> RpcResult<Option<H256>> {
         let execution_height = height.height();
 
-        let ibc_handler = self.ibc_handler();
+        let ibc_handler = self.ibc_handler(provider);
 
         let raw = ibc_handler
             .commitments(
@@ -316,13 +345,14 @@ impl Module {
     #[instrument(skip_all, fields(chain_id = %self.chain_id, %height, %channel_id))]
     async fn query_batch_receipts(
         &self,
+        provider: &DynProvider,
         height: Height,
         channel_id: u32,
         batch_hash: H256,
     ) -> RpcResult<Option<H256>> {
         let execution_height = height.height();
 
-        let ibc_handler = self.ibc_handler();
+        let ibc_handler = self.ibc_handler(provider);
 
         let raw = ibc_handler
             .commitments(
@@ -351,50 +381,66 @@ impl Module {
             Ok(Some(raw.into()))
         }
     }
-}
 
-#[async_trait]
-impl StateModuleServer<IbcUnion> for Module {
-    async fn query_ibc_state(
+    async fn query_ibc_state_with(
         &self,
-        _: &Extensions,
+        provider: &DynProvider,
         at: Height,
         path: StorePath,
     ) -> RpcResult<Value> {
         match path {
             StorePath::ClientState(path) => self
-                .query_client_state(at, path.client_id)
+                .query_client_state(provider, at, path.client_id)
                 .await
                 .map(into_value),
             StorePath::ConsensusState(path) => self
-                .query_consensus_state(at, path.client_id, path.height)
+                .query_consensus_state(provider, at, path.client_id, path.height)
                 .await
                 .map(into_value),
             StorePath::Connection(path) => self
-                .query_connection(at, path.connection_id)
+                .query_connection(provider, at, path.connection_id)
                 .await
                 .map(into_value),
             StorePath::Channel(path) => self
-                .query_channel(at, path.channel_id)
+                .query_channel(provider, at, path.channel_id)
                 .await
                 .map(into_value),
             StorePath::BatchReceipts(path) => self
-                .query_batch_receipts(at, path.channel_id, path.batch_hash)
+                .query_batch_receipts(provider, at, path.channel_id, path.batch_hash)
                 .await
                 .map(into_value),
             StorePath::BatchPackets(path) => self
-                .query_batch_packets(at, path.channel_id, path.batch_hash)
+                .query_batch_packets(provider, at, path.channel_id, path.batch_hash)
                 .await
                 .map(into_value),
         }
     }
+}
+
+#[async_trait]
+impl StateModuleServer<IbcUnion> for Module {
+    async fn query_ibc_state(
+        &self,
+        _: &Extensions,
+        at: Height,
+        path: StorePath,
+    ) -> RpcResult<Value> {
+        self.provider
+            .read(|provider| self.query_ibc_state_with(provider, at, path.clone()))
+            .await
+            .map_err(multi_rpc_error)
+    }
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
     async fn client_info(&self, _: &Extensions, client_id: u32) -> RpcResult<ClientInfo> {
-        let ibc_handler = self.ibc_handler();
-        let client_type = ibc_handler
-            .clientTypes(client_id)
-            .call()
+        let client_type = self
+            .provider
+            .failover(|provider| async move {
+                self.ibc_handler(provider)
+                    .clientTypes(client_id)
+                    .call()
+                    .await
+            })
             .await
             .map_err(|e| {
                 ErrorObject::owned(
@@ -412,3 +458,10 @@ impl StateModuleServer<IbcUnion> for Module {
         })
     }
 }
+
+fn multi_rpc_error(err: multi_rpc::Error<ErrorObjectOwned>) -> ErrorObjectOwned {
+    match err {
+        multi_rpc::Error::Rpc(err) => err,
+        err => ErrorObject::owned(-1, err.to_string(), None::<()>),
+    }
+}
@@ -1,15 +1,17 @@
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 use unionlabs::ibc::core::client::height::Height;
 use voyager_message::core::ChainId;
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 pub enum ModuleCall {
     FetchUpdate(FetchUpdate),
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchUpdate {
     pub counterparty_chain_id: ChainId,
     pub update_from: Height,
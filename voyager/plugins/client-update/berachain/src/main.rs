@@ -7,7 +7,7 @@ use ethereum_light_client_types::AccountProof;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::ErrorObject,
-    Extensions,
+    Extensions, Methods,
 };
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
@@ -25,7 +25,7 @@ use voyager_message::{
     hook::UpdateHook,
     into_value,
     module::{PluginInfo, PluginServer},
-    DefaultCmd, Plugin, PluginMessage, RawClientId, VoyagerMessage,
+    plugin_schema_methods, DefaultCmd, Plugin, PluginMessage, RawClientId, VoyagerMessage,
 };
 use voyager_vm::{call, conc, data, pass::PassResult, seq, BoxDynError, Op, Visit};
 
@@ -112,6 +112,10 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, ModuleCallback>()
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
@@ -1,9 +1,10 @@
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 use unionlabs::ibc::core::client::height::Height;
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 #[allow(clippy::large_enum_variant)]
 pub enum ModuleCall {
     FetchUpdateBoot(FetchUpdateBoot),
@@ -12,19 +13,145 @@ pub enum ModuleCall {
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchUpdateBoot {
     pub update_from: Height,
     pub update_to: Height,
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchUpdate {
     pub update_from: Height,
     pub update_to: Height,
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchProveRequest {
     pub update_from: Height,
+    /// The request as sent to galois, this is not described by the schema.
+    #[schemars(with = "serde_json::Value")]
     pub request: galois_rpc::prove_request::ProveRequest,
+    /// The prover the request has been submitted to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submission: Option<ProverSubmission>,
+}
+
+#[model]
+#[derive(JsonSchema)]
+pub struct ProverSubmission {
+    pub endpoint: String,
+    /// The unix timestamp, in seconds, at which the request was submitted to the prover.
+    pub submitted_at: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use cometbft_types::{
+        crypto::public_key::PublicKey,
+        types::{
+            canonical_block_id::CanonicalBlockId,
+            canonical_part_set_header::CanonicalPartSetHeader, header::Header,
+            signed_msg_type::SignedMsgType, simple_validator::SimpleValidator,
+        },
+    };
+    use galois_rpc::{
+        canonical_vote::CanonicalVote, prove_request::ProveRequest,
+        validator_set_commit::ValidatorSetCommit,
+    };
+    use unionlabs::bounded::BoundedI64;
+    use voyager_message::into_value;
+
+    use super::*;
+
+    /// Validates the message as voyager does when it is enqueued, against the advertised schema.
+    fn assert_valid(call: ModuleCall) {
+        let schema = into_value(schemars::schema_for!(ModuleCall));
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let message = into_value(call);
+
+        let errors = validator
+            .iter_errors(&message)
+            .map(|err| format!("{err} (at {})", err.instance_path))
+            .collect::<Vec<_>>();
+
+        assert!(errors.is_empty(), "{errors:?}\n{message:#}");
+    }
+
+    fn validator_set_commit() -> ValidatorSetCommit {
+        ValidatorSetCommit {
+            validators: vec![SimpleValidator {
+                pub_key: PublicKey::Bn254(vec![1; 32].into()),
+                voting_power: 100,
+            }],
+            signatures: vec![vec![2; 64]],
+            bitmap: vec![1],
+        }
+    }
+
+    fn prove_request() -> ProveRequest {
+        ProveRequest {
+            vote: CanonicalVote {
+                ty: SignedMsgType::Precommit,
+                height: BoundedI64::new(2).unwrap(),
+                round: BoundedI64::new(0).unwrap(),
+                block_id: CanonicalBlockId {
+                    hash: Default::default(),
+                    part_set_header: CanonicalPartSetHeader {
+                        total: 1,
+                        hash: Default::default(),
+                    },
+                },
+                chain_id: "union-devnet-1".to_owned(),
+            },
+            untrusted_header: Header {
+                version: Default::default(),
+                chain_id: "union-devnet-1".to_owned(),
+                height: BoundedI64::new(2).unwrap(),
+                time: Default::default(),
+                last_block_id: Default::default(),
+                last_commit_hash: Default::default(),
+                data_hash: Default::default(),
+                validators_hash: Default::default(),
+                next_validators_hash: Default::default(),
+                consensus_hash: Default::default(),
+                app_hash: Default::default(),
+                last_results_hash: Default::default(),
+                evidence_hash: Default::default(),
+                proposer_address: Default::default(),
+            },
+            trusted_commit: validator_set_commit(),
+            untrusted_commit: validator_set_commit(),
+        }
+    }
+
+    #[test]
+    fn fetch_prove_request_matches_schema() {
+        assert_valid(ModuleCall::from(FetchProveRequest {
+            update_from: Height::new(1),
+            request: prove_request(),
+            submission: None,
+        }));
+        assert_valid(ModuleCall::from(FetchProveRequest {
+            update_from: Height::new(1),
+            request: prove_request(),
+            submission: Some(ProverSubmission {
+                endpoint: "http://galois:9999".to_owned(),
+                submitted_at: 1_700_000_000,
+            }),
+        }));
+    }
+
+    #[test]
+    fn fetch_update_matches_schema() {
+        assert_valid(ModuleCall::from(FetchUpdateBoot {
+            update_from: Height::new(1),
+            update_to: Height::new(2),
+        }));
+        assert_valid(ModuleCall::from(FetchUpdate {
+            update_from: Height::new(1),
+            update_to: Height::new(2),
+        }));
+    }
 }
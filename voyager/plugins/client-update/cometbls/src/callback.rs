@@ -1,6 +1,7 @@
 use cometbls_light_client_types::{header::Header, light_header::LightHeader};
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 use subset_of::SubsetOf;
 use unionlabs::ibc::core::client::height::Height;
 use voyager_message::{
@@ -12,13 +13,14 @@ use voyager_vm::{data, Op};
 use crate::{data::ProveResponse, Module};
 
 #[model]
-#[derive(Enumorph, SubsetOf)]
+#[derive(Enumorph, SubsetOf, JsonSchema)]
 #[allow(clippy::large_enum_variant)]
 pub enum ModuleCallback {
     AggregateHeader(AggregateHeader),
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct AggregateHeader {}
 
 impl Module {
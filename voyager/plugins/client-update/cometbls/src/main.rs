@@ -1,6 +1,7 @@
 use std::{
     collections::{HashMap, VecDeque},
     num::ParseIntError,
+    sync::Arc,
 };
 
 use call::FetchUpdateBoot;
@@ -22,12 +23,12 @@ use galois_rpc::{
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::ErrorObject,
-    Extensions,
+    Extensions, Methods,
 };
 use num_bigint::BigUint;
 use protos::union::galois::api::v3::union_prover_api_client;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, info, instrument, trace};
+use tracing::{debug, error, info, instrument, trace, warn};
 use unionlabs::{bounded::BoundedI64, ibc::core::client::height::Height};
 use voyager_message::{
     call::{Call, WaitForHeight},
@@ -35,21 +36,24 @@ use voyager_message::{
     data::Data,
     hook::UpdateHook,
     module::{PluginInfo, PluginServer},
-    DefaultCmd, Plugin, PluginMessage, VoyagerMessage, FATAL_JSONRPC_ERROR_CODE,
+    plugin_schema_methods, DefaultCmd, Plugin, PluginMessage, VoyagerMessage,
+    FATAL_JSONRPC_ERROR_CODE,
 };
 use voyager_vm::{
     call, data, defer, noop, now, pass::PassResult, promise, seq, void, BoxDynError, Op, Visit,
 };
 
 use crate::{
-    call::{FetchProveRequest, FetchUpdate, ModuleCall},
+    call::{FetchProveRequest, FetchUpdate, ModuleCall, ProverSubmission},
     callback::{AggregateHeader, ModuleCallback},
     data::{ModuleData, ProveResponse},
+    prover::{unix_timestamp, JobId, ProverConfig, Provers},
 };
 
 pub mod call;
 pub mod callback;
 pub mod data;
+pub mod prover;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() {
@@ -63,7 +67,7 @@ pub struct Module {
     pub cometbft_client: cometbft_rpc::Client,
     pub chain_revision: u64,
 
-    pub prover_endpoints: Vec<String>,
+    pub provers: Arc<Provers>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +78,9 @@ pub struct Config {
     pub rpc_url: String,
 
     pub prover_endpoints: Vec<String>,
+
+    #[serde(default)]
+    pub prover: ProverConfig,
 }
 
 impl Plugin for Module {
@@ -84,6 +91,10 @@ impl Plugin for Module {
     type Cmd = DefaultCmd;
 
     async fn new(config: Self::Config) -> Result<Self, BoxDynError> {
+        if config.prover_endpoints.is_empty() {
+            return Err("at least one prover endpoint is required".into());
+        }
+
         let cometbft_client = cometbft_rpc::Client::new(config.rpc_url).await?;
 
         let chain_id = cometbft_client
@@ -118,7 +129,7 @@ impl Plugin for Module {
             cometbft_client,
             chain_id: ChainId::new(chain_id),
             chain_revision,
-            prover_endpoints: config.prover_endpoints,
+            provers: Provers::new(config.prover_endpoints, config.prover),
         })
     }
 
@@ -135,6 +146,10 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, ModuleCallback>()
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
@@ -502,6 +517,7 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                                 trusted_commit: trusted_validators_commit,
                                 untrusted_commit: untrusted_validators_commit,
                             },
+                            submission: None,
                         }),
                     )),
                 ]))
@@ -509,32 +525,14 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
             ModuleCall::FetchProveRequest(FetchProveRequest {
                 update_from,
                 request,
+                submission,
             }) => {
-                debug!("submitting prove request");
-
-                let prover_endpoint = &self.prover_endpoints[usize::try_from(
-                    request.untrusted_header.height.inner(),
-                )
-                .expect("never going to happen bro")
-                    % self.prover_endpoints.len()];
-
-                let response =
-                    union_prover_api_client::UnionProverApiClient::connect(prover_endpoint.clone())
-                        .await
-                        .unwrap()
-                        .poll(protos::union::galois::api::v3::PollRequest::from(
-                            PollRequest {
-                                request: request.clone(),
-                            },
-                        ))
-                        .await
-                        .map(|x| x.into_inner().try_into().unwrap());
-
-                debug!("submitted prove request");
-
-                let retry = || {
-                    debug!("proof pending");
+                let job = JobId {
+                    update_from,
+                    update_to: request.untrusted_header.height.inner(),
+                };
 
+                let retry = |submission: Option<ProverSubmission>| {
                     seq([
                         // REVIEW: How long should we wait between polls?
                         defer(now() + 1),
@@ -543,17 +541,114 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                             ModuleCall::from(FetchProveRequest {
                                 update_from,
                                 request: request.clone(),
+                                submission,
                             }),
                         )),
                     ])
                 };
+
+                let submit = |endpoint| ProverSubmission {
+                    endpoint,
+                    submitted_at: unix_timestamp(),
+                };
+
+                let submission = match submission {
+                    Some(submission)
+                        if unix_timestamp().saturating_sub(submission.submitted_at)
+                            > self.provers.job_timeout() =>
+                    {
+                        warn!(
+                            prover = %submission.endpoint,
+                            ?job,
+                            "prove request timed out, re-submitting to another prover"
+                        );
+
+                        self.provers.resubmit(job, &submission.endpoint, false);
+
+                        self.provers
+                            .select(job, Some(&submission.endpoint))
+                            .map(submit)
+                    }
+                    Some(submission)
+                        if self.provers.resume(
+                            job,
+                            &submission.endpoint,
+                            submission.submitted_at,
+                        ) =>
+                    {
+                        Some(submission)
+                    }
+                    Some(submission) => {
+                        warn!(
+                            prover = %submission.endpoint,
+                            ?job,
+                            "prover is no longer configured, re-submitting to another prover"
+                        );
+
+                        self.provers.select(job, None).map(submit)
+                    }
+                    None => self.provers.select(job, None).map(submit),
+                };
+
+                let Some(submission) = submission else {
+                    debug!(?job, "no prover available");
+
+                    return Ok(retry(None));
+                };
+
+                debug!(prover = %submission.endpoint, "submitting prove request");
+
+                let response = match union_prover_api_client::UnionProverApiClient::connect(
+                    submission.endpoint.clone(),
+                )
+                .await
+                {
+                    Ok(mut client) => client
+                        .poll(protos::union::galois::api::v3::PollRequest::from(
+                            PollRequest {
+                                request: request.clone(),
+                            },
+                        ))
+                        .await
+                        .map(|x| x.into_inner().try_into().unwrap()),
+                    Err(err) => Err(tonic::Status::unavailable(err.to_string())),
+                };
+
+                debug!("submitted prove request");
+
                 match response {
-                    Ok(PollResponse::Pending) => Ok(retry()),
-                    Err(status) if status.message() == "busy_building" => Ok(retry()),
-                    Err(err) => panic!("prove request failed: {:?}", err),
+                    Ok(PollResponse::Pending) => {
+                        debug!("proof pending");
+
+                        Ok(retry(Some(submission)))
+                    }
+                    Err(status) if status.message() == "busy_building" => {
+                        debug!("proof pending");
+
+                        Ok(retry(Some(submission)))
+                    }
+                    Err(status) => {
+                        warn!(
+                            prover = %submission.endpoint,
+                            ?job,
+                            %status,
+                            "prove request failed, re-submitting to another prover"
+                        );
+
+                        self.provers.resubmit(job, &submission.endpoint, true);
+
+                        Ok(retry(None))
+                    }
                     Ok(PollResponse::Failed(ProveRequestFailed { message })) => {
                         error!(%message, "prove request failed");
 
+                        self.provers.finish(
+                            job,
+                            &submission.endpoint,
+                            submission.submitted_at,
+                            false,
+                        );
+
                         Err(ErrorObject::owned(
                             FATAL_JSONRPC_ERROR_CODE,
                             format!("prove request failed: {message}"),
@@ -561,7 +656,14 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                         ))
                     }
                     Ok(PollResponse::Done(ProveRequestDone { response })) => {
-                        info!(prover = %prover_endpoint, "proof generated");
+                        info!(prover = %submission.endpoint, "proof generated");
+
+                        self.provers.finish(
+                            job,
+                            &submission.endpoint,
+                            submission.submitted_at,
+                            true,
+                        );
 
                         Ok(data(PluginMessage::new(
                             self.plugin_name(),
@@ -0,0 +1,405 @@
+//! Selection of the prover a prove request is submitted to.
+//!
+//! Requests are assigned to the healthy prover with the fewest active jobs, up to `max_concurrent_jobs` per prover.
+//! Provers are health checked in the background, and are also marked as unhealthy as soon as a request to them fails.
+//! The active jobs are only tracked in memory; after a restart, they are registered again as they are polled. Jobs that
+//! are no longer polled, such as when their message was dropped from the queue, are expired after the `job_timeout`.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use opentelemetry::{
+    metrics::{Counter, Gauge, Histogram},
+    KeyValue,
+};
+use protos::union::galois::api::v3::{union_prover_api_client, QueryStatsRequest};
+use serde::{Deserialize, Serialize};
+use tonic::Code;
+use tracing::{debug, info, warn};
+use unionlabs::ibc::core::client::height::Height;
+
+/// Health checks taking longer than this are considered failed.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProverConfig {
+    /// The maximum number of jobs submitted to a single prover at once. Unlimited if not set.
+    pub max_concurrent_jobs: Option<usize>,
+    /// Seconds after which a job that is still pending is re-submitted to another prover.
+    pub job_timeout: u64,
+    /// Seconds between health checks of each prover.
+    pub health_check_interval: u64,
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_jobs: None,
+            job_timeout: 10 * 60,
+            health_check_interval: 30,
+        }
+    }
+}
+
+/// Identifies a prove request while it is being worked on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JobId {
+    pub update_from: Height,
+    pub update_to: i64,
+}
+
+#[derive(Debug)]
+pub struct Provers {
+    config: ProverConfig,
+    provers: Vec<Prover>,
+
+    /// Seconds from the submission of a job to its proof, per prover.
+    proof_latency: Histogram<f64>,
+    /// Jobs re-submitted to another prover, per prover they were taken away from.
+    resubmissions: Counter<u64>,
+    /// 1 if the prover passed its last health check.
+    healthy: Gauge<u64>,
+}
+
+#[derive(Debug)]
+struct Prover {
+    endpoint: String,
+    state: Mutex<ProverState>,
+}
+
+#[derive(Debug)]
+struct ProverState {
+    healthy: bool,
+    /// The unix timestamp each job was assigned to the prover at.
+    active_jobs: HashMap<JobId, u64>,
+}
+
+impl Provers {
+    /// Creates the provers and spawns their health checks.
+    pub fn new(endpoints: Vec<String>, config: ProverConfig) -> Arc<Self> {
+        let provers = Arc::new(Self::without_health_checks(endpoints, config));
+
+        for index in 0..provers.provers.len() {
+            tokio::spawn(provers.clone().health_check(index));
+        }
+
+        provers
+    }
+
+    fn without_health_checks(endpoints: Vec<String>, config: ProverConfig) -> Self {
+        let meter = opentelemetry::global::meter("voyager.client_update.cometbls");
+
+        Self {
+            provers: endpoints
+                .into_iter()
+                .map(|endpoint| Prover {
+                    endpoint,
+                    // optimistically healthy until the first health check
+                    state: Mutex::new(ProverState {
+                        healthy: true,
+                        active_jobs: HashMap::new(),
+                    }),
+                })
+                .collect(),
+            proof_latency: meter
+                .f64_histogram("prover.proof_latency")
+                .with_unit("s")
+                .with_description("Seconds from the submission of a prove request to its proof")
+                .build(),
+            resubmissions: meter
+                .u64_counter("prover.resubmissions")
+                .with_description("Prove requests re-submitted to another prover")
+                .build(),
+            healthy: meter
+                .u64_gauge("prover.healthy")
+                .with_description("Whether the prover passed its last health check")
+                .build(),
+            config,
+        }
+    }
+
+    #[must_use]
+    pub fn job_timeout(&self) -> u64 {
+        self.config.job_timeout
+    }
+
+    /// Assigns `job` to the healthy prover with the fewest active jobs, avoiding `exclude` unless it is the only prover
+    /// available. Returns `None` if all provers are unhealthy or at their job limit.
+    pub fn select(&self, job: JobId, exclude: Option<&str>) -> Option<String> {
+        let now = unix_timestamp();
+
+        let prover = self
+            .provers
+            .iter()
+            .filter_map(|prover| {
+                let mut state = prover.state.lock().expect("lock is not poisoned");
+
+                self.expire_jobs(&prover.endpoint, &mut state, now);
+
+                self.is_available(&state)
+                    .then_some((prover, state.active_jobs.len()))
+            })
+            .min_by_key(|(prover, active_jobs)| {
+                (Some(prover.endpoint.as_str()) == exclude, *active_jobs)
+            })
+            .map(|(prover, _)| prover)?;
+
+        let mut state = prover.state.lock().expect("lock is not poisoned");
+
+        // another job may have been assigned to the prover in the meantime
+        if !self.is_available(&state) {
+            return None;
+        }
+
+        state.active_jobs.insert(job, now);
+
+        debug!(prover = %prover.endpoint, ?job, "assigned job to prover");
+
+        Some(prover.endpoint.clone())
+    }
+
+    /// Removes the jobs that were assigned to the prover more than `job_timeout` ago.
+    fn expire_jobs(&self, endpoint: &str, state: &mut ProverState, now: u64) {
+        state.active_jobs.retain(|job, assigned_at| {
+            let expired = now.saturating_sub(*assigned_at) > self.config.job_timeout;
+
+            if expired {
+                debug!(prover = %endpoint, ?job, "expiring job");
+            }
+
+            !expired
+        });
+    }
+
+    fn is_available(&self, state: &ProverState) -> bool {
+        state.healthy
+            && self
+                .config
+                .max_concurrent_jobs
+                .is_none_or(|max| state.active_jobs.len() < max)
+    }
+
+    /// Registers a job that was assigned to `endpoint` at `submitted_at` before, as the active jobs are not persisted.
+    /// Returns `false` if the prover is no longer configured.
+    pub fn resume(&self, job: JobId, endpoint: &str, submitted_at: u64) -> bool {
+        match self.prover(endpoint) {
+            Some(prover) => {
+                prover
+                    .state
+                    .lock()
+                    .expect("lock is not poisoned")
+                    .active_jobs
+                    .insert(job, submitted_at);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the job from the prover, recording the time to its proof if it succeeded.
+    pub fn finish(&self, job: JobId, endpoint: &str, submitted_at: u64, succeeded: bool) {
+        if let Some(prover) = self.prover(endpoint) {
+            prover
+                .state
+                .lock()
+                .expect("lock is not poisoned")
+                .active_jobs
+                .remove(&job);
+        }
+
+        if succeeded {
+            self.proof_latency.record(
+                unix_timestamp().saturating_sub(submitted_at) as f64,
+                &[KeyValue::new("endpoint", endpoint.to_owned())],
+            );
+        }
+    }
+
+    /// Takes the job away from the prover, so that it can be re-submitted to another one. If the request to the prover
+    /// failed, it is also marked as unhealthy until its next successful health check.
+    pub fn resubmit(&self, job: JobId, endpoint: &str, failed: bool) {
+        if let Some(prover) = self.prover(endpoint) {
+            let mut state = prover.state.lock().expect("lock is not poisoned");
+
+            state.active_jobs.remove(&job);
+
+            if failed {
+                state.healthy = false;
+            }
+        }
+
+        self.resubmissions
+            .add(1, &[KeyValue::new("endpoint", endpoint.to_owned())]);
+    }
+
+    fn prover(&self, endpoint: &str) -> Option<&Prover> {
+        self.provers
+            .iter()
+            .find(|prover| prover.endpoint == endpoint)
+    }
+
+    async fn health_check(self: Arc<Self>, index: usize) {
+        let prover = &self.provers[index];
+
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.health_check_interval));
+
+        loop {
+            interval.tick().await;
+
+            let healthy = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, async {
+                union_prover_api_client::UnionProverApiClient::connect(prover.endpoint.clone())
+                    .await
+                    .map_err(|err| err.to_string())?
+                    .query_stats(QueryStatsRequest {})
+                    .await
+                    .map(|_| ())
+                    .or_else(|status| match status.code() {
+                        // the prover is reachable, but busy or an older version
+                        Code::Unimplemented | Code::ResourceExhausted => Ok(()),
+                        _ => Err(status.to_string()),
+                    })
+            })
+            .await
+            {
+                Ok(Ok(())) => true,
+                Ok(Err(err)) => {
+                    warn!(prover = %prover.endpoint, %err, "prover health check failed");
+                    false
+                }
+                Err(_) => {
+                    warn!(prover = %prover.endpoint, "prover health check timed out");
+                    false
+                }
+            };
+
+            let mut state = prover.state.lock().expect("lock is not poisoned");
+
+            if healthy && !state.healthy {
+                info!(prover = %prover.endpoint, "prover is healthy again");
+            }
+
+            state.healthy = healthy;
+
+            self.healthy.record(
+                healthy.into(),
+                &[KeyValue::new("endpoint", prover.endpoint.clone())],
+            );
+        }
+    }
+}
+
+/// The current unix timestamp, in seconds.
+#[must_use]
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "http://a";
+    const B: &str = "http://b";
+
+    fn provers(max_concurrent_jobs: Option<usize>) -> Provers {
+        Provers::without_health_checks(
+            vec![A.to_owned(), B.to_owned()],
+            ProverConfig {
+                max_concurrent_jobs,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn job(update_to: i64) -> JobId {
+        JobId {
+            update_from: Height::new(1),
+            update_to,
+        }
+    }
+
+    #[test]
+    fn least_loaded() {
+        let provers = provers(None);
+
+        assert_eq!(provers.select(job(2), None).as_deref(), Some(A));
+        assert_eq!(provers.select(job(3), None).as_deref(), Some(B));
+        assert_eq!(provers.select(job(4), None).as_deref(), Some(A));
+
+        assert_eq!(provers.select(job(5), None).as_deref(), Some(B));
+
+        provers.finish(job(4), A, unix_timestamp(), true);
+        provers.finish(job(2), A, unix_timestamp(), true);
+
+        assert_eq!(provers.select(job(6), None).as_deref(), Some(A));
+    }
+
+    #[test]
+    fn job_limit() {
+        let provers = provers(Some(1));
+
+        assert_eq!(provers.select(job(2), None).as_deref(), Some(A));
+        assert_eq!(provers.select(job(3), None).as_deref(), Some(B));
+        assert_eq!(provers.select(job(4), None), None);
+
+        provers.finish(job(3), B, unix_timestamp(), false);
+
+        assert_eq!(provers.select(job(4), None).as_deref(), Some(B));
+    }
+
+    #[test]
+    fn exclude() {
+        let provers = provers(None);
+
+        assert_eq!(provers.select(job(2), None).as_deref(), Some(A));
+        assert_eq!(provers.select(job(3), None).as_deref(), Some(B));
+
+        provers.finish(job(2), A, unix_timestamp(), true);
+
+        // the excluded prover is avoided even if it has fewer jobs
+        assert_eq!(provers.select(job(4), Some(A)).as_deref(), Some(B));
+
+        // but is used if it is the only prover available
+        provers.resubmit(job(5), B, true);
+        assert_eq!(provers.select(job(5), Some(A)).as_deref(), Some(A));
+    }
+
+    #[test]
+    fn skips_unhealthy() {
+        let provers = provers(None);
+
+        provers.resubmit(job(2), A, true);
+
+        assert_eq!(provers.select(job(3), None).as_deref(), Some(B));
+        assert_eq!(provers.select(job(4), None).as_deref(), Some(B));
+
+        // resubmitting without a failure doesn't mark the prover as unhealthy
+        provers.resubmit(job(4), B, false);
+        assert_eq!(provers.select(job(4), None).as_deref(), Some(B));
+
+        provers.resubmit(job(4), B, true);
+        assert_eq!(provers.select(job(5), None), None);
+    }
+
+    #[test]
+    fn expires_jobs() {
+        let provers = provers(Some(1));
+
+        // jobs that are no longer polled don't hold on to their slot forever
+        assert!(provers.resume(job(2), A, 0));
+        assert!(provers.resume(job(3), B, unix_timestamp()));
+
+        assert_eq!(provers.select(job(4), None).as_deref(), Some(A));
+        assert_eq!(provers.select(job(5), None), None);
+
+        assert!(!provers.resume(job(6), "http://c", 0));
+    }
+}
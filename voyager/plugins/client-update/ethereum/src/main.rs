@@ -19,7 +19,7 @@ use futures::{stream, StreamExt, TryStreamExt};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::ErrorObject,
-    Extensions,
+    Extensions, Methods,
 };
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, instrument};
@@ -35,7 +35,7 @@ use voyager_message::{
     hook::UpdateHook,
     into_value,
     module::{PluginInfo, PluginServer},
-    DefaultCmd, Plugin, PluginMessage, RawClientId, VoyagerMessage,
+    plugin_schema_methods, DefaultCmd, Plugin, PluginMessage, RawClientId, VoyagerMessage,
 };
 use voyager_vm::{call, defer, now, pass::PassResult, seq, BoxDynError, Op, Visit};
 
@@ -193,6 +193,10 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, ModuleCallback>()
+    }
 }
 
 #[async_trait]
@@ -1,14 +1,16 @@
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 use unionlabs::ibc::core::client::height::Height;
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 pub enum ModuleCall {
     FetchUpdate(FetchUpdate),
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchUpdate {
     pub update_from: Height,
     pub update_to: Height,
@@ -3,7 +3,7 @@ use std::{collections::VecDeque, fmt::Debug, num::ParseIntError};
 use cometbft_types::types::{validator::Validator, validator_set::ValidatorSet};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    Extensions,
+    Extensions, Methods,
 };
 use serde::{Deserialize, Serialize};
 use tendermint_light_client_types::Header;
@@ -18,7 +18,7 @@ use voyager_message::{
     data::{Data, DecodedHeaderMeta, OrderedHeaders},
     hook::UpdateHook,
     module::{PluginInfo, PluginServer},
-    DefaultCmd, Plugin, PluginMessage, VoyagerMessage,
+    plugin_schema_methods, DefaultCmd, Plugin, PluginMessage, VoyagerMessage,
 };
 use voyager_vm::{data, pass::PassResult, BoxDynError, Op, Visit};
 
@@ -104,6 +104,10 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, ModuleCallback>()
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
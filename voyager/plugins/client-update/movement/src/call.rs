@@ -1,13 +1,15 @@
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 pub enum ModuleCall {
     FetchUpdate(FetchUpdate),
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchUpdate {
     pub from: u64,
     pub to: u64,
@@ -1,6 +1,7 @@
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 pub enum ModuleCallback {}
@@ -5,7 +5,7 @@ use call::FetchUpdate;
 use ethereum_light_client_types::{account_proof::AccountProof, storage_proof::StorageProof};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    Extensions,
+    Extensions, Methods,
 };
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument};
@@ -23,6 +23,7 @@ use voyager_message::{
     data::{Data, DecodedHeaderMeta, OrderedHeaders},
     hook::UpdateHook,
     module::{PluginInfo, PluginServer, UnexpectedChainIdError},
+    plugin_schema_methods,
     vm::{data, pass::PassResult, BoxDynError, Op, Visit},
     DefaultCmd, Plugin, PluginMessage, VoyagerMessage,
 };
@@ -105,6 +106,10 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, ModuleCallback>()
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
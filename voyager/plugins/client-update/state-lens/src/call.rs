@@ -1,18 +1,20 @@
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 use unionlabs::ibc::core::client::height::Height;
 use voyager_message::core::ChainId;
 
 use crate::StateLensClientState;
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 pub enum ModuleCall {
     FetchUpdate(FetchUpdate),
     FetchUpdateAfterL1Update(FetchUpdateAfterL1Update),
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchUpdate {
     pub chain_id: ChainId,
     pub counterparty_chain_id: ChainId,
@@ -22,8 +24,11 @@ pub struct FetchUpdate {
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchUpdateAfterL1Update {
     pub counterparty_chain_id: ChainId,
+    /// This is not described by the schema.
+    #[schemars(with = "serde_json::Value")]
     pub state_lens_client_state: StateLensClientState,
     pub client_id: u32,
     pub update_from: Height,
@@ -8,7 +8,7 @@ use ibc_union_spec::{
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::ErrorObject,
-    Extensions,
+    Extensions, Methods,
 };
 use serde::{Deserialize, Serialize};
 use state_lens_light_client_types::Header;
@@ -21,6 +21,7 @@ use voyager_message::{
     data::{Data, DecodedHeaderMeta, OrderedHeaders},
     into_value,
     module::{PluginInfo, PluginServer},
+    plugin_schema_methods,
     rpc::ProofType,
     DefaultCmd, ExtensionsExt, Plugin, PluginMessage, RawClientId, VoyagerClient, VoyagerMessage,
     FATAL_JSONRPC_ERROR_CODE, MISSING_STATE_ERROR_CODE,
@@ -82,6 +83,10 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, ModuleCallback>()
+    }
 }
 
 fn plugin_name(state_lens_client_type: &ClientType) -> String {
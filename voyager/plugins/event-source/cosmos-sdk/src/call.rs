@@ -2,10 +2,11 @@ use std::collections::BTreeSet;
 
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 use unionlabs::{ibc::core::client::height::Height, primitives::H256};
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 #[allow(clippy::large_enum_variant)]
 pub enum ModuleCall {
     FetchBlocks(FetchBlocks),
@@ -15,11 +16,13 @@ pub enum ModuleCall {
 
 /// Fetch a block at the specified height, requeuing a seq(wait(H+1), fetch(H+1)).
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchBlocks {
     pub height: Height,
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchBlock {
     /// If this is Some, then this message is "re-fetching" the events in this block, to ensure that no events were missed during the original fetch of this block.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -28,8 +31,11 @@ pub struct FetchBlock {
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct MakeChainEvent {
     pub height: Height,
     pub tx_hash: H256,
+    /// The raw event as emitted by the chain, this is not described by the schema.
+    #[schemars(with = "serde_json::Value")]
     pub event: crate::ibc_events::IbcEvent,
 }
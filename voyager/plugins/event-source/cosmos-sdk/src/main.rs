@@ -16,7 +16,7 @@ use ibc_union_spec::{path::ChannelPath, IbcUnion};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::{ErrorObject, ErrorObjectOwned},
-    Extensions,
+    Extensions, Methods,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -38,17 +38,20 @@ use voyager_message::{
     data::{ChainEvent, Data},
     into_value,
     module::{PluginInfo, PluginServer},
-    ExtensionsExt, Plugin, PluginMessage, VoyagerClient, VoyagerMessage, FATAL_JSONRPC_ERROR_CODE,
+    plugin_schema_methods, ExtensionsExt, Plugin, PluginMessage, VoyagerClient, VoyagerMessage,
+    FATAL_JSONRPC_ERROR_CODE,
 };
-use voyager_vm::{call, conc, data, noop, pass::PassResult, seq, BoxDynError, Op};
+use voyager_vm::{call, conc, data, defer, noop, now, pass::PassResult, seq, BoxDynError, Op};
 
 use crate::{
     call::{FetchBlock, FetchBlocks, MakeChainEvent, ModuleCall},
     callback::ModuleCallback,
     ibc_events::IbcEvent,
+    subscription::{Subscription, Taken},
 };
 
 pub mod ibc_events;
+pub mod subscription;
 
 pub mod call;
 pub mod callback;
@@ -74,6 +77,8 @@ pub struct Module {
     pub checksum_cache: Arc<DashMap<H256, WasmClientType>>,
 
     pub ibc_host_contract_address: Option<Bech32<H256>>,
+
+    pub subscription: Option<Arc<Subscription>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,6 +86,10 @@ pub struct Module {
 pub struct Config {
     pub chain_id: ChainId,
     pub rpc_url: String,
+    /// The CometBFT websocket endpoint (`ws://<host>:26657/websocket`). If set, new blocks are received through a
+    /// subscription instead of being polled from `rpc_url`. Blocks missed by the subscription are still polled.
+    #[serde(default)]
+    pub websocket_url: Option<String>,
     #[serde(default = "default_chunk_block_fetch_size")]
     pub chunk_block_fetch_size: u64,
     #[serde(default = "default_refetch_delay")]
@@ -137,6 +146,7 @@ impl Plugin for Module {
             refetch_delay: config.refetch_delay,
             checksum_cache: Arc::new(DashMap::default()),
             ibc_host_contract_address: config.ibc_host_contract_address,
+            subscription: config.websocket_url.map(Subscription::spawn),
         })
     }
 
@@ -166,6 +176,10 @@ impl Plugin for Module {
             }
         }
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, ModuleCallback>()
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
@@ -371,6 +385,24 @@ impl Module {
         voyager_client: &VoyagerClient,
         height: Height,
     ) -> RpcResult<Op<VoyagerMessage>> {
+        if let Some(subscription) = &self.subscription {
+            match subscription.take(height.height(), self.chunk_block_fetch_size) {
+                Taken::Blocks(blocks) => return Ok(self.subscribed_blocks(height, blocks)),
+                Taken::Wait => {
+                    return Ok(seq([
+                        defer(now() + 1),
+                        call(PluginMessage::new(
+                            self.plugin_name(),
+                            ModuleCall::from(FetchBlocks { height }),
+                        )),
+                    ]))
+                }
+                Taken::Gap => {
+                    info!(%height, "block was not received over the websocket, polling");
+                }
+            }
+        }
+
         let latest_height = voyager_client
             .query_latest_height(self.chain_id.clone(), true)
             .await?;
@@ -523,39 +555,10 @@ impl Module {
             for tx_response in response.txs {
                 let _span = info_span!("tx_result.events", tx_hash = %tx_response.hash).entered();
                 for event in tx_response.tx_result.events {
-                    trace!(%event.ty, "observed event");
-
-                    let event = match CosmosSdkEvent::<IbcEvent>::new(event.clone()) {
-                        Ok(event) => event,
-                        Err(cosmos_sdk_event::Error::Deserialize(error)) => {
-                            trace!("unable to parse event: {error}");
-                            continue;
-                        }
-                        Err(err) => {
-                            error!("error parsing event: {}", ErrorReporter(err));
-                            continue;
-                        }
+                    let Some(event) = self.parse_event(event) else {
+                        continue;
                     };
 
-                    match (&event.contract_address, &self.ibc_host_contract_address) {
-                        (None, _) => {}
-                        (Some(addr), None) => {
-                            debug!(
-                                "found ibc-union event for contract {addr}, but no contract address is configured",
-                            );
-                            continue;
-                        }
-                        (Some(event_addr), Some(configured_addr)) => {
-                            if event_addr == configured_addr {
-                            } else {
-                                debug!(
-                                    "found ibc-union event for contract {event_addr}, but the configured contract address is {configured_addr}",
-                                );
-                                continue;
-                            }
-                        }
-                    }
-
                     let make_chain_event = || {
                         call(PluginMessage::new(
                             self.plugin_name(),
@@ -620,6 +623,92 @@ impl Module {
         )))
     }
 
+    /// Parses an [`IbcEvent`], filtering out ibc-union events that were not emitted by the configured host contract.
+    fn parse_event(
+        &self,
+        event: cometbft_rpc::types::abci::event::Event,
+    ) -> Option<CosmosSdkEvent<IbcEvent>> {
+        trace!(%event.ty, "observed event");
+
+        let event = match CosmosSdkEvent::<IbcEvent>::new(event) {
+            Ok(event) => event,
+            Err(cosmos_sdk_event::Error::Deserialize(error)) => {
+                trace!("unable to parse event: {error}");
+                return None;
+            }
+            Err(err) => {
+                error!("error parsing event: {}", ErrorReporter(err));
+                return None;
+            }
+        };
+
+        match (&event.contract_address, &self.ibc_host_contract_address) {
+            (None, _) => {}
+            (Some(addr), None) => {
+                debug!(
+                    "found ibc-union event for contract {addr}, but no contract address is configured",
+                );
+                return None;
+            }
+            (Some(event_addr), Some(configured_addr)) => {
+                if event_addr == configured_addr {
+                } else {
+                    debug!(
+                        "found ibc-union event for contract {event_addr}, but the configured contract address is {configured_addr}",
+                    );
+                    return None;
+                }
+            }
+        }
+
+        Some(event)
+    }
+
+    /// Queues the events of the blocks taken from the [`Subscription`], and continues fetching from the block after
+    /// them.
+    fn subscribed_blocks(
+        &self,
+        height: Height,
+        blocks: Vec<(u64, Vec<subscription::Tx>)>,
+    ) -> Op<VoyagerMessage> {
+        let next_height = blocks
+            .last()
+            .map_or(height.height(), |(last_height, _)| last_height + 1);
+
+        info!(
+            from_height = height.height(),
+            to_height = next_height,
+            "received blocks in range {height}..{next_height} over the websocket"
+        );
+
+        let make_chain_event_ops = blocks.into_iter().flat_map(|(block_height, txs)| {
+            txs.into_iter().flat_map(move |tx| {
+                let _span = info_span!("tx_result.events", tx_hash = %tx.hash).entered();
+                tx.events
+                    .into_iter()
+                    .filter_map(|event| self.parse_event(event))
+                    .map(|event| {
+                        call(PluginMessage::new(
+                            self.plugin_name(),
+                            ModuleCall::from(MakeChainEvent {
+                                height: Height::new_with_revision(height.revision(), block_height),
+                                tx_hash: tx.hash,
+                                event: event.event,
+                            }),
+                        ))
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+
+        conc(make_chain_event_ops.chain([call(PluginMessage::new(
+            self.plugin_name(),
+            ModuleCall::from(FetchBlocks {
+                height: Height::new_with_revision(height.revision(), next_height),
+            }),
+        ))]))
+    }
+
     #[instrument(level = "info", skip_all, fields(%height, %tx_hash))]
     async fn make_chain_event(
         &self,
@@ -0,0 +1,379 @@
+//! Websocket subscription to the `NewBlock` and `Tx` events of a CometBFT node.
+//!
+//! The events are buffered per block until [`FetchBlocks`](crate::call::FetchBlocks) takes them. A block is complete
+//! once its `NewBlock` event and a `Tx` event for each of the transactions in it have been received. The events of the
+//! two subscriptions are not ordered relative to each other, so the blocks are keyed by height and completed in any
+//! order.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use cometbft_rpc::types::abci::event::Event;
+use jsonrpsee::{
+    client_transport::ws::{Url, WsTransportClientBuilder},
+    core::client::{ReceivedMessage, TransportReceiverT, TransportSenderT},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Digest;
+use tracing::{debug, info, trace, warn};
+use unionlabs::{
+    primitives::{encoding::Base64, Bytes, H256},
+    ErrorReporter,
+};
+use voyager_vm::BoxDynError;
+
+/// The maximum number of blocks kept in the buffer. If the buffer is full, the oldest blocks are dropped and will be
+/// polled instead.
+const MAX_BUFFERED_BLOCKS: usize = 1000;
+
+/// An incomplete block is polled instead once this many newer blocks have been announced, in case one of its `Tx`
+/// events was lost.
+const MAX_INCOMPLETE_BLOCKS: u64 = 5;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Default)]
+pub struct Subscription {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The first height of the current uninterrupted run of `NewBlock` events. Every block from this height on is, or
+    /// will be, in `blocks`. This is reset on reconnect and when a `NewBlock` event is skipped.
+    covered_from: Option<u64>,
+    /// The height of the latest `NewBlock` event.
+    latest_height: Option<u64>,
+    blocks: BTreeMap<u64, Block>,
+}
+
+#[derive(Debug, Default)]
+struct Block {
+    /// The number of transactions in the block, set once the `NewBlock` event is received.
+    tx_count: Option<usize>,
+    /// The transactions of the block, keyed by their index in the block.
+    txs: BTreeMap<u32, Tx>,
+}
+
+impl Block {
+    fn is_complete(&self) -> bool {
+        self.tx_count == Some(self.txs.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tx {
+    pub hash: H256,
+    pub events: Vec<Event>,
+}
+
+/// The result of [`Subscription::take`].
+#[derive(Debug)]
+pub enum Taken {
+    /// The complete blocks starting at the requested height, in order.
+    Blocks(Vec<(u64, Vec<Tx>)>),
+    /// The requested block has not been received yet.
+    Wait,
+    /// The requested block was not received over the websocket, and must be polled.
+    Gap,
+}
+
+impl Subscription {
+    /// Spawns the task receiving the events from `url`, reconnecting on failure.
+    pub fn spawn(url: String) -> Arc<Self> {
+        let subscription = Arc::new(Self::default());
+
+        tokio::spawn({
+            let subscription = subscription.clone();
+            async move {
+                loop {
+                    if let Err(err) = subscription.receive(&url).await {
+                        warn!(%url, err = %ErrorReporter(&*err), "websocket subscription failed, reconnecting");
+                    }
+
+                    subscription.disconnected();
+
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        });
+
+        subscription
+    }
+
+    /// Takes up to `limit` complete blocks starting at `height`. Blocks before `height` are dropped, as they won't be
+    /// requested again.
+    pub fn take(&self, height: u64, limit: u64) -> Taken {
+        let mut state = self.state.lock().expect("lock is not poisoned");
+
+        state.blocks = state.blocks.split_off(&height);
+
+        let mut blocks = vec![];
+        while (blocks.len() as u64) < limit {
+            let next_height = height + blocks.len() as u64;
+
+            match state.blocks.get(&next_height) {
+                Some(block) if block.is_complete() => {
+                    let block = state
+                        .blocks
+                        .remove(&next_height)
+                        .expect("block exists; qed;");
+                    blocks.push((next_height, block.txs.into_values().collect()));
+                }
+                _ => break,
+            }
+        }
+
+        if !blocks.is_empty() {
+            return Taken::Blocks(blocks);
+        }
+
+        let covered = state.covered_from.is_some_and(|from| from <= height);
+        let incomplete_for_too_long = state
+            .latest_height
+            .is_some_and(|latest| latest >= height + MAX_INCOMPLETE_BLOCKS);
+
+        if covered && !incomplete_for_too_long {
+            Taken::Wait
+        } else {
+            Taken::Gap
+        }
+    }
+
+    async fn receive(&self, url: &str) -> Result<(), BoxDynError> {
+        let (mut sender, mut receiver) = WsTransportClientBuilder {
+            max_response_size: 100 * 1024 * 1024,
+            ..Default::default()
+        }
+        .build(Url::parse(url)?)
+        .await?;
+
+        for (id, query) in [(0, "tm.event='NewBlock'"), (1, "tm.event='Tx'")] {
+            sender
+                .send(
+                    json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "method": "subscribe",
+                        "params": { "query": query },
+                    })
+                    .to_string(),
+                )
+                .await?;
+        }
+
+        info!(%url, "subscribed to events");
+
+        loop {
+            let message = match receiver.receive().await? {
+                ReceivedMessage::Text(text) => text,
+                ReceivedMessage::Bytes(bytes) => String::from_utf8(bytes)?,
+                ReceivedMessage::Pong => continue,
+            };
+
+            let response = serde_json::from_str::<Response>(&message)?;
+
+            if let Some(error) = response.error {
+                return Err(format!("subscription error: {error}").into());
+            }
+
+            match response.result.and_then(|result| result.data) {
+                Some(EventData::NewBlock { block }) => self.new_block(
+                    block.header.height,
+                    block.data.txs.map_or(0, |txs| txs.len()),
+                ),
+                Some(EventData::Tx { tx_result }) => self.tx(tx_result),
+                // the response to the subscribe requests
+                None => {}
+            }
+        }
+    }
+
+    fn new_block(&self, height: u64, tx_count: usize) {
+        let mut state = self.state.lock().expect("lock is not poisoned");
+
+        debug!(%height, %tx_count, "received new block");
+
+        match state.latest_height {
+            Some(latest) if latest + 1 == height => {}
+            latest => {
+                if state.covered_from.is_some() {
+                    warn!(?latest, %height, "missed new block events, the missing blocks will be polled");
+                }
+
+                state.covered_from = Some(height);
+                // blocks before this one may be missing transactions received before a reconnect
+                state
+                    .blocks
+                    .retain(|h, block| *h >= height || block.is_complete());
+            }
+        }
+
+        state.latest_height = Some(height);
+        state.blocks.entry(height).or_default().tx_count = Some(tx_count);
+
+        while state.blocks.len() > MAX_BUFFERED_BLOCKS {
+            let (dropped, _) = state.blocks.pop_first().expect("blocks is not empty; qed;");
+            state.covered_from = state.covered_from.map(|from| from.max(dropped + 1));
+        }
+    }
+
+    fn tx(&self, tx_result: TxResult) {
+        let mut state = self.state.lock().expect("lock is not poisoned");
+
+        let hash = H256::new(sha2::Sha256::digest(&*tx_result.tx).into());
+
+        trace!(height = tx_result.height, index = tx_result.index, %hash, "received tx");
+
+        state
+            .blocks
+            .entry(tx_result.height)
+            .or_default()
+            .txs
+            .insert(
+                tx_result.index,
+                Tx {
+                    hash,
+                    events: tx_result.result.events,
+                },
+            );
+    }
+
+    fn disconnected(&self) {
+        let mut state = self.state.lock().expect("lock is not poisoned");
+
+        state.covered_from = None;
+        state.latest_height = None;
+        state.blocks.retain(|_, block| block.is_complete());
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    result: Option<SubscriptionResult>,
+    #[serde(default)]
+    error: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionResult {
+    #[serde(default)]
+    data: Option<EventData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "value")]
+enum EventData {
+    #[serde(rename = "tendermint/event/NewBlock")]
+    NewBlock { block: NewBlock },
+    #[serde(rename = "tendermint/event/Tx")]
+    Tx {
+        #[serde(rename = "TxResult")]
+        tx_result: TxResult,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct NewBlock {
+    header: NewBlockHeader,
+    data: NewBlockData,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewBlockHeader {
+    #[serde(with = "::serde_utils::string")]
+    height: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct NewBlockData {
+    /// Only the number of transactions is needed, the events are taken from the `Tx` events.
+    #[serde(default)]
+    txs: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TxResult {
+    #[serde(with = "::serde_utils::string")]
+    height: u64,
+    #[serde(default)]
+    index: u32,
+    tx: Bytes<Base64>,
+    result: ExecResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecResult {
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(height: u64, index: u32) -> TxResult {
+        TxResult {
+            height,
+            index,
+            tx: vec![index as u8].into(),
+            result: ExecResult { events: vec![] },
+        }
+    }
+
+    #[test]
+    fn blocks_complete_in_any_order() {
+        let subscription = Subscription::default();
+
+        subscription.tx(tx(10, 1));
+        subscription.new_block(10, 2);
+
+        assert!(matches!(subscription.take(10, 10), Taken::Wait));
+
+        subscription.tx(tx(10, 0));
+        subscription.new_block(11, 0);
+
+        let Taken::Blocks(blocks) = subscription.take(10, 10) else {
+            panic!("blocks should be complete");
+        };
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|(h, txs)| (*h, txs.len()))
+                .collect::<Vec<_>>(),
+            [(10, 2), (11, 0)]
+        );
+
+        assert!(matches!(subscription.take(12, 10), Taken::Wait));
+    }
+
+    #[test]
+    fn gaps_are_polled() {
+        let subscription = Subscription::default();
+
+        // not connected yet
+        assert!(matches!(subscription.take(10, 10), Taken::Gap));
+
+        subscription.new_block(10, 0);
+        assert!(matches!(subscription.take(9, 10), Taken::Gap));
+
+        subscription.disconnected();
+        subscription.new_block(13, 1);
+
+        let Taken::Blocks(blocks) = subscription.take(10, 10) else {
+            panic!("block 10 should still be buffered");
+        };
+        assert_eq!(blocks.len(), 1);
+
+        assert!(matches!(subscription.take(11, 10), Taken::Gap));
+        assert!(matches!(subscription.take(13, 10), Taken::Wait));
+
+        subscription.new_block(13 + MAX_INCOMPLETE_BLOCKS, 0);
+        assert!(matches!(subscription.take(13, 10), Taken::Gap));
+    }
+}
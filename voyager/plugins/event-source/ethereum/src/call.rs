@@ -1,11 +1,12 @@
 use enumorph::Enumorph;
 use ibc_solidity::Ibc;
 use macros::model;
+use schemars::JsonSchema;
 use subset_of::SubsetOf;
 use unionlabs::primitives::H256;
 
 #[model]
-#[derive(Enumorph, SubsetOf)]
+#[derive(Enumorph, SubsetOf, JsonSchema)]
 pub enum ModuleCall {
     FetchBlocks(FetchBlocks),
     FetchGetLogs(FetchGetLogs),
@@ -14,6 +15,7 @@ pub enum ModuleCall {
 
 /// Fetch a block at the specified height, requeuing a seq(wait(H+1), fetch(H+1)).
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchBlocks {
     pub block_number: u64,
 }
@@ -22,17 +24,21 @@ pub struct FetchBlocks {
 ///
 /// [`eth_getLogs`]: https://ethereum.org/en/developers/docs/apis/json-rpc/#[model]th_getlogs
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchGetLogs {
     pub block_number: u64,
 }
 
 /// Construct a full ChainEvent from the given EVM event and associated metadata.
 #[model]
+#[derive(JsonSchema)]
 pub struct MakeFullEvent {
     /// The *execution* block number that this event was emitted at.
     pub block_number: u64,
     /// Tx hash of the transaction that emitted this event.
     pub tx_hash: H256,
+    /// The raw event as emitted by the `IBCHandler`, this is not described by the schema.
+    #[schemars(with = "serde_json::Value")]
     pub event: IbcEvents,
 }
 
@@ -1,7 +1,8 @@
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 use subset_of::SubsetOf;
 
 #[model]
-#[derive(Enumorph, SubsetOf)]
+#[derive(Enumorph, SubsetOf, JsonSchema)]
 pub enum ModuleCallback {}
@@ -21,7 +21,7 @@ use ibc_union_spec::{
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::ErrorObject,
-    Extensions,
+    Extensions, Methods,
 };
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, info_span, instrument, trace, warn};
@@ -36,7 +36,8 @@ use voyager_message::{
     data::{ChainEvent, Data},
     into_value,
     module::{PluginInfo, PluginServer},
-    DefaultCmd, ExtensionsExt, Plugin, PluginMessage, VoyagerClient, VoyagerMessage,
+    plugin_schema_methods, DefaultCmd, ExtensionsExt, Plugin, PluginMessage, VoyagerClient,
+    VoyagerMessage,
 };
 use voyager_vm::{call, conc, data, noop, pass::PassResult, seq, BoxDynError, Op};
 
@@ -112,6 +113,10 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, ModuleCallback>()
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
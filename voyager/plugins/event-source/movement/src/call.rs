@@ -1,9 +1,10 @@
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 use unionlabs::primitives::H256;
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 #[allow(clippy::large_enum_variant)]
 pub enum ModuleCall {
     FetchTransactions(FetchTransactions),
@@ -12,17 +13,22 @@ pub enum ModuleCall {
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchBlocks {
     pub height: u64,
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct FetchTransactions {
     pub height: u64,
 }
 
 #[model]
+#[derive(JsonSchema)]
 pub struct MakeFullEvent {
+    /// The raw event as emitted by the ibc module, this is not described by the schema.
+    #[schemars(with = "serde_json::Value")]
     pub event: crate::events::IbcEvent,
     pub tx_hash: H256,
     pub height: u64,
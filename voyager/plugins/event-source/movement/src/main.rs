@@ -23,7 +23,7 @@ use ibc_union_spec::{
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::{ErrorObject, ErrorObjectOwned},
-    Extensions,
+    Extensions, Methods,
 };
 use move_bindgen::MoveOutputType;
 use serde::{Deserialize, Serialize};
@@ -36,7 +36,8 @@ use voyager_message::{
     data::{ChainEvent, Data},
     into_value,
     module::{PluginInfo, PluginServer},
-    DefaultCmd, ExtensionsExt, Plugin, PluginMessage, VoyagerClient, VoyagerMessage,
+    plugin_schema_methods, DefaultCmd, ExtensionsExt, Plugin, PluginMessage, VoyagerClient,
+    VoyagerMessage,
 };
 use voyager_vm::{call, conc, data, pass::PassResult, seq, BoxDynError, Op};
 
@@ -107,6 +108,10 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, ModuleCallback>()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
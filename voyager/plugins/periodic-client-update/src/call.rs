@@ -1,4 +1,5 @@
 use macros::model;
+use schemars::JsonSchema;
 use voyager_message::{
     core::{ChainId, IbcSpecId},
     RawClientId,
@@ -6,12 +7,13 @@ use voyager_message::{
 use voyager_vm::BoxDynError;
 
 #[model]
+#[derive(JsonSchema)]
 pub enum ModuleCall {
     CheckForClientAge(CheckForClientAge),
 }
 
 #[model]
-#[derive(clap::Args)]
+#[derive(clap::Args, JsonSchema)]
 pub struct CheckForClientAge {
     #[arg(value_parser(|s: &str| Ok::<_, BoxDynError>(ChainId::new(s.to_owned()))))]
     pub chain_id: ChainId,
@@ -21,3 +23,34 @@ pub struct CheckForClientAge {
     /// The maximum amount of blocks this client can lag behind the latest finalized height of the chain it's tracking.
     pub max_age: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use voyager_message::into_value;
+
+    use super::*;
+
+    /// Validates the message as voyager does when it is enqueued, against the advertised schema.
+    fn assert_valid(call: ModuleCall) {
+        let schema = into_value(schemars::schema_for!(ModuleCall));
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let message = into_value(call);
+
+        let errors = validator
+            .iter_errors(&message)
+            .map(|err| format!("{err} (at {})", err.instance_path))
+            .collect::<Vec<_>>();
+
+        assert!(errors.is_empty(), "{errors:?}\n{message:#}");
+    }
+
+    #[test]
+    fn check_for_client_age_matches_schema() {
+        assert_valid(ModuleCall::CheckForClientAge(CheckForClientAge {
+            chain_id: ChainId::new("union-devnet-1"),
+            ibc_spec_id: IbcSpecId::new("ibc-union"),
+            client_id: RawClientId::new(1),
+            max_age: 100,
+        }));
+    }
+}
@@ -1,20 +1,25 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    Extensions,
+    Extensions, Methods,
 };
+use opentelemetry::{metrics::Gauge, KeyValue};
 use serde::{Deserialize, Serialize};
-use tracing::{error, info, instrument};
+use tracing::{error, info, instrument, warn};
 use unionlabs::{ibc::core::client::height::Height, never::Never};
 use voyager_message::{
     call::{FetchUpdateHeaders, WaitForTrustedHeight},
     callback::AggregateSubmitTxFromOrderedHeaders,
-    core::{ChainId, IbcSpecId, QueryHeight},
+    core::{ChainId, ClientStateMeta, IbcSpecId, QueryHeight},
     data::Data,
     into_value,
     module::{PluginInfo, PluginServer},
-    ExtensionsExt, Plugin, PluginMessage, RawClientId, VoyagerClient, VoyagerMessage,
+    plugin_schema_methods, ExtensionsExt, Plugin, PluginMessage, RawClientId, VoyagerClient,
+    VoyagerMessage,
 };
 use voyager_vm::{call, conc, defer, now, pass::PassResult, promise, seq, BoxDynError, Op};
 
@@ -27,11 +32,71 @@ async fn main() {
     Module::run().await
 }
 
-pub struct Module {}
+pub struct Module {
+    pub config: Config,
+
+    /// Seconds left until the client expires, negative if it has expired.
+    pub trusting_period_remaining: Gauge<i64>,
+    /// 1 if less than `expiry_warning_fraction` of the trusting period of the client is left.
+    pub nearing_expiry: Gauge<u64>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
-pub struct Config {}
+pub struct Config {
+    /// The refresh config of clients that are not configured in `clients`.
+    #[serde(default)]
+    pub default: RefreshConfig,
+    #[serde(default)]
+    pub clients: Vec<ClientConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientConfig {
+    pub chain_id: ChainId,
+    pub ibc_spec_id: IbcSpecId,
+    pub client_id: RawClientId,
+    pub refresh: RefreshConfig,
+}
+
+/// Clients with a trusting period (see [`voyager_message::core::ClientStateMeta::trusting_period`]) are refreshed
+/// based on the time since their latest consensus state, in addition to the `max_age` of the [`CheckForClientAge`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RefreshConfig {
+    /// Update the client once this fraction of its trusting period has passed since its latest consensus state.
+    pub trusting_period_fraction: f64,
+    /// Report the client as nearing expiry once less than this fraction of its trusting period is left.
+    pub expiry_warning_fraction: f64,
+    /// The interval between checks of the client, in seconds.
+    pub check_interval: u64,
+}
+
+impl Default for RefreshConfig {
+    fn default() -> Self {
+        Self {
+            trusting_period_fraction: 0.5,
+            expiry_warning_fraction: 0.2,
+            check_interval: 60,
+        }
+    }
+}
+
+impl RefreshConfig {
+    fn validate(&self) -> Result<(), BoxDynError> {
+        for (name, fraction) in [
+            ("trusting_period_fraction", self.trusting_period_fraction),
+            ("expiry_warning_fraction", self.expiry_warning_fraction),
+        ] {
+            if !(fraction > 0.0 && fraction <= 1.0) {
+                return Err(format!("{name} must be in (0, 1], found {fraction}").into());
+            }
+        }
+
+        Ok(())
+    }
+}
 
 impl Plugin for Module {
     type Call = ModuleCall;
@@ -41,6 +106,11 @@ impl Plugin for Module {
     type Cmd = Cmd;
 
     async fn new(config: Self::Config) -> Result<Self, BoxDynError> {
+        config.default.validate()?;
+        for client in &config.clients {
+            client.refresh.validate()?;
+        }
+
         Ok(Module::new(config))
     }
 
@@ -78,6 +148,10 @@ impl Plugin for Module {
             }
         }
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, Never>()
+    }
 }
 
 #[derive(clap::Parser)]
@@ -92,8 +166,99 @@ impl Module {
         PLUGIN_NAME.to_owned()
     }
 
-    pub fn new(_: Config) -> Self {
-        Self {}
+    pub fn new(config: Config) -> Self {
+        let meter = opentelemetry::global::meter("voyager.periodic_client_update");
+
+        Self {
+            config,
+            trusting_period_remaining: meter
+                .i64_gauge("client.trusting_period_remaining")
+                .with_unit("s")
+                .with_description("Seconds left until the client expires")
+                .build(),
+            nearing_expiry: meter
+                .u64_gauge("client.nearing_expiry")
+                .with_description("Whether the client is close to expiring")
+                .build(),
+        }
+    }
+
+    fn refresh_config(
+        &self,
+        chain_id: &ChainId,
+        ibc_spec_id: &IbcSpecId,
+        client_id: &RawClientId,
+    ) -> &RefreshConfig {
+        self.config
+            .clients
+            .iter()
+            .find(|client| {
+                &client.chain_id == chain_id
+                    && &client.ibc_spec_id == ibc_spec_id
+                    && &client.client_id == client_id
+            })
+            .map_or(&self.config.default, |client| &client.refresh)
+    }
+
+    /// Whether the trusting period based refresh of the client is due, recording the time left until it expires.
+    async fn trusting_period_refresh_due(
+        &self,
+        voyager_client: &VoyagerClient,
+        chain_id: &ChainId,
+        ibc_spec_id: &IbcSpecId,
+        client_id: &RawClientId,
+        client_state_meta: &ClientStateMeta,
+        refresh: &RefreshConfig,
+    ) -> RpcResult<bool> {
+        let Some(trusting_period) = client_state_meta.trusting_period else {
+            return Ok(false);
+        };
+
+        let consensus_state_meta = voyager_client
+            .consensus_state_meta_raw(
+                chain_id.clone(),
+                ibc_spec_id.clone(),
+                QueryHeight::Latest,
+                client_id.clone(),
+                client_state_meta.counterparty_height,
+            )
+            .await?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+
+        let TrustingPeriodStatus {
+            remaining,
+            nearing_expiry,
+            refresh_due,
+        } = trusting_period_status(
+            now,
+            consensus_state_meta.timestamp.as_nanos().into(),
+            trusting_period,
+            refresh,
+        );
+
+        let attributes = [
+            KeyValue::new("chain_id", chain_id.to_string()),
+            KeyValue::new("ibc_spec_id", ibc_spec_id.to_string()),
+            KeyValue::new("client_id", client_id.as_raw().to_string()),
+        ];
+        self.trusting_period_remaining
+            .record((remaining / 1_000_000_000) as i64, &attributes);
+        self.nearing_expiry
+            .record(nearing_expiry.into(), &attributes);
+
+        if nearing_expiry {
+            warn!(
+                remaining_seconds = remaining / 1_000_000_000,
+                trusting_period_seconds = trusting_period / 1_000_000_000,
+                "client is nearing expiry"
+            );
+        }
+
+        Ok(refresh_due)
     }
 
     #[instrument(
@@ -130,10 +295,29 @@ impl Module {
             .query_latest_height(client_state_meta.counterparty_chain_id.clone(), true)
             .await?;
 
-        if client_state_meta.counterparty_height.height() + max_age
-            < latest_finalized_height.height()
-        {
-            info!("client is older than threshold");
+        let refresh = self.refresh_config(&chain_id, &ibc_spec_id, &client_id);
+
+        let older_than_max_age = client_state_meta.counterparty_height.height() + max_age
+            < latest_finalized_height.height();
+
+        let trusting_period_refresh_due = self
+            .trusting_period_refresh_due(
+                voyager_client,
+                &chain_id,
+                &ibc_spec_id,
+                &client_id,
+                &client_state_meta,
+                refresh,
+            )
+            .await?
+            // the client can't be updated if the counterparty has not progressed
+            && client_state_meta.counterparty_height.height() < latest_finalized_height.height();
+
+        if older_than_max_age || trusting_period_refresh_due {
+            info!(
+                older_than_max_age,
+                trusting_period_refresh_due, "client is older than threshold"
+            );
 
             Ok(conc([
                 promise(
@@ -159,7 +343,8 @@ impl Module {
                         client_id: client_id.clone(),
                         height: Height::new_with_revision(
                             client_state_meta.counterparty_height.revision(),
-                            client_state_meta.counterparty_height.height() + max_age,
+                            (client_state_meta.counterparty_height.height() + max_age)
+                                .min(latest_finalized_height.height()),
                         ),
                         finalized: false,
                     }),
@@ -176,7 +361,7 @@ impl Module {
             ]))
         } else {
             Ok(seq([
-                defer(now() + 60),
+                defer(now() + refresh.check_interval),
                 call(PluginMessage::new(
                     self.plugin_name(),
                     ModuleCall::CheckForClientAge(CheckForClientAge {
@@ -226,3 +411,126 @@ impl PluginServer<ModuleCall, Never> for Module {
         match cb {}
     }
 }
+
+/// The state of a client relative to its trusting period, as computed by [`trusting_period_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TrustingPeriodStatus {
+    /// Nanoseconds left until the client expires, negative if it has expired.
+    remaining: i128,
+    /// Whether less than `expiry_warning_fraction` of the trusting period is left.
+    nearing_expiry: bool,
+    /// Whether at least `trusting_period_fraction` of the trusting period has passed.
+    refresh_due: bool,
+}
+
+/// Computes the [`TrustingPeriodStatus`] of a client whose latest consensus state is at `consensus_timestamp`. All times
+/// are in nanoseconds.
+fn trusting_period_status(
+    now: u128,
+    consensus_timestamp: u128,
+    trusting_period: u64,
+    refresh: &RefreshConfig,
+) -> TrustingPeriodStatus {
+    let elapsed = now.saturating_sub(consensus_timestamp);
+    let remaining = (consensus_timestamp + u128::from(trusting_period)) as i128 - now as i128;
+
+    TrustingPeriodStatus {
+        remaining,
+        nearing_expiry: remaining
+            < (trusting_period as f64 * refresh.expiry_warning_fraction) as i128,
+        refresh_due: elapsed >= (trusting_period as f64 * refresh.trusting_period_fraction) as u128,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRUSTING_PERIOD: u64 = 1_000;
+
+    fn refresh(trusting_period_fraction: f64) -> RefreshConfig {
+        RefreshConfig {
+            trusting_period_fraction,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fraction_half() {
+        let refresh = refresh(0.5);
+
+        assert_eq!(
+            trusting_period_status(1_499, 1_000, TRUSTING_PERIOD, &refresh),
+            TrustingPeriodStatus {
+                remaining: 501,
+                nearing_expiry: false,
+                refresh_due: false,
+            }
+        );
+        assert_eq!(
+            trusting_period_status(1_500, 1_000, TRUSTING_PERIOD, &refresh),
+            TrustingPeriodStatus {
+                remaining: 500,
+                nearing_expiry: false,
+                refresh_due: true,
+            }
+        );
+    }
+
+    #[test]
+    fn fraction_one() {
+        let refresh = refresh(1.0);
+
+        // only due once the trusting period has fully passed, at which point the client has expired
+        assert_eq!(
+            trusting_period_status(1_999, 1_000, TRUSTING_PERIOD, &refresh),
+            TrustingPeriodStatus {
+                remaining: 1,
+                nearing_expiry: true,
+                refresh_due: false,
+            }
+        );
+        assert_eq!(
+            trusting_period_status(2_000, 1_000, TRUSTING_PERIOD, &refresh),
+            TrustingPeriodStatus {
+                remaining: 0,
+                nearing_expiry: true,
+                refresh_due: true,
+            }
+        );
+    }
+
+    #[test]
+    fn expired() {
+        assert_eq!(
+            trusting_period_status(2_500, 1_000, TRUSTING_PERIOD, &refresh(0.5)),
+            TrustingPeriodStatus {
+                remaining: -500,
+                nearing_expiry: true,
+                refresh_due: true,
+            }
+        );
+    }
+
+    #[test]
+    fn nearing_expiry() {
+        // the default expiry warning fraction is 0.2
+        let refresh = refresh(0.9);
+
+        assert!(!trusting_period_status(1_800, 1_000, TRUSTING_PERIOD, &refresh).nearing_expiry);
+        assert!(trusting_period_status(1_801, 1_000, TRUSTING_PERIOD, &refresh).nearing_expiry);
+    }
+
+    #[test]
+    fn consensus_state_in_the_future() {
+        // clock drift between the chains must not underflow
+        assert_eq!(
+            trusting_period_status(900, 1_000, TRUSTING_PERIOD, &refresh(0.5)),
+            TrustingPeriodStatus {
+                remaining: 1_100,
+                nearing_expiry: false,
+                refresh_due: false,
+            }
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use enumorph::Enumorph;
 use macros::model;
+use schemars::JsonSchema;
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 pub enum ModuleCall {
     SubmitTransaction(Vec<ibc_union_spec::datagram::Datagram>),
 }
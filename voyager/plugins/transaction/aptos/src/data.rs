@@ -1,6 +1,24 @@
 use enumorph::Enumorph;
+use ibc_union_spec::datagram::Datagram;
 use macros::model;
+use unionlabs::primitives::H256;
 
 #[model]
 #[derive(Enumorph)]
-pub enum ModuleData {}
+pub enum ModuleData {
+    DryRunTransaction(DryRunTransaction),
+}
+
+/// A transaction that was simulated instead of submitted, as the plugin is running in dry run mode. Each message is
+/// submitted in its own transaction, and each of them is simulated against the current state of the chain.
+#[model]
+pub struct DryRunTransaction {
+    pub sender: H256,
+    pub datagram: Datagram,
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: u64,
+    pub gas_unit_price: u64,
+    /// The fee that would have been paid for the transaction, in octas.
+    pub fee: u64,
+}
@@ -1,16 +1,22 @@
 use std::{collections::VecDeque, panic::AssertUnwindSafe, sync::Arc};
 
-use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519Signature},
+    PrivateKey,
+};
 use aptos_rest_client::aptos_api_types::Address;
 use aptos_types::{
     account_address::AccountAddress,
-    transaction::{EntryFunction, RawTransaction},
+    transaction::{EntryFunction, RawTransaction, SignedTransaction},
+};
+use concurrent_keyring::{
+    ChainKeyring, ConcurrentKeyring, KeyringConfig, KeyringEntry, SignerBalance,
 };
-use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
 use ibc_union_spec::{datagram::Datagram, IbcUnion};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    Extensions,
+    types::ErrorObject,
+    Extensions, Methods,
 };
 use move_core_types::{
     identifier::Identifier,
@@ -18,24 +24,32 @@ use move_core_types::{
 };
 use serde::{Deserialize, Serialize};
 use sha3::Digest;
-use tracing::instrument;
-use unionlabs::primitives::H256;
+use tracing::{info, instrument, warn};
+use unionlabs::{primitives::H256, ErrorReporter};
 use voyager_message::{
     core::ChainId,
     data::Data,
     hook::SubmitTxHook,
-    module::{PluginInfo, PluginServer},
-    vm::{call, noop, pass::PassResult, Op, Visit},
+    module::{KeyringServer, PluginInfo, PluginServer},
+    plugin_schema_methods,
+    vm::{call, conc, data, noop, pass::PassResult, Op, Visit},
     DefaultCmd, Plugin, PluginMessage, VoyagerMessage,
 };
 use voyager_vm::BoxDynError;
 
-use crate::{call::ModuleCall, callback::ModuleCallback};
+use crate::{
+    call::ModuleCall,
+    callback::ModuleCallback,
+    data::{DryRunTransaction, ModuleData},
+};
 
 pub mod call;
 pub mod callback;
 pub mod data;
 
+const MAX_GAS_AMOUNT: u64 = 400000;
+const GAS_UNIT_PRICE: u64 = 100;
+
 #[tokio::main(flavor = "multi_thread")]
 async fn main() {
     Module::run().await
@@ -50,6 +64,8 @@ pub struct Module {
     pub aptos_client: aptos_rest_client::Client,
 
     pub keyring: ConcurrentKeyring<AccountAddress, Arc<Ed25519PrivateKey>>,
+
+    pub dry_run: bool,
 }
 
 impl Plugin for Module {
@@ -89,7 +105,9 @@ impl Plugin for Module {
                         signer: Arc::new(pk),
                     }
                 }),
-            ),
+            )
+            .with_min_balance(config.keyring.min_balance),
+            dry_run: config.dry_run,
         })
     }
 
@@ -103,6 +121,14 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        let mut methods = Methods::from(KeyringServer::into_rpc(self.clone()));
+        methods
+            .merge(plugin_schema_methods::<ModuleCall, ModuleCallback>())
+            .expect("the schema methods do not conflict with the keyring methods");
+        methods
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,6 +139,10 @@ pub struct Config {
     pub ibc_handler_address: Address,
 
     pub keyring: KeyringConfig,
+
+    /// Simulate the transactions instead of submitting them, returning them as [`DryRunTransaction`] data.
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 impl aptos_move_ibc::ibc::ClientExt for Module {
@@ -149,6 +179,109 @@ impl Module {
     fn plugin_name(&self) -> String {
         plugin_name(&self.chain_id)
     }
+
+    /// Simulates each of the messages in its own transaction. The transactions are all simulated with the current
+    /// sequence number of the account, as the earlier ones are not applied.
+    async fn simulate(
+        &self,
+        pk: &Ed25519PrivateKey,
+        sender: AccountAddress,
+        sequence_number: u64,
+        msgs: Vec<(Datagram, EntryFunction)>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let mut dry_runs = vec![];
+
+        for (datagram, entry_fn) in msgs {
+            let raw = RawTransaction::new_entry_function(
+                sender,
+                sequence_number,
+                entry_fn,
+                MAX_GAS_AMOUNT,
+                GAS_UNIT_PRICE,
+                voyager_vm::now() + 100,
+                self.chain_id.as_str().parse().unwrap(),
+            );
+
+            // the node rejects simulations of transactions with a valid signature
+            let signed_tx =
+                SignedTransaction::new(raw, pk.public_key(), Ed25519Signature::dummy_signature());
+
+            let simulated = self
+                .aptos_client
+                .simulate(&signed_tx)
+                .await
+                .map_err(|err| {
+                    ErrorObject::owned(
+                        -1,
+                        ErrorReporter(err).with_message("error simulating transaction"),
+                        None::<()>,
+                    )
+                })?
+                .into_inner()
+                .pop()
+                .ok_or_else(|| ErrorObject::owned(-1, "empty simulation response", None::<()>))?;
+
+            let gas_used = simulated.info.gas_used.0;
+            let gas_unit_price = simulated.request.gas_unit_price.0;
+
+            info!(
+                msg = datagram.name(),
+                success = simulated.info.success,
+                vm_status = %simulated.info.vm_status,
+                gas_used,
+                "simulated aptos transaction (dry run)"
+            );
+
+            dry_runs.push(DryRunTransaction {
+                sender: sender.into_bytes().into(),
+                datagram,
+                success: simulated.info.success,
+                vm_status: simulated.info.vm_status,
+                gas_used,
+                gas_unit_price,
+                fee: gas_used.saturating_mul(gas_unit_price),
+            });
+        }
+
+        Ok(conc(dry_runs.into_iter().map(|dry_run| {
+            data(PluginMessage::new(
+                self.plugin_name(),
+                ModuleData::from(dry_run),
+            ))
+        })))
+    }
+}
+
+impl ChainKeyring for Module {
+    type Address = AccountAddress;
+    type Signer = Arc<Ed25519PrivateKey>;
+
+    fn keyring(&self) -> &ConcurrentKeyring<Self::Address, Self::Signer> {
+        &self.keyring
+    }
+
+    async fn balances(&self) -> Vec<SignerBalance<Self::Address>> {
+        let mut balances = vec![];
+
+        for (key_name, address) in self.keyring.keys() {
+            match self.aptos_client.get_account_balance(*address).await {
+                Ok(balance) => balances.push(SignerBalance {
+                    key_name: key_name.to_owned(),
+                    address: *address,
+                    balance: balance.into_inner().get().into(),
+                    denom: "octa".to_owned(),
+                }),
+                Err(err) => warn!(
+                    %key_name,
+                    %address,
+                    "error querying signer balance: {}",
+                    ErrorReporter(err)
+                ),
+            }
+        }
+
+        balances
+    }
 }
 
 #[async_trait]
@@ -222,6 +355,12 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                         )
                         .await;
 
+                        if self.dry_run {
+                            return self
+                                .simulate(pk, sender, account.sequence_number, msgs)
+                                .await;
+                        }
+
                         let mut txs = vec![];
 
                         for (i, (_, entry_fn)) in msgs.into_iter().enumerate() {
@@ -229,8 +368,8 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                                 sender,
                                 account.sequence_number + (i as u64),
                                 entry_fn,
-                                400000,
-                                100,
+                                MAX_GAS_AMOUNT,
+                                GAS_UNIT_PRICE,
                                 voyager_vm::now() + 100,
                                 self.chain_id.as_str().parse().unwrap(),
                             );
@@ -3,17 +3,18 @@ use ibc_classic_spec::IbcClassic;
 use ibc_union_spec::IbcUnion;
 use jsonrpsee::{core::RpcResult, types::ErrorObject};
 use macros::model;
+use schemars::JsonSchema;
 use unionlabs::ErrorReporter;
 use voyager_message::{data::IbcDatagram, FATAL_JSONRPC_ERROR_CODE};
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 pub enum ModuleCall {
     SubmitTransaction(Vec<IbcMessage>),
 }
 
 #[model]
-#[derive(Enumorph)]
+#[derive(Enumorph, JsonSchema)]
 pub enum IbcMessage {
     IbcV1(ibc_classic_spec::Datagram),
     IbcUnion(ibc_union_spec::datagram::Datagram),
@@ -1,6 +1,22 @@
 use enumorph::Enumorph;
 use macros::model;
+use unionlabs::{bech32::Bech32, cosmos::tx::fee::Fee, primitives::H160};
+
+use crate::call::IbcMessage;
 
 #[model]
 #[derive(Enumorph)]
-pub enum ModuleData {}
+pub enum ModuleData {
+    DryRunTransaction(DryRunTransaction),
+}
+
+/// A transaction that was simulated instead of broadcast, as the plugin is running in dry run mode.
+#[model]
+pub struct DryRunTransaction {
+    pub signer: Bech32<H160>,
+    pub msgs: Vec<IbcMessage>,
+    pub gas_used: u64,
+    pub gas_wanted: u64,
+    /// The fee that would have been paid for the transaction.
+    pub fee: Fee,
+}
@@ -9,9 +9,11 @@ use std::{
 };
 
 use cometbft_rpc::rpc_types::GrpcAbciQueryError;
-use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
+use concurrent_keyring::{
+    ChainKeyring, ConcurrentKeyring, KeyringConfig, KeyringEntry, SignerBalance,
+};
 use cosmos_client::{
-    gas::GasConfig,
+    gas::{GasConfig, GasFillerT},
     rpc::{Rpc, RpcT},
     wallet::{LocalSigner, WalletT},
     BroadcastTxCommitError, FetchAccountInfoError, SimulateTxError, TxClient,
@@ -20,7 +22,7 @@ use ibc_union::ContractErrorKind;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::ErrorObject,
-    Extensions,
+    Extensions, Methods,
 };
 use prost::Message;
 use serde::{Deserialize, Serialize};
@@ -37,14 +39,16 @@ use voyager_message::{
     core::ChainId,
     data::Data,
     hook::SubmitTxHook,
-    module::{PluginInfo, PluginServer},
-    vm::{call, noop, pass::PassResult, seq, BoxDynError, Op, Visit},
+    module::{KeyringServer, PluginInfo, PluginServer},
+    plugin_schema_methods,
+    vm::{call, data, noop, pass::PassResult, seq, BoxDynError, Op, Visit},
     DefaultCmd, Plugin, PluginMessage, VoyagerMessage, FATAL_JSONRPC_ERROR_CODE,
 };
 
 use crate::{
     call::{IbcMessage, ModuleCall},
     callback::ModuleCallback,
+    data::{DryRunTransaction, ModuleData},
 };
 
 pub mod call;
@@ -56,7 +60,7 @@ async fn main() {
     Module::run().await
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Module {
     pub chain_id: ChainId,
     pub ibc_host_contract_address: Bech32<H256>,
@@ -65,6 +69,7 @@ pub struct Module {
     pub gas_config: GasConfig,
     pub bech32_prefix: String,
     pub fatal_errors: HashMap<(String, NonZeroU32), Option<String>>,
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +83,9 @@ pub struct Config {
     /// A list of (codespace, code) tuples that are to be considered non-recoverable.
     #[serde(default)]
     pub fatal_errors: HashMap<(String, NonZeroU32), Option<String>>,
+    /// Simulate the transactions instead of broadcasting them, returning them as [`DryRunTransaction`] data.
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 const FATAL_ERRORS: &[(&str, NonZeroU32)] = &[
@@ -135,7 +143,8 @@ impl Plugin for Module {
                         signer,
                     }
                 }),
-            ),
+            )
+            .with_min_balance(config.keyring.min_balance),
             rpc,
             chain_id: ChainId::new(chain_id),
             gas_config: config.gas_config,
@@ -149,6 +158,7 @@ impl Plugin for Module {
                         .map(|(codespace, code)| (((*codespace).to_owned(), *code), None)),
                 )
                 .collect(),
+            dry_run: config.dry_run,
         })
     }
 
@@ -162,6 +172,14 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        let mut methods = Methods::from(KeyringServer::into_rpc(self.clone()));
+        methods
+            .merge(plugin_schema_methods::<ModuleCall, ModuleCallback>())
+            .expect("the schema methods do not conflict with the keyring methods");
+        methods
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
@@ -175,10 +193,35 @@ impl Module {
         plugin_name(&self.chain_id)
     }
 
+    /// The balance of `address` in the gas denom.
+    async fn query_balance(&self, address: &Bech32<H160>) -> Result<u128, BoxDynError> {
+        let balance = self
+            .rpc
+            .client()
+            .grpc_abci_query::<_, protos::cosmos::bank::v1beta1::QueryBalanceResponse>(
+                "/cosmos.bank.v1beta1.Query/Balance",
+                &protos::cosmos::bank::v1beta1::QueryBalanceRequest {
+                    address: address.to_string(),
+                    denom: self.gas_config.gas_denom.clone(),
+                },
+                None,
+                false,
+            )
+            .await?
+            .into_result()?
+            .and_then(|response| response.balance);
+
+        Ok(match balance {
+            Some(coin) => coin.amount.parse()?,
+            None => 0,
+        })
+    }
+
+    /// Returns the simulated transaction if the plugin is running in dry run mode.
     pub async fn do_send_transaction(
         &self,
         msgs: Vec<IbcMessage>,
-    ) -> Option<Result<(), BroadcastTxCommitError>> {
+    ) -> Option<Result<Option<DryRunTransaction>, BroadcastTxCommitError>> {
         self.keyring
             .with(|signer| {
                 let msgs = msgs.clone();
@@ -210,7 +253,28 @@ impl Module {
                 AssertUnwindSafe(async move {
                     if msgs.is_empty() {
                         info!("no msgs left to submit after filtering out invalid msgs");
-                        return Ok(());
+                        return Ok(None);
+                    }
+
+                    if self.dry_run {
+                        let (_, _, gas_info) = tx_client
+                            .simulate_tx(msgs.iter().map(|x| x.1.clone()).collect::<Vec<_>>(), memo)
+                            .await?;
+
+                        info!(
+                            gas_used = %gas_info.gas_used,
+                            gas_wanted = %gas_info.gas_wanted,
+                            batch.size = %batch_size,
+                            "simulated cosmos transaction (dry run)"
+                        );
+
+                        return Ok(Some(DryRunTransaction {
+                            signer: signer.address(),
+                            msgs: msgs.into_iter().map(|x| x.0).collect(),
+                            gas_used: gas_info.gas_used,
+                            gas_wanted: gas_info.gas_wanted,
+                            fee: self.gas_config.mk_fee(gas_info.gas_used).await,
+                        }));
                     }
 
                     match tx_client
@@ -232,7 +296,7 @@ impl Module {
                                 info!(%tx_hash, %msg, "cosmos tx");
                             }
 
-                            Ok(())
+                            Ok(None)
                         }
                         Err(err) => Err(err),
                     }
@@ -242,6 +306,33 @@ impl Module {
     }
 }
 
+impl ChainKeyring for Module {
+    type Address = Bech32<H160>;
+    type Signer = LocalSigner;
+
+    fn keyring(&self) -> &ConcurrentKeyring<Self::Address, Self::Signer> {
+        &self.keyring
+    }
+
+    async fn balances(&self) -> Vec<SignerBalance<Self::Address>> {
+        let mut balances = vec![];
+
+        for (key_name, address) in self.keyring.keys() {
+            match self.query_balance(address).await {
+                Ok(balance) => balances.push(SignerBalance {
+                    key_name: key_name.to_owned(),
+                    address: address.clone(),
+                    balance,
+                    denom: self.gas_config.gas_denom.clone(),
+                }),
+                Err(err) => warn!(%key_name, %address, "error querying signer balance: {err}"),
+            }
+        }
+
+        balances
+    }
+}
+
 // {
 //     Ok((tx_hash, gas_used)) => {
 //         info!(
@@ -333,7 +424,13 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
 
                 match batch_submission_result {
                     None => return Err(ErrorObject::owned(-1, "no signers available", None::<()>)),
-                    Some(Ok(())) => return Ok(noop()),
+                    Some(Ok(None)) => return Ok(noop()),
+                    Some(Ok(Some(dry_run))) => {
+                        return Ok(data(PluginMessage::new(
+                            self.plugin_name(),
+                            ModuleData::from(dry_run),
+                        )))
+                    }
                     Some(Err(err)) => {
                         // dbg!(&err);
 
@@ -1,4 +1,6 @@
 use macros::model;
+use schemars::JsonSchema;
 
 #[model]
+#[derive(JsonSchema)]
 pub enum ModuleCallback {}
@@ -1,6 +1,31 @@
 use enumorph::Enumorph;
+use ibc_union_spec::datagram::Datagram;
 use macros::model;
+use unionlabs::primitives::{Bytes, H160};
 
 #[model]
 #[derive(Enumorph)]
-pub enum ModuleData {}
+pub enum ModuleData {
+    DryRunTransaction(DryRunTransaction),
+}
+
+/// A multicall that was simulated with `eth_call` instead of being sent, as the plugin is running in dry run mode.
+#[model]
+pub struct DryRunTransaction {
+    pub sender: H160,
+    pub gas_estimate: u64,
+    /// The gas limit the transaction would have been sent with.
+    pub gas_limit: u64,
+    pub gas_price: u128,
+    /// The fee that would have been paid for the transaction, in wei, assuming all of the estimated gas is used.
+    pub fee: u128,
+    pub msgs: Vec<DryRunMsg>,
+}
+
+#[model]
+pub struct DryRunMsg {
+    pub datagram: Datagram,
+    pub success: bool,
+    /// The revert data, if the message failed.
+    pub revert: Option<Bytes>,
+}
@@ -12,13 +12,15 @@ use alloy::{
     transports::TransportError,
 };
 use bip32::secp256k1::ecdsa::{self, SigningKey};
-use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
+use concurrent_keyring::{
+    ChainKeyring, ConcurrentKeyring, KeyringConfig, KeyringEntry, SignerBalance,
+};
 use ibc_solidity::Ibc::{self, IbcErrors};
 use ibc_union_spec::{datagram::Datagram, IbcUnion};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::{ErrorObject, ErrorObjectOwned},
-    Extensions,
+    Extensions, Methods,
 };
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, info_span, instrument, trace, warn, Instrument};
@@ -30,14 +32,16 @@ use voyager_message::{
     core::ChainId,
     data::Data,
     hook::SubmitTxHook,
-    module::{PluginInfo, PluginServer},
-    vm::{call, defer, now, pass::PassResult, seq, BoxDynError, Op, Visit},
+    module::{KeyringServer, PluginInfo, PluginServer},
+    plugin_schema_methods,
+    vm::{call, data, defer, now, pass::PassResult, seq, BoxDynError, Op, Visit},
     DefaultCmd, Plugin, PluginMessage, VoyagerMessage,
 };
 
 use crate::{
     call::ModuleCall,
     callback::ModuleCallback,
+    data::{DryRunMsg, DryRunTransaction, ModuleData},
     multicall::{Call3, Multicall, MulticallResult},
 };
 
@@ -68,6 +72,8 @@ pub struct Module {
     pub fixed_gas_price: Option<u128>,
 
     pub legacy: bool,
+
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,6 +104,11 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+
+    /// Simulate the transactions with `eth_call` instead of sending them, returning them as [`DryRunTransaction`]
+    /// data.
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 impl Plugin for Module {
@@ -149,10 +160,12 @@ impl Plugin for Module {
                         signer,
                     }
                 }),
-            ),
+            )
+            .with_min_balance(config.keyring.min_balance),
             max_gas_price: config.max_gas_price,
             fixed_gas_price: config.fixed_gas_price,
             legacy: config.legacy,
+            dry_run: config.dry_run,
         })
     }
 
@@ -170,6 +183,14 @@ impl Plugin for Module {
     async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
         match cmd {}
     }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        let mut methods = Methods::from(KeyringServer::into_rpc(self.clone()));
+        methods
+            .merge(plugin_schema_methods::<ModuleCall, ModuleCallback>())
+            .expect("the schema methods do not conflict with the keyring methods");
+        methods
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
@@ -184,6 +205,38 @@ impl Module {
     }
 }
 
+impl ChainKeyring for Module {
+    type Address = alloy::primitives::Address;
+    type Signer = LocalSigner<SigningKey>;
+
+    fn keyring(&self) -> &ConcurrentKeyring<Self::Address, Self::Signer> {
+        &self.keyring
+    }
+
+    async fn balances(&self) -> Vec<SignerBalance<Self::Address>> {
+        let mut balances = vec![];
+
+        for (key_name, address) in self.keyring.keys() {
+            match self.provider.get_balance(*address).await {
+                Ok(balance) => balances.push(SignerBalance {
+                    key_name: key_name.to_owned(),
+                    address: *address,
+                    balance: balance.try_into().unwrap_or(u128::MAX),
+                    denom: "wei".to_owned(),
+                }),
+                Err(err) => warn!(
+                    %key_name,
+                    %address,
+                    "error querying signer balance: {}",
+                    ErrorReporter(err)
+                ),
+            }
+        }
+
+        balances
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum TxSubmitError {
     #[error(transparent)]
@@ -264,7 +317,11 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                 };
 
                 match res {
-                    Some(Ok(())) => Ok(Op::Noop),
+                    Some(Ok(None)) => Ok(Op::Noop),
+                    Some(Ok(Some(dry_run))) => Ok(data(PluginMessage::new(
+                        self.plugin_name(),
+                        ModuleData::from(dry_run),
+                    ))),
                     Some(Err(TxSubmitError::GasPriceTooHigh { .. })) => {
                         Ok(seq([defer(now() + 6), call(rewrap_msg())]))
                     }
@@ -314,11 +371,12 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
 }
 
 impl Module {
+    /// Returns the simulated transaction if the plugin is running in dry run mode.
     async fn submit_transaction(
         &self,
         wallet: &LocalSigner<SigningKey>,
         ibc_messages: Vec<Datagram>,
-    ) -> Result<(), TxSubmitError> {
+    ) -> Result<Option<DryRunTransaction>, TxSubmitError> {
         let signer = DynProvider::new(
             ProviderBuilder::new()
                 .network::<AnyNetwork>()
@@ -398,6 +456,43 @@ impl Module {
             call = call.gas_price(fixed_gas_price);
         }
 
+        if self.dry_run {
+            let gas_price = match self.fixed_gas_price {
+                Some(fixed_gas_price) => fixed_gas_price,
+                None => self
+                    .provider
+                    .get_gas_price()
+                    .await
+                    .map_err(|err| TxSubmitError::Error(Error::TransportError(err)))?,
+            };
+
+            let results = call.gas(gas_to_use).call().await?.returnData;
+
+            info!(
+                gas_estimate,
+                gas_price,
+                batch.size = msg_names.len(),
+                "simulated batched evm messages (dry run)"
+            );
+
+            return Ok(Some(DryRunTransaction {
+                sender: wallet.address().0.into(),
+                gas_estimate,
+                gas_limit: gas_to_use,
+                gas_price,
+                fee: u128::from(gas_estimate).saturating_mul(gas_price),
+                msgs: results
+                    .into_iter()
+                    .zip(msgs)
+                    .map(|(result, (datagram, _))| DryRunMsg {
+                        datagram,
+                        success: result.success,
+                        revert: (!result.success).then(|| result.returnData.to_vec().into()),
+                    })
+                    .collect(),
+            }));
+        }
+
         match call.gas(gas_to_use).send().await {
             Ok(ok) => {
                 let tx_hash = <H256>::from(*ok.tx_hash());
@@ -466,7 +561,7 @@ impl Module {
                         }
                     }
 
-                    Ok(())
+                    Ok(None)
                 }
                 .instrument(info_span!(
                     "evm tx",
@@ -497,7 +592,7 @@ impl Module {
             {
                 if msgs.len() == 1 {
                     error!(error = %e.message, msg = ?msgs[0], "message is too large");
-                    Ok(()) // drop the message
+                    Ok(None) // drop the message
                 } else {
                     warn!(error = %e.message, "batch is too large");
                     Err(TxSubmitError::BatchTooLarge)
@@ -0,0 +1,14 @@
+use macros::model;
+use schemars::JsonSchema;
+
+#[model]
+#[derive(JsonSchema)]
+pub enum ModuleCall {
+    CheckBalances(CheckBalances),
+}
+
+/// Check the balances of all of the configured accounts, topping up the ones below their threshold. This message
+/// requeues itself after the configured check interval.
+#[model]
+#[derive(JsonSchema)]
+pub struct CheckBalances {}
@@ -0,0 +1,146 @@
+use alloy::{
+    network::{EthereumWallet, TransactionBuilder},
+    primitives::{Address, U256},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+};
+use cosmos_client::{
+    gas::GasConfig,
+    rpc::{Rpc, RpcT},
+    wallet::{LocalSigner, WalletT},
+    TxClient,
+};
+use serde::{Deserialize, Serialize};
+use unionlabs::{google::protobuf::any::mk_any, primitives::H256};
+use voyager_vm::BoxDynError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ChainConfig {
+    /// Top-ups are sent with a bank send of the gas denom.
+    CosmosSdk {
+        rpc_url: String,
+        gas_config: GasConfig,
+    },
+    /// Top-ups are sent as native transfers.
+    Evm { rpc_url: String },
+}
+
+/// The treasury on a chain, that top-ups are sent from.
+#[derive(Debug)]
+pub enum Treasury {
+    CosmosSdk {
+        rpc: Rpc,
+        gas_config: GasConfig,
+        signer: LocalSigner,
+    },
+    Evm {
+        provider: DynProvider,
+        address: Address,
+    },
+}
+
+impl Treasury {
+    pub async fn new(config: ChainConfig, treasury_key: H256) -> Result<Self, BoxDynError> {
+        match config {
+            ChainConfig::CosmosSdk {
+                rpc_url,
+                gas_config,
+            } => {
+                let rpc = Rpc::new(rpc_url).await?;
+
+                let bech32_prefix = rpc
+                    .client()
+                    .grpc_abci_query::<_, protos::cosmos::auth::v1beta1::Bech32PrefixResponse>(
+                        "/cosmos.auth.v1beta1.Query/Bech32Prefix",
+                        &protos::cosmos::auth::v1beta1::Bech32PrefixRequest {},
+                        None,
+                        false,
+                    )
+                    .await?
+                    .into_result()?
+                    .ok_or("empty bech32 prefix response")?
+                    .bech32_prefix;
+
+                Ok(Self::CosmosSdk {
+                    rpc,
+                    gas_config,
+                    signer: LocalSigner::new(treasury_key, bech32_prefix),
+                })
+            }
+            ChainConfig::Evm { rpc_url } => {
+                let signer = PrivateKeySigner::from_bytes(&(*treasury_key.get()).into())?;
+                let address = signer.address();
+
+                let provider = DynProvider::new(
+                    ProviderBuilder::new()
+                        .with_recommended_fillers()
+                        .wallet(EthereumWallet::new(signer))
+                        .on_builtin(&rpc_url)
+                        .await?,
+                );
+
+                Ok(Self::Evm { provider, address })
+            }
+        }
+    }
+
+    pub fn address(&self) -> String {
+        match self {
+            Self::CosmosSdk { signer, .. } => signer.address().to_string(),
+            Self::Evm { address, .. } => address.to_string(),
+        }
+    }
+
+    pub fn denom(&self) -> &str {
+        match self {
+            Self::CosmosSdk { gas_config, .. } => &gas_config.gas_denom,
+            Self::Evm { .. } => "wei",
+        }
+    }
+
+    /// Sends `amount` from the treasury to `address`, returning the hash of the transaction.
+    pub async fn send(&self, address: &str, amount: u128) -> Result<String, BoxDynError> {
+        match self {
+            Self::CosmosSdk {
+                rpc,
+                gas_config,
+                signer,
+            } => {
+                let (tx_hash, _) = TxClient::new(signer, rpc, gas_config)
+                    .broadcast_tx_commit(
+                        [mk_any(&protos::cosmos::bank::v1beta1::MsgSend {
+                            from_address: signer.address().to_string(),
+                            to_address: address.to_owned(),
+                            amount: vec![protos::cosmos::base::v1beta1::Coin {
+                                denom: gas_config.gas_denom.clone(),
+                                amount: amount.to_string(),
+                            }],
+                        })],
+                        format!("Voyager {} top-up", env!("CARGO_PKG_VERSION")),
+                    )
+                    .await?;
+
+                Ok(tx_hash.to_string())
+            }
+            Self::Evm { provider, .. } => {
+                let receipt = provider
+                    .send_transaction(
+                        TransactionRequest::default()
+                            .with_to(address.parse()?)
+                            .with_value(U256::from(amount)),
+                    )
+                    .await?
+                    .get_receipt()
+                    .await?;
+
+                if !receipt.status() {
+                    return Err(format!("transfer {} reverted", receipt.transaction_hash).into());
+                }
+
+                Ok(receipt.transaction_hash.to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,564 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use concurrent_keyring::SignerBalance;
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    Extensions, Methods,
+};
+use opentelemetry::{
+    metrics::{Counter, Gauge},
+    KeyValue,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, instrument, warn};
+use unionlabs::{never::Never, primitives::H256};
+use voyager_message::{
+    core::ChainId,
+    data::Data,
+    into_value,
+    module::{PluginInfo, PluginServer},
+    plugin_schema_methods, ExtensionsExt, Plugin, PluginMessage, VoyagerClient, VoyagerMessage,
+};
+use voyager_vm::{call, defer, now, pass::PassResult, seq, BoxDynError, Op};
+
+use crate::{
+    call::{CheckBalances, ModuleCall},
+    chain::{ChainConfig, Treasury},
+    store::TopUps,
+};
+
+pub mod call;
+pub mod chain;
+pub mod store;
+
+/// The window that the daily cap applies to, in seconds.
+const DAY: u64 = 24 * 60 * 60;
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    Module::run().await
+}
+
+pub struct Module {
+    pub chain_id: ChainId,
+    pub treasury: Treasury,
+    pub top_ups_store: TopUps,
+    pub transaction_plugin: String,
+    pub top_up_configs: TopUpConfigs,
+    pub daily_cap: Mutex<DailyCap>,
+    pub check_interval: u64,
+
+    /// The total amount sent to each account.
+    pub topped_up: Counter<f64>,
+    /// The number of top-ups sent to each account.
+    pub top_ups: Counter<u64>,
+    /// Top-ups that were skipped because they would exceed the daily cap.
+    pub cap_reached: Counter<u64>,
+    /// The amount sent in the last day.
+    pub daily_sent: Gauge<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub chain_id: ChainId,
+    pub chain: ChainConfig,
+    /// The private key of the account that the top-ups are sent from.
+    pub treasury_key: H256,
+    /// The name of the transaction plugin whose keyring is topped up.
+    pub transaction_plugin: String,
+    /// Keys are topped up once their balance falls below this.
+    #[serde(with = "::serde_utils::string")]
+    pub threshold: u128,
+    /// The balance keys are topped up to.
+    #[serde(with = "::serde_utils::string")]
+    pub target: u128,
+    /// Overrides of the threshold and target of individual keys, by key name or address.
+    #[serde(default)]
+    pub keys: BTreeMap<String, TopUpConfig>,
+    /// The maximum total amount sent in any 24 hour window.
+    #[serde(with = "::serde_utils::string")]
+    pub daily_cap: u128,
+    /// The postgres database that the sent top-ups are recorded in, typically the voyager queue database.
+    pub database_url: String,
+    /// The interval between balance checks, in seconds.
+    #[serde(default = "default_check_interval")]
+    pub check_interval: u64,
+}
+
+fn default_check_interval() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TopUpConfig {
+    /// The key is topped up once its balance falls below this.
+    #[serde(with = "::serde_utils::string")]
+    pub threshold: u128,
+    /// The balance the key is topped up to.
+    #[serde(with = "::serde_utils::string")]
+    pub target: u128,
+}
+
+/// The threshold and target of each key, falling back to the default for keys without an override.
+#[derive(Debug, Clone)]
+pub struct TopUpConfigs {
+    pub default: TopUpConfig,
+    pub keys: BTreeMap<String, TopUpConfig>,
+}
+
+impl TopUpConfigs {
+    /// The config of a key, looked up by its name first and then by its address.
+    #[must_use]
+    pub fn get(&self, key_name: &str, address: &str) -> &TopUpConfig {
+        self.keys
+            .get(key_name)
+            .or_else(|| self.keys.get(address))
+            .unwrap_or(&self.default)
+    }
+}
+
+impl Plugin for Module {
+    type Call = ModuleCall;
+    type Callback = Never;
+
+    type Config = Config;
+    type Cmd = Cmd;
+
+    async fn new(config: Self::Config) -> Result<Self, BoxDynError> {
+        let top_up_configs = TopUpConfigs {
+            default: TopUpConfig {
+                threshold: config.threshold,
+                target: config.target,
+            },
+            keys: config.keys,
+        };
+
+        for (key, top_up_config) in [("default", &top_up_configs.default)].into_iter().chain(
+            top_up_configs
+                .keys
+                .iter()
+                .map(|(key, top_up_config)| (key.as_str(), top_up_config)),
+        ) {
+            if top_up_config.target <= top_up_config.threshold {
+                return Err(format!("target must be greater than threshold for {key}").into());
+            }
+        }
+
+        let treasury = Treasury::new(config.chain, config.treasury_key).await?;
+
+        info!(address = %treasury.address(), "loaded treasury");
+
+        let top_ups_store = TopUps::new(&config.database_url, config.chain_id.clone()).await?;
+
+        let mut daily_cap = DailyCap::new(config.daily_cap);
+        for (sent_at, amount) in top_ups_store
+            .sent_since(unix_timestamp().saturating_sub(DAY))
+            .await?
+        {
+            daily_cap.record(sent_at, amount);
+        }
+
+        let meter = opentelemetry::global::meter("voyager.wallet_top_up");
+
+        Ok(Self {
+            chain_id: config.chain_id,
+            treasury,
+            top_ups_store,
+            transaction_plugin: config.transaction_plugin,
+            top_up_configs,
+            daily_cap: Mutex::new(daily_cap),
+            check_interval: config.check_interval,
+            topped_up: meter
+                .f64_counter("wallet.topped_up")
+                .with_description("The total amount sent to the account from the treasury")
+                .build(),
+            top_ups: meter
+                .u64_counter("wallet.top_ups")
+                .with_description("The number of top-ups sent to the account")
+                .build(),
+            cap_reached: meter
+                .u64_counter("wallet.cap_reached")
+                .with_description("Top-ups skipped because they would exceed the daily cap")
+                .build(),
+            daily_sent: meter
+                .f64_gauge("wallet.daily_sent")
+                .with_description("The amount sent from the treasury in the last 24 hours")
+                .build(),
+        })
+    }
+
+    fn info(config: Self::Config) -> PluginInfo {
+        PluginInfo {
+            name: plugin_name(&config.chain_id),
+            // never interested in any messages since this plugin does not utilize a queue
+            interest_filter: "false".to_owned(),
+        }
+    }
+
+    async fn cmd(config: Self::Config, cmd: Self::Cmd) {
+        match cmd {
+            Cmd::MakeMessage => {
+                let op = call::<VoyagerMessage>(PluginMessage::new(
+                    plugin_name(&config.chain_id),
+                    ModuleCall::CheckBalances(CheckBalances {}),
+                ));
+
+                println!("{}", into_value(op));
+            }
+        }
+    }
+
+    fn extra_rpc_methods(&self) -> Methods {
+        plugin_schema_methods::<ModuleCall, Never>()
+    }
+}
+
+#[derive(clap::Subcommand)]
+pub enum Cmd {
+    /// Print the message that starts the periodic balance checks.
+    MakeMessage,
+}
+
+fn plugin_name(chain_id: &ChainId) -> String {
+    pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+    format!("{PLUGIN_NAME}/{}", chain_id)
+}
+
+impl Module {
+    fn plugin_name(&self) -> String {
+        plugin_name(&self.chain_id)
+    }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn check_balances(&self, voyager_client: &VoyagerClient) -> Op<VoyagerMessage> {
+        let denom = self.treasury.denom();
+
+        // the balances are queried from the transaction plugin, and the top-ups are only sent once they are
+        // committed, so a key that was topped up is not seen with its old balance again
+        let balances = match voyager_client
+            .signer_balances(&self.transaction_plugin)
+            .await
+        {
+            Ok(balances) => balances,
+            Err(err) => {
+                warn!(
+                    transaction_plugin = %self.transaction_plugin,
+                    "error querying signer balances: {}", err.message()
+                );
+                vec![]
+            }
+        };
+
+        for SignerBalance {
+            key_name,
+            address,
+            balance,
+            denom: balance_denom,
+        } in balances
+        {
+            if balance_denom != denom {
+                warn!(
+                    %key_name,
+                    %address,
+                    %balance_denom,
+                    %denom,
+                    "signer balance is not in the treasury denom"
+                );
+                continue;
+            }
+
+            let TopUpConfig { threshold, target } = *self.top_up_configs.get(&key_name, &address);
+
+            if balance >= threshold {
+                continue;
+            }
+
+            let attributes = [
+                KeyValue::new("chain_id", self.chain_id.to_string()),
+                KeyValue::new("key_name", key_name.clone()),
+                KeyValue::new("address", address.clone()),
+                KeyValue::new("denom", denom.to_owned()),
+            ];
+
+            let amount = target - balance;
+            let now = unix_timestamp();
+
+            let reserved = self
+                .daily_cap
+                .lock()
+                .expect("lock is not poisoned")
+                .reserve(now, amount);
+
+            let (reservation, sent_today) = match reserved {
+                Ok(reserved) => reserved,
+                Err(daily_cap) => {
+                    warn!(
+                        %key_name,
+                        %address,
+                        balance,
+                        amount,
+                        daily_cap,
+                        %denom,
+                        "daily top-up cap reached, not topping up account"
+                    );
+
+                    self.cap_reached.add(1, &attributes);
+
+                    continue;
+                }
+            };
+
+            let id = match self.top_ups_store.insert(&address, amount, now).await {
+                Ok(id) => id,
+                Err(err) => {
+                    error!(%key_name, %address, amount, "error recording top-up: {err}");
+
+                    self.daily_cap
+                        .lock()
+                        .expect("lock is not poisoned")
+                        .release(reservation);
+
+                    continue;
+                }
+            };
+
+            match self.treasury.send(&address, amount).await {
+                Ok(tx_hash) => {
+                    info!(
+                        %key_name,
+                        %address,
+                        balance,
+                        amount,
+                        %denom,
+                        %tx_hash,
+                        "topped up account"
+                    );
+
+                    self.top_ups.add(1, &attributes);
+                    self.topped_up.add(amount as f64, &attributes);
+                    self.daily_sent.record(
+                        sent_today as f64,
+                        &[KeyValue::new("chain_id", self.chain_id.to_string())],
+                    );
+                }
+                Err(err) => {
+                    error!(
+                        %key_name,
+                        %address,
+                        amount,
+                        %denom,
+                        "error topping up account: {err}"
+                    );
+
+                    self.daily_cap
+                        .lock()
+                        .expect("lock is not poisoned")
+                        .release(reservation);
+
+                    if let Err(err) = self.top_ups_store.delete(id).await {
+                        error!(id, "error removing failed top-up: {err}");
+                    }
+                }
+            }
+        }
+
+        seq([
+            defer(now() + self.check_interval),
+            call(PluginMessage::new(
+                self.plugin_name(),
+                ModuleCall::CheckBalances(CheckBalances {}),
+            )),
+        ])
+    }
+}
+
+#[async_trait]
+impl PluginServer<ModuleCall, Never> for Module {
+    async fn run_pass(
+        &self,
+        _: &Extensions,
+        msgs: Vec<Op<VoyagerMessage>>,
+    ) -> RpcResult<PassResult<VoyagerMessage>> {
+        error!(?msgs, "this plugin does not utilize a queue");
+
+        Ok(PassResult::default())
+    }
+
+    async fn call(&self, e: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
+        match msg {
+            ModuleCall::CheckBalances(CheckBalances {}) => {
+                Ok(self.check_balances(e.try_get()?).await)
+            }
+        }
+    }
+
+    async fn callback(
+        &self,
+        _: &Extensions,
+        cb: Never,
+        _data: VecDeque<Data>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        match cb {}
+    }
+}
+
+/// The top-ups sent in the last 24 hours. The top-ups are also recorded in [`TopUps`], and restored with
+/// [`Self::record`] when the plugin starts.
+#[derive(Debug)]
+pub struct DailyCap {
+    cap: u128,
+    next_id: u64,
+    sent: VecDeque<Reservation>,
+}
+
+/// Identifies an amount reserved with [`DailyCap::reserve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReservationId(u64);
+
+#[derive(Debug)]
+struct Reservation {
+    id: ReservationId,
+    /// unix timestamp
+    sent_at: u64,
+    amount: u128,
+}
+
+impl DailyCap {
+    #[must_use]
+    pub fn new(cap: u128) -> Self {
+        Self {
+            cap,
+            next_id: 0,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// Records a top-up that was already sent, regardless of the cap.
+    pub fn record(&mut self, sent_at: u64, amount: u128) {
+        self.push(sent_at, amount);
+    }
+
+    /// Reserves `amount` from the cap, returning the reservation and the total amount sent in the last day including
+    /// `amount`. Returns the cap as the error if it would be exceeded.
+    pub fn reserve(&mut self, now: u64, amount: u128) -> Result<(ReservationId, u128), u128> {
+        while self
+            .sent
+            .front()
+            .is_some_and(|reservation| reservation.sent_at + DAY <= now)
+        {
+            self.sent.pop_front();
+        }
+
+        let sent_today = self
+            .sent
+            .iter()
+            .map(|reservation| reservation.amount)
+            .sum::<u128>()
+            .saturating_add(amount);
+
+        if sent_today > self.cap {
+            return Err(self.cap);
+        }
+
+        Ok((self.push(now, amount), sent_today))
+    }
+
+    /// Releases an amount reserved with [`Self::reserve`], if the top-up failed.
+    pub fn release(&mut self, id: ReservationId) {
+        self.sent.retain(|reservation| reservation.id != id);
+    }
+
+    fn push(&mut self, sent_at: u64, amount: u128) -> ReservationId {
+        let id = ReservationId(self.next_id);
+        self.next_id += 1;
+
+        self.sent.push_back(Reservation {
+            id,
+            sent_at,
+            amount,
+        });
+
+        id
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sent_today(reserved: Result<(ReservationId, u128), u128>) -> Result<u128, u128> {
+        reserved.map(|(_, sent_today)| sent_today)
+    }
+
+    #[test]
+    fn top_up_configs() {
+        let top_up_config = |threshold, target| TopUpConfig { threshold, target };
+
+        let configs = TopUpConfigs {
+            default: top_up_config(10, 20),
+            keys: [
+                ("alice".to_owned(), top_up_config(100, 200)),
+                ("0xb0b".to_owned(), top_up_config(1000, 2000)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        // by key name
+        assert_eq!(configs.get("alice", "0xa11ce"), &top_up_config(100, 200));
+        // by address
+        assert_eq!(configs.get("bob", "0xb0b"), &top_up_config(1000, 2000));
+        // the key name takes precedence over the address
+        assert_eq!(configs.get("alice", "0xb0b"), &top_up_config(100, 200));
+        // the default for keys without an override
+        assert_eq!(configs.get("carol", "0xca201"), &top_up_config(10, 20));
+    }
+
+    #[test]
+    fn daily_cap() {
+        let mut cap = DailyCap::new(100);
+
+        assert_eq!(sent_today(cap.reserve(0, 60)), Ok(60));
+        assert_eq!(sent_today(cap.reserve(1, 60)), Err(100));
+        assert_eq!(sent_today(cap.reserve(1, 40)), Ok(100));
+        assert_eq!(sent_today(cap.reserve(DAY - 1, 1)), Err(100));
+        // the first top-up is out of the window
+        assert_eq!(sent_today(cap.reserve(DAY, 60)), Ok(100));
+    }
+
+    #[test]
+    fn release() {
+        let mut cap = DailyCap::new(100);
+
+        let (first, _) = cap.reserve(0, 50).unwrap();
+        assert_eq!(sent_today(cap.reserve(DAY / 2, 50)), Ok(100));
+
+        // releases the first reservation, not the later one of the same amount
+        cap.release(first);
+        assert_eq!(sent_today(cap.reserve(DAY, 100)), Err(100));
+        assert_eq!(sent_today(cap.reserve(DAY / 2 + DAY, 100)), Ok(100));
+    }
+
+    #[test]
+    fn record() {
+        let mut cap = DailyCap::new(100);
+
+        cap.record(0, 60);
+        assert_eq!(sent_today(cap.reserve(1, 60)), Err(100));
+        assert_eq!(sent_today(cap.reserve(DAY, 60)), Ok(60));
+    }
+}
@@ -0,0 +1,83 @@
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use voyager_message::core::ChainId;
+use voyager_vm::BoxDynError;
+
+/// The top-ups sent from the treasury, recorded in postgres so that the daily cap carries over restarts of the plugin.
+#[derive(Debug, Clone)]
+pub struct TopUps {
+    pool: PgPool,
+    chain_id: ChainId,
+}
+
+impl TopUps {
+    pub async fn new(database_url: &str, chain_id: ChainId) -> Result<Self, BoxDynError> {
+        let pool = PgPoolOptions::new()
+            .max_connections(2)
+            .connect(database_url)
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS wallet_top_ups(
+                id BIGSERIAL PRIMARY KEY,
+                chain_id TEXT NOT NULL,
+                address TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                sent_at BIGINT NOT NULL
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS wallet_top_ups_chain_id_sent_at ON wallet_top_ups(chain_id, sent_at)",
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(Self { pool, chain_id })
+    }
+
+    /// The top-ups sent after the unix timestamp `since`, as (unix timestamp, amount), oldest first.
+    pub async fn sent_since(&self, since: u64) -> Result<Vec<(u64, u128)>, BoxDynError> {
+        sqlx::query_as::<_, (i64, String)>(
+            "SELECT sent_at, amount FROM wallet_top_ups WHERE chain_id = $1 AND sent_at > $2 ORDER BY sent_at",
+        )
+        .bind(self.chain_id.as_str())
+        .bind(i64::try_from(since)?)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|(sent_at, amount)| Ok((sent_at.try_into()?, amount.parse()?)))
+        .collect()
+    }
+
+    /// Records a top-up before it is sent, returning the id of the row.
+    pub async fn insert(
+        &self,
+        address: &str,
+        amount: u128,
+        sent_at: u64,
+    ) -> Result<i64, BoxDynError> {
+        Ok(sqlx::query_scalar(
+            "INSERT INTO wallet_top_ups(chain_id, address, amount, sent_at) VALUES ($1, $2, $3, $4) RETURNING id",
+        )
+        .bind(self.chain_id.as_str())
+        .bind(address)
+        .bind(amount.to_string())
+        .bind(i64::try_from(sent_at)?)
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    /// Removes a top-up recorded with [`Self::insert`], if it failed to send.
+    pub async fn delete(&self, id: i64) -> Result<(), BoxDynError> {
+        sqlx::query("DELETE FROM wallet_top_ups WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
     extract::State,
@@ -15,21 +15,40 @@ use tracing::error;
 use voyager_message::VoyagerMessage;
 use voyager_vm::Op;
 
-pub fn run(laddr: &SocketAddr) -> UnboundedReceiver<Op<VoyagerMessage>> {
+use crate::{schema::Schemas, signer_balances::SignerBalances};
+
+#[derive(Clone)]
+struct EnqueueState {
+    sender: UnboundedSender<Op<VoyagerMessage>>,
+    schemas: Arc<Schemas>,
+}
+
+pub fn run(
+    laddr: &SocketAddr,
+    schemas: Arc<Schemas>,
+    signer_balances: SignerBalances,
+) -> UnboundedReceiver<Op<VoyagerMessage>> {
     let (queue_tx, queue_rx) = unbounded::<Op<VoyagerMessage>>();
 
     let app = axum::Router::new()
         .route("/enqueue", post(enqueue))
         .route("/health", get(|| async move { StatusCode::OK }))
         .route("/metrics", get(metrics))
-        // .route(
-        //     "/signer/balances",
-        //     get({
-        //         let chains = self.chains.clone();
-        //         || async move { Json(signer_balances(&chains).await) }
-        //     }),
-        // )
-        .with_state(queue_tx.clone());
+        .route(
+            "/signer/balances",
+            get(|| async move {
+                Json(
+                    signer_balances
+                        .read()
+                        .expect("lock is not poisoned")
+                        .clone(),
+                )
+            }),
+        )
+        .with_state(EnqueueState {
+            sender: queue_tx.clone(),
+            schemas,
+        });
 
     tokio::spawn(axum::Server::bind(laddr).serve(app.into_make_service()));
 
@@ -38,12 +57,21 @@ pub fn run(laddr: &SocketAddr) -> UnboundedReceiver<Op<VoyagerMessage>> {
 
 // #[axum::debug_handler]
 async fn enqueue(
-    State(mut sender): State<UnboundedSender<Op<VoyagerMessage>>>,
+    State(EnqueueState {
+        mut sender,
+        schemas,
+    }): State<EnqueueState>,
     Json(op): Json<Op<VoyagerMessage>>,
-) -> StatusCode {
+) -> Result<StatusCode, (StatusCode, Json<Vec<String>>)> {
+    let errors = schemas.validate(&op);
+
+    if !errors.is_empty() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, Json(errors)));
+    }
+
     sender.send(op).await.expect("receiver should not close");
 
-    StatusCode::OK
+    Ok(StatusCode::OK)
 }
 
 async fn metrics() -> Result<String, StatusCode> {
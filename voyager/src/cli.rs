@@ -318,6 +318,83 @@ pub enum MsgCmd {
         #[arg(long)]
         update_to: Option<Height>,
 
+        /// Automatically enqueue the op.
+        #[arg(long, short = 'e', default_value_t = false)]
+        enqueue: bool,
+        #[arg(
+            long,
+            global = true,
+            default_value_t = format!(
+                "http://{}",
+                default_rest_laddr()
+            )
+        )]
+        rest_url: String,
+    },
+    /// Construct a call to a plugin. The message is validated against the plugin's schema when it is enqueued.
+    Plugin {
+        plugin: String,
+        #[arg(
+            // the autoref value parser selector chooses From<String> before FromStr, but Value's From<String> impl always returns Value::String(..), whereas FromStr actually parses the json contained within the string
+            value_parser(serde_json::Value::from_str)
+        )]
+        message: serde_json::Value,
+
+        /// Automatically enqueue the op.
+        #[arg(long, short = 'e', default_value_t = false)]
+        enqueue: bool,
+        #[arg(
+            long,
+            global = true,
+            default_value_t = format!(
+                "http://{}",
+                default_rest_laddr()
+            )
+        )]
+        rest_url: String,
+    },
+    /// Combine the provided ops into an op that executes them sequentially.
+    Seq {
+        #[arg(value_parser(|s: &str| serde_json::from_str::<Op<VoyagerMessage>>(s)))]
+        ops: Vec<Op<VoyagerMessage>>,
+
+        /// Automatically enqueue the op.
+        #[arg(long, short = 'e', default_value_t = false)]
+        enqueue: bool,
+        #[arg(
+            long,
+            global = true,
+            default_value_t = format!(
+                "http://{}",
+                default_rest_laddr()
+            )
+        )]
+        rest_url: String,
+    },
+    /// Combine the provided ops into an op that executes them concurrently.
+    Conc {
+        #[arg(value_parser(|s: &str| serde_json::from_str::<Op<VoyagerMessage>>(s)))]
+        ops: Vec<Op<VoyagerMessage>>,
+
+        /// Automatically enqueue the op.
+        #[arg(long, short = 'e', default_value_t = false)]
+        enqueue: bool,
+        #[arg(
+            long,
+            global = true,
+            default_value_t = format!(
+                "http://{}",
+                default_rest_laddr()
+            )
+        )]
+        rest_url: String,
+    },
+    /// Delay the provided op by the specified number of seconds from now.
+    Defer {
+        seconds: u64,
+        #[arg(value_parser(|s: &str| serde_json::from_str::<Op<VoyagerMessage>>(s)))]
+        op: Op<VoyagerMessage>,
+
         /// Automatically enqueue the op.
         #[arg(long, short = 'e', default_value_t = false)]
         enqueue: bool,
@@ -38,6 +38,9 @@ pub struct VoyagerConfig {
     // TODO: Specify per plugin
     #[serde(default = "default_optimizer_delay_milliseconds")]
     pub optimizer_delay_milliseconds: u64,
+    /// How often the balances of the signers of all plugins are collected, in seconds.
+    #[serde(default = "default_signer_balances_interval_seconds")]
+    pub signer_balances_interval_seconds: u64,
     #[serde(default = "default_ipc_client_request_timeout")]
     pub ipc_client_request_timeout: Duration,
     pub cache: voyager_message::rpc::server::cache::Config,
@@ -66,6 +69,12 @@ pub const fn default_optimizer_delay_milliseconds() -> u64 {
     100
 }
 
+#[must_use]
+#[inline]
+pub const fn default_signer_balances_interval_seconds() -> u64 {
+    60
+}
+
 #[must_use]
 #[inline]
 pub const fn default_ipc_client_request_timeout() -> Duration {
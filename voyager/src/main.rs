@@ -14,7 +14,7 @@ use std::{
     process::ExitCode, time::Duration,
 };
 
-use anyhow::{anyhow, Context as _};
+use anyhow::{anyhow, bail, Context as _};
 use clap::Parser;
 use ibc_classic_spec::IbcClassic;
 use ibc_union_spec::IbcUnion;
@@ -34,9 +34,9 @@ use voyager_message::{
     core::{IbcSpec, QueryHeight},
     filter::{make_filter, run_filter, JaqInterestFilter},
     rpc::{server::cache, IbcState, VoyagerRpcClient},
-    VoyagerMessage,
+    PluginMessage, VoyagerMessage,
 };
-use voyager_vm::{call, filter::FilterResult, promise, Op, Queue};
+use voyager_vm::{call, conc, defer, filter::FilterResult, now, promise, seq, Op, Queue};
 
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
@@ -44,7 +44,8 @@ static GLOBAL: Jemalloc = Jemalloc;
 use crate::{
     cli::{AppArgs, Command, ConfigCmd, ModuleCmd, MsgCmd, PluginCmd, QueueCmd, RpcCmd},
     config::{
-        default_metrics_endpoint, default_rest_laddr, default_rpc_laddr, Config, VoyagerConfig,
+        default_metrics_endpoint, default_rest_laddr, default_rpc_laddr,
+        default_signer_balances_interval_seconds, Config, VoyagerConfig,
     },
     queue::{QueueConfig, Voyager},
     utils::make_msg_create_client,
@@ -61,6 +62,8 @@ pub mod cli;
 pub mod config;
 pub mod metrics;
 pub mod queue;
+pub mod schema;
+pub mod signer_balances;
 
 fn main() -> ExitCode {
     let args = AppArgs::parse();
@@ -167,6 +170,7 @@ async fn do_main(args: cli::AppArgs) -> anyhow::Result<()> {
                         optimize_batch_limit: None,
                     }),
                     optimizer_delay_milliseconds: 100,
+                    signer_balances_interval_seconds: default_signer_balances_interval_seconds(),
                     ipc_client_request_timeout: Duration::new(60, 0),
                     cache: voyager_message::rpc::server::cache::Config::default(),
                 },
@@ -537,6 +541,7 @@ async fn do_main(args: cli::AppArgs) -> anyhow::Result<()> {
                     },
                     Duration::new(60, 0),
                     cache::Config::default(),
+                    None,
                 )
                 .await?;
 
@@ -584,6 +589,7 @@ async fn do_main(args: cli::AppArgs) -> anyhow::Result<()> {
                     },
                     Duration::new(60, 0),
                     cache::Config::default(),
+                    None,
                 )
                 .await?;
 
@@ -628,6 +634,60 @@ async fn do_main(args: cli::AppArgs) -> anyhow::Result<()> {
                     },
                 );
 
+                if enqueue {
+                    send_enqueue(&rest_url, op).await?;
+                } else {
+                    print_json(&op);
+                }
+            }
+            MsgCmd::Plugin {
+                plugin,
+                message,
+                enqueue,
+                rest_url,
+            } => {
+                let op = call::<VoyagerMessage>(PluginMessage::new(plugin, message));
+
+                if enqueue {
+                    send_enqueue(&rest_url, op).await?;
+                } else {
+                    print_json(&op);
+                }
+            }
+            MsgCmd::Seq {
+                ops,
+                enqueue,
+                rest_url,
+            } => {
+                let op = seq(ops);
+
+                if enqueue {
+                    send_enqueue(&rest_url, op).await?;
+                } else {
+                    print_json(&op);
+                }
+            }
+            MsgCmd::Conc {
+                ops,
+                enqueue,
+                rest_url,
+            } => {
+                let op = conc(ops);
+
+                if enqueue {
+                    send_enqueue(&rest_url, op).await?;
+                } else {
+                    print_json(&op);
+                }
+            }
+            MsgCmd::Defer {
+                seconds,
+                op,
+                enqueue,
+                rest_url,
+            } => {
+                let op = seq([defer(now() + seconds), op]);
+
                 if enqueue {
                     send_enqueue(&rest_url, op).await?;
                 } else {
@@ -644,11 +704,22 @@ async fn send_enqueue(
     rest_laddr: &str,
     op: Op<VoyagerMessage>,
 ) -> anyhow::Result<reqwest::Response> {
-    Ok(reqwest::Client::new()
+    let response = reqwest::Client::new()
         .post(format!("{rest_laddr}/enqueue"))
         .json(&op)
         .send()
-        .await?)
+        .await?;
+
+    // ops with plugin messages that don't match the plugin's schema are rejected with the validation errors
+    if !response.status().is_success() {
+        bail!(
+            "error enqueueing op ({}): {}",
+            response.status(),
+            response.text().await?
+        );
+    }
+
+    Ok(response)
 }
 
 fn print_json<T: Serialize>(t: &T) {
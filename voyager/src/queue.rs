@@ -1,6 +1,6 @@
 #![allow(clippy::type_complexity)]
 
-use std::{fmt::Debug, net::SocketAddr, panic::AssertUnwindSafe};
+use std::{fmt::Debug, net::SocketAddr, panic::AssertUnwindSafe, sync::Arc, time::Duration};
 
 use anyhow::{bail, Context as _};
 use futures::{future::BoxFuture, stream::FuturesUnordered, Future, FutureExt, StreamExt};
@@ -21,7 +21,7 @@ use voyager_vm::{
     ItemId, Op, Queue, QueueError,
 };
 
-use crate::{api, config::Config};
+use crate::{api, config::Config, schema::Schemas, signer_balances};
 
 #[derive(Debug)]
 pub struct Voyager {
@@ -32,6 +32,7 @@ pub struct Voyager {
     rpc_laddr: SocketAddr,
     queue: QueueImpl,
     optimizer_delay_milliseconds: u64,
+    signer_balances_interval_seconds: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -160,6 +161,7 @@ impl Voyager {
                 },
                 config.voyager.ipc_client_request_timeout,
                 config.voyager.cache,
+                Some(config.voyager.metrics_endpoint),
             )
             .await
             .context("error initializing plugins")?,
@@ -168,6 +170,7 @@ impl Voyager {
             rpc_laddr: config.voyager.rpc_laddr,
             queue,
             optimizer_delay_milliseconds: config.voyager.optimizer_delay_milliseconds,
+            signer_balances_interval_seconds: config.voyager.signer_balances_interval_seconds,
         })
     }
 
@@ -185,7 +188,11 @@ impl Voyager {
                 .collect(),
         )?;
 
-        let queue_rx = api::run(&self.rest_laddr);
+        let signer_balances = signer_balances::SignerBalances::default();
+
+        let schemas = Arc::new(Schemas::fetch(&self.context).await);
+
+        let queue_rx = api::run(&self.rest_laddr, schemas, signer_balances.clone());
 
         {
             let mut tasks =
@@ -229,6 +236,18 @@ impl Voyager {
                 .catch_unwind(),
             ));
 
+            tasks.push(Box::pin(
+                AssertUnwindSafe(
+                    signer_balances::run(
+                        &self.context,
+                        signer_balances,
+                        Duration::from_secs(self.signer_balances_interval_seconds),
+                    )
+                    .map(Ok),
+                )
+                .catch_unwind(),
+            ));
+
             info!("spawning {} workers", self.num_workers);
 
             for id in 0..self.num_workers {
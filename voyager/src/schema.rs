@@ -0,0 +1,260 @@
+//! Validation of plugin messages against the JSON schemas advertised by the plugins (see
+//! [`voyager_message::plugin_schema_methods`]), so that malformed ops are rejected when they are enqueued rather than
+//! failing once they reach the plugin.
+
+use std::collections::HashMap;
+
+use jsonrpsee::{core::client::Error, types::error::METHOD_NOT_FOUND_CODE};
+use jsonschema::Validator;
+use serde_json::Value;
+use tracing::{debug, info, warn};
+use unionlabs::ErrorReporter;
+use voyager_message::{
+    call::Call, callback::Callback, context::Context, module::PluginSchemaClient, PluginMessage,
+    VoyagerMessage,
+};
+use voyager_vm::{Op, Promise};
+
+#[derive(Default)]
+pub struct Schemas {
+    /// All loaded plugins, with the validators for their schemas if they advertise them.
+    plugins: HashMap<String, Option<PluginSchemas>>,
+}
+
+struct PluginSchemas {
+    call: Validator,
+    callback: Validator,
+}
+
+impl Schemas {
+    /// Fetches the schemas of all loaded plugins. Plugins that don't advertise their schemas, or whose schemas are
+    /// invalid, are not validated against.
+    pub async fn fetch(context: &Context) -> Self {
+        let mut plugins = HashMap::new();
+
+        for plugin_name in context.interest_filters().keys() {
+            let client = context
+                .plugin(plugin_name)
+                .expect("plugin exists")
+                .with_id(None);
+
+            let schemas = match (
+                PluginSchemaClient::call_schema(&client).await,
+                PluginSchemaClient::callback_schema(&client).await,
+            ) {
+                (Ok(call), Ok(callback)) => match (compile(&call), compile(&callback)) {
+                    (Ok(call), Ok(callback)) => {
+                        info!(%plugin_name, "loaded plugin schemas");
+
+                        Some(PluginSchemas { call, callback })
+                    }
+                    (Err(err), _) | (_, Err(err)) => {
+                        warn!(%plugin_name, "invalid plugin schema: {err}");
+                        None
+                    }
+                },
+                (Err(Error::Call(err)), _) if err.code() == METHOD_NOT_FOUND_CODE => {
+                    debug!(%plugin_name, "plugin does not advertise schemas");
+                    None
+                }
+                (Err(err), _) | (_, Err(err)) => {
+                    warn!(
+                        %plugin_name,
+                        error = %ErrorReporter(err),
+                        "error fetching plugin schemas"
+                    );
+                    None
+                }
+            };
+
+            plugins.insert(plugin_name.clone(), schemas);
+        }
+
+        Self { plugins }
+    }
+
+    /// Validates all of the plugin messages in `op`, returning a description of each error.
+    pub fn validate(&self, op: &Op<VoyagerMessage>) -> Vec<String> {
+        let mut errors = vec![];
+
+        self.validate_op(op, &mut errors);
+
+        errors
+    }
+
+    fn validate_op(&self, op: &Op<VoyagerMessage>, errors: &mut Vec<String>) {
+        match op {
+            Op::Call(Call::Plugin(message)) => {
+                self.validate_message(message, |schemas| &schemas.call, errors);
+            }
+            Op::Call(_) | Op::Data(_) | Op::Defer { .. } | Op::Noop => {}
+            Op::Seq(ops) | Op::Conc(ops) => {
+                ops.iter().for_each(|op| self.validate_op(op, errors));
+            }
+            Op::Promise(Promise {
+                queue,
+                data: _,
+                receiver,
+            }) => {
+                queue.iter().for_each(|op| self.validate_op(op, errors));
+
+                if let Callback::Plugin(message) = receiver {
+                    self.validate_message(message, |schemas| &schemas.callback, errors);
+                }
+            }
+            Op::Void(op) => self.validate_op(op, errors),
+        }
+    }
+
+    fn validate_message(
+        &self,
+        PluginMessage { plugin, message }: &PluginMessage,
+        validator: impl FnOnce(&PluginSchemas) -> &Validator,
+        errors: &mut Vec<String>,
+    ) {
+        match self.plugins.get(plugin) {
+            Some(Some(schemas)) => {
+                errors.extend(validator(schemas).iter_errors(message).map(|err| {
+                    format!(
+                        "invalid message for plugin {plugin}: {err} (at {})",
+                        err.instance_path
+                    )
+                }))
+            }
+            Some(None) => {}
+            None => errors.push(format!("plugin {plugin} is not loaded")),
+        }
+    }
+}
+
+fn compile(schema: &Value) -> Result<Validator, String> {
+    jsonschema::validator_for(schema).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use voyager_vm::{call, conc, promise, seq, void};
+
+    use super::*;
+
+    // The schemas advertised by the plugins are tested against real messages in the plugins
+    // themselves, since they are binaries and can't be depended on here.
+
+    const PLUGIN: &str = "plugin";
+
+    fn schemas() -> Schemas {
+        Schemas {
+            plugins: [
+                (
+                    PLUGIN.to_owned(),
+                    Some(PluginSchemas {
+                        call: compile(&json!({
+                            "type": "object",
+                            "properties": {
+                                "fetch": {
+                                    "type": "object",
+                                    "properties": { "height": { "type": "integer" } },
+                                    "required": ["height"]
+                                }
+                            },
+                            "required": ["fetch"]
+                        }))
+                        .unwrap(),
+                        callback: compile(&json!({
+                            "type": "object",
+                            "required": ["aggregate"]
+                        }))
+                        .unwrap(),
+                    }),
+                ),
+                ("no-schemas".to_owned(), None),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    fn plugin_call(plugin: &str, message: Value) -> Op<VoyagerMessage> {
+        call(Call::Plugin(PluginMessage::new(plugin, message)))
+    }
+
+    fn plugin_callback(plugin: &str, message: Value) -> Callback {
+        Callback::Plugin(PluginMessage::new(plugin, message))
+    }
+
+    #[test]
+    fn valid_nested() {
+        let op = seq([
+            plugin_call(PLUGIN, json!({ "fetch": { "height": 1 } })),
+            conc([
+                void(plugin_call(PLUGIN, json!({ "fetch": { "height": 2 } }))),
+                promise(
+                    [plugin_call(PLUGIN, json!({ "fetch": { "height": 3 } }))],
+                    [],
+                    plugin_callback(PLUGIN, json!({ "aggregate": {} })),
+                ),
+            ]),
+        ]);
+
+        assert_eq!(schemas().validate(&op), Vec::<String>::new());
+    }
+
+    #[test]
+    fn invalid_nested() {
+        let op = seq([
+            plugin_call(PLUGIN, json!({ "fetch": { "height": 1 } })),
+            conc([promise(
+                [seq([plugin_call(
+                    PLUGIN,
+                    json!({ "fetch": { "height": "2" } }),
+                )])],
+                [],
+                plugin_callback(PLUGIN, json!({ "aggregate": {} })),
+            )]),
+            void(plugin_call(PLUGIN, json!("fetch"))),
+        ]);
+
+        let errors = schemas().validate(&op);
+
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors[0].contains("(at /fetch/height)"), "{errors:?}");
+        assert!(
+            errors[1].contains("invalid message for plugin plugin"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn callback() {
+        // callbacks are validated against the callback schema, not the call schema
+        let op = promise(
+            [plugin_call(PLUGIN, json!({ "fetch": { "height": 1 } }))],
+            [],
+            plugin_callback(PLUGIN, json!({ "fetch": { "height": 1 } })),
+        );
+
+        let errors = schemas().validate(&op);
+
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(errors[0].contains("aggregate"), "{errors:?}");
+    }
+
+    #[test]
+    fn unknown_plugin() {
+        let op = seq([
+            plugin_call("unknown", json!({})),
+            promise([], [], plugin_callback("unknown", json!({}))),
+            // plugins that don't advertise schemas are not validated
+            plugin_call("no-schemas", json!("anything")),
+        ]);
+
+        assert_eq!(
+            schemas().validate(&op),
+            vec![
+                "plugin unknown is not loaded".to_owned(),
+                "plugin unknown is not loaded".to_owned(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock, RwLock},
+    time::Duration,
+};
+
+use concurrent_keyring::SignerBalance;
+use jsonrpsee::{core::client::Error, types::error::METHOD_NOT_FOUND_CODE};
+use prometheus::{register_gauge_vec, GaugeVec};
+use tracing::{debug, info_span, warn, Instrument};
+use unionlabs::ErrorReporter;
+use voyager_message::{context::Context, module::KeyringClient};
+
+/// The last collected signer balances, keyed by the name of the plugin that owns the keyring.
+pub type SignerBalances = Arc<RwLock<BTreeMap<String, Vec<SignerBalance<String>>>>>;
+
+static SIGNER_BALANCE: LazyLock<GaugeVec> = LazyLock::new(|| {
+    register_gauge_vec!(
+        "voyager_signer_balance",
+        "The balance of a signer in a plugin's keyring",
+        &["plugin", "key_name", "address", "denom"]
+    )
+    .expect("metric is only registered once; qed;")
+});
+
+/// Periodically collects the signer balances of all plugins that expose the keyring rpc methods. Querying the
+/// balances also updates the set of low balance keys in the plugin's keyring.
+pub async fn run(context: &Context, signer_balances: SignerBalances, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+
+    loop {
+        interval.tick().await;
+
+        // drop the balances of keys that were removed from a keyring or of plugins that failed to respond
+        SIGNER_BALANCE.reset();
+
+        for plugin_name in context.interest_filters().keys() {
+            let client = context.plugin(plugin_name).expect("plugin exists");
+
+            let balances = match KeyringClient::signer_balances(&client.with_id(None))
+                .instrument(info_span!("signer_balances", %plugin_name))
+                .await
+            {
+                Ok(balances) => balances,
+                // not all plugins sign transactions
+                Err(Error::Call(err)) if err.code() == METHOD_NOT_FOUND_CODE => continue,
+                Err(err) => {
+                    warn!(
+                        %plugin_name,
+                        error = %ErrorReporter(err),
+                        "error querying signer balances"
+                    );
+
+                    // the metrics of the plugin were reset, don't keep serving stale balances either
+                    signer_balances
+                        .write()
+                        .expect("lock is not poisoned")
+                        .remove(plugin_name);
+
+                    continue;
+                }
+            };
+
+            debug!(%plugin_name, ?balances, "collected signer balances");
+
+            for balance in &balances {
+                SIGNER_BALANCE
+                    .with_label_values(&[
+                        plugin_name,
+                        &balance.key_name,
+                        &balance.address,
+                        &balance.denom,
+                    ])
+                    .set(balance.balance as f64);
+            }
+
+            signer_balances
+                .write()
+                .expect("lock is not poisoned")
+                .insert(plugin_name.clone(), balances);
+        }
+    }
+}